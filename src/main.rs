@@ -1,3 +1,239 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use liyuu::syntax::{
+    parser::parser::Parser,
+    preprocessor::{output::make_rule, preprocessor::Preprocessor},
+    source::source_file::FileId,
+};
+
+const USAGE: &str = "usage: liyuu [-E] [-M | -MD] [-MF file] [-MT target] [-o file] \
+                     [-Dname[=value]] [-Uname] [-Idir] file.c";
+
+/// A `-D`, `-U` or `-I`, applied in command-line order.
+enum Setting {
+    Define(String),
+    Undefine(String),
+    IncludeDir(PathBuf),
+}
+
+#[derive(Default)]
+struct Options {
+    input: Option<PathBuf>,
+    output: Option<PathBuf>,
+    // -E
+    preprocess_only: bool,
+    // -M: print the dependency rule instead of the preprocessed text
+    deps_only: bool,
+    // -MD: also write the dependency rule to a file
+    deps_file: bool,
+    // -MF
+    deps_path: Option<PathBuf>,
+    // -MT
+    deps_target: Option<String>,
+    settings: Vec<Setting>,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // the value of an option, attached or in the next argument
+        let mut value = |flag: &str| -> Result<String, String> {
+            match &arg[flag.len()..] {
+                "" => args
+                    .next()
+                    .cloned()
+                    .ok_or_else(|| format!("missing argument to '{}'", flag)),
+                attached => Ok(attached.to_owned()),
+            }
+        };
+        match arg.as_str() {
+            "-E" => options.preprocess_only = true,
+            "-M" => options.deps_only = true,
+            "-MD" => options.deps_file = true,
+            "-h" | "--help" => return Err(USAGE.to_owned()),
+            _ if arg.starts_with("-MF") => options.deps_path = Some(value("-MF")?.into()),
+            _ if arg.starts_with("-MT") => options.deps_target = Some(value("-MT")?),
+            _ if arg.starts_with("-o") => options.output = Some(value("-o")?.into()),
+            _ if arg.starts_with("-D") => options.settings.push(Setting::Define(value("-D")?)),
+            _ if arg.starts_with("-U") => options.settings.push(Setting::Undefine(value("-U")?)),
+            _ if arg.starts_with("-I") => options
+                .settings
+                .push(Setting::IncludeDir(value("-I")?.into())),
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option '{}'", arg))
+            }
+            _ if options.input.is_some() => return Err(String::from("more than one input file")),
+            _ => options.input = Some(arg.into()),
+        }
+    }
+    if options.input.is_none() {
+        return Err(USAGE.to_owned());
+    }
+    Ok(options)
+}
+
+/// The object file a compile of `input` would produce, the default
+/// target of the dependency rule.
+fn object_file(options: &Options, input: &Path) -> String {
+    match &options.output {
+        Some(output) if !options.preprocess_only => output.to_string_lossy().into_owned(),
+        _ => {
+            let stem = input.file_stem().unwrap_or_default();
+            format!("{}.o", stem.to_string_lossy())
+        }
+    }
+}
+
+fn run(options: &Options) -> Result<bool, String> {
+    let input = options.input.as_deref().unwrap();
+    let mut pp = Preprocessor::new();
+    for setting in &options.settings {
+        match setting {
+            Setting::Define(definition) => pp.define(definition),
+            Setting::Undefine(name) => pp.undefine(name),
+            Setting::IncludeDir(dir) => pp.add_include_dir(dir.clone()),
+        }
+    }
+    let file = pp
+        .source_map_mut()
+        .load_file(input)
+        .map_err(|e| format!("{}: {}", input.display(), e))?;
+
+    let compile = !options.preprocess_only && !options.deps_only;
+    let (text, ok) = if compile {
+        (None, check(&mut pp, file))
+    } else {
+        let text = pp.preprocess_text(file);
+        report(&pp);
+        (Some(text), !pp.has_errors())
+    };
+
+    let target = match &options.deps_target {
+        Some(target) => target.clone(),
+        None => object_file(options, input),
+    };
+    let mut prerequisites = vec![input.to_str().unwrap_or_default()];
+    prerequisites.extend(pp.includes());
+    let rule = make_rule(&target, &prerequisites);
+
+    if let Some(text) = text {
+        let output = if options.deps_only { &rule } else { &text };
+        match &options.output {
+            Some(path) => {
+                fs::write(path, output).map_err(|e| format!("{}: {}", path.display(), e))?
+            }
+            None => print!("{}", output),
+        }
+    }
+    if options.deps_file {
+        let path = match (&options.deps_path, &options.output) {
+            (Some(path), _) => path.clone(),
+            (None, Some(output)) => output.with_extension("d"),
+            (None, None) => PathBuf::from(target).with_extension("d"),
+        };
+        fs::write(&path, &rule).map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    Ok(ok)
+}
+
+/// Preprocesses and parses `file`, reporting what is wrong with it; there
+/// is no code generation yet.
+fn check(pp: &mut Preprocessor, file: FileId) -> bool {
+    let tokens = pp.preprocess(file);
+    report(pp);
+    if pp.has_errors() {
+        return false;
+    }
+    match Parser::from_tokens(tokens).parse_unit() {
+        Ok(_) => true,
+        Err(diagnostics) => {
+            for diagnostic in diagnostics {
+                eprint!("{}", diagnostic.render(pp.source_map()));
+            }
+            false
+        }
+    }
+}
+
+fn report(pp: &Preprocessor) {
+    for diagnostic in pp.diagnostics() {
+        eprint!("{}", diagnostic.render(pp.source_map()));
+    }
+}
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let options = match parse_args(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    };
+    match run(&options) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("liyuu: error: {}", message);
+            process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::{parse_args, run};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    pub fn test_md_while_compiling() {
+        let dir = env::temp_dir().join(format!("liyuu-md-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("m.h"), "int f(int x);\n").unwrap();
+        fs::write(
+            dir.join("m.c"),
+            "#include \"m.h\"\nint main() { return f(1); }\n",
+        )
+        .unwrap();
+        let input = dir.join("m.c");
+        let output = dir.join("m.o");
+
+        let options = parse_args(&args(&[
+            "-MD",
+            "-o",
+            output.to_str().unwrap(),
+            input.to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(run(&options), Ok(true));
+        let rule = fs::read_to_string(dir.join("m.d")).unwrap();
+        assert!(rule.starts_with(&format!("{}:", output.display())));
+        assert!(rule.contains("m.h"));
+        assert!(!output.exists());
+
+        let deps = dir.join("deps.d");
+        let options = parse_args(&args(&[
+            "-MD",
+            "-MF",
+            deps.to_str().unwrap(),
+            "-MT",
+            "m.o",
+            input.to_str().unwrap(),
+        ]))
+        .unwrap();
+        assert_eq!(run(&options), Ok(true));
+        assert!(fs::read_to_string(&deps).unwrap().starts_with("m.o:"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,7 +1,21 @@
-use super::stmt::stmt::Stmt;
+use std::fmt;
 
+use crate::syntax::typing::tag::TagTable;
+
+use super::{printer::Printer, stmt::stmt::Stmt};
+
+#[derive(PartialEq, Debug)]
 pub struct CompilationUnit {
     pub stmts: Vec<Stmt>,
+    // the structs, unions and enums the types in `stmts` refer to
+    pub tags: TagTable,
+}
+
+/// The unit as C that parses back to it.
+impl fmt::Display for CompilationUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", Printer::new(&self.tags).print(&self.stmts))
+    }
 }
 
 /*
@@ -14,12 +28,12 @@ CompilationUnit
 \_  Stmt::Declare(Declare::DeclareFunction(
         String::from("main"),
         [
-            (String::from("argc"), Type::Int), 
+            (String::from("argc"), Type::Int),
             (String::from("argv"), Type::Pointer<Type::Pointer<Type::Char>>)
         ],
         Type::Int,
         [
-            Stmt::Return(Box::new(Expr::LiteralExpr(Literal::Int(0)))),
+            Stmt::Return(Some(Box::new(Expr::LiteralExpr(Literal::Int(0))))),
         ]
     ))
 */
@@ -0,0 +1,97 @@
+use super::expr::Expr;
+
+/// An assignment, whose value is the one stored. The left side is an
+/// lvalue, as `Expr::is_lvalue` tells.
+#[derive(PartialEq, Debug)]
+pub enum Assign {
+    /// a = b
+    Assign(Box<Expr>, Box<Expr>),
+    /// a += b
+    PlusAssign(Box<Expr>, Box<Expr>),
+    /// a -= b
+    MinusAssign(Box<Expr>, Box<Expr>),
+    /// a *= b
+    MulAssign(Box<Expr>, Box<Expr>),
+    /// a /= b
+    DivAssign(Box<Expr>, Box<Expr>),
+    /// a %= b
+    ModAssign(Box<Expr>, Box<Expr>),
+    /// a &= b
+    BitAndAssign(Box<Expr>, Box<Expr>),
+    /// a |= b
+    BitOrAssign(Box<Expr>, Box<Expr>),
+    /// a ^= b
+    BitXorAssign(Box<Expr>, Box<Expr>),
+    /// a <<= b
+    LShiftAssign(Box<Expr>, Box<Expr>),
+    /// a >>= b
+    RShiftAssign(Box<Expr>, Box<Expr>),
+}
+
+impl Assign {
+    /// The operator, as it is spelled.
+    pub fn op(&self) -> &'static str {
+        match self {
+            Assign::Assign(..) => "=",
+            Assign::PlusAssign(..) => "+=",
+            Assign::MinusAssign(..) => "-=",
+            Assign::MulAssign(..) => "*=",
+            Assign::DivAssign(..) => "/=",
+            Assign::ModAssign(..) => "%=",
+            Assign::BitAndAssign(..) => "&=",
+            Assign::BitOrAssign(..) => "|=",
+            Assign::BitXorAssign(..) => "^=",
+            Assign::LShiftAssign(..) => "<<=",
+            Assign::RShiftAssign(..) => ">>=",
+        }
+    }
+
+    /// The target and the value.
+    pub fn operands(&self) -> (&Expr, &Expr) {
+        match self {
+            Assign::Assign(target, value)
+            | Assign::PlusAssign(target, value)
+            | Assign::MinusAssign(target, value)
+            | Assign::MulAssign(target, value)
+            | Assign::DivAssign(target, value)
+            | Assign::ModAssign(target, value)
+            | Assign::BitAndAssign(target, value)
+            | Assign::BitOrAssign(target, value)
+            | Assign::BitXorAssign(target, value)
+            | Assign::LShiftAssign(target, value)
+            | Assign::RShiftAssign(target, value) => (target, value),
+        }
+    }
+
+    pub fn operands_mut(&mut self) -> (&mut Expr, &mut Expr) {
+        match self {
+            Assign::Assign(target, value)
+            | Assign::PlusAssign(target, value)
+            | Assign::MinusAssign(target, value)
+            | Assign::MulAssign(target, value)
+            | Assign::DivAssign(target, value)
+            | Assign::ModAssign(target, value)
+            | Assign::BitAndAssign(target, value)
+            | Assign::BitOrAssign(target, value)
+            | Assign::BitXorAssign(target, value)
+            | Assign::LShiftAssign(target, value)
+            | Assign::RShiftAssign(target, value) => (target, value),
+        }
+    }
+
+    pub fn into_operands(self) -> (Box<Expr>, Box<Expr>) {
+        match self {
+            Assign::Assign(target, value)
+            | Assign::PlusAssign(target, value)
+            | Assign::MinusAssign(target, value)
+            | Assign::MulAssign(target, value)
+            | Assign::DivAssign(target, value)
+            | Assign::ModAssign(target, value)
+            | Assign::BitAndAssign(target, value)
+            | Assign::BitOrAssign(target, value)
+            | Assign::BitXorAssign(target, value)
+            | Assign::LShiftAssign(target, value)
+            | Assign::RShiftAssign(target, value) => (target, value),
+        }
+    }
+}
@@ -44,4 +44,8 @@ pub enum Binary {
     Member(Box<Expr>, Box<Expr>),
     /// foo->bar
     PtrMember(Box<Expr>, Box<Expr>),
+    /// foo[bar]
+    Index(Box<Expr>, Box<Expr>),
+    /// a, b
+    Comma(Box<Expr>, Box<Expr>),
 }
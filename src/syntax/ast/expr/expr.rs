@@ -1,244 +1,343 @@
 use std::fmt;
 
+use crate::syntax::{
+    ast::stmt::initializer::Initializer,
+    tokenizer::attr::Attr,
+    typing::{
+        tag::{TagId, TagKind},
+        types::numbered_tag,
+    },
+};
+
 use super::{
-    binary_expr::Binary, group_expr::GroupExpr, literal_expr::Literal, trinary_expr::Trinary,
-    unary_expr::Unary,
+    assign_expr::Assign, binary_expr::Binary, group_expr::GroupExpr, literal_expr::Literal,
+    trinary_expr::Trinary, type_expr::TypeExpr, unary_expr::Unary,
 };
 
-#[derive(PartialEq, Debug)]
+/// An expression, with the attr of the tokens it was parsed from.
+/// Expressions compare equal whatever their attrs, so a parsed tree can
+/// be compared with a tree built by hand.
+#[derive(Debug)]
 pub enum Expr {
-    LiteralExpr(Literal),
-    UnaryExpr(Unary),
-    BinaryExpr(Binary),
-    TrinaryExpr(Trinary),
-    GroupExpr(GroupExpr),
+    LiteralExpr(Attr, Literal),
+    UnaryExpr(Attr, Unary),
+    BinaryExpr(Attr, Binary),
+    TrinaryExpr(Attr, Trinary),
+    GroupExpr(Attr, GroupExpr),
+    AssignExpr(Attr, Assign),
+    TypeExpr(Attr, TypeExpr),
 }
 
-impl fmt::Display for Expr {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+// how tightly the forms of expressions bind, from the loosest
+const COMMA: u8 = 0;
+const ASSIGN: u8 = 1;
+const CONDITIONAL: u8 = 2;
+// the binary operators written between their operands, each level
+// binding tighter than the one before and left-associative
+const INFIX: [&[&str]; 10] = [
+    &["||"],
+    &["&&"],
+    &["|"],
+    &["^"],
+    &["&"],
+    &["==", "!="],
+    &["<", ">", "<=", ">="],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+const CAST: u8 = 13;
+const UNARY: u8 = 14;
+const POSTFIX: u8 = 15;
+
+impl PartialEq for Expr {
+    fn eq(&self, other: &Expr) -> bool {
+        match (self, other) {
+            (Expr::LiteralExpr(_, a), Expr::LiteralExpr(_, b)) => a == b,
+            (Expr::UnaryExpr(_, a), Expr::UnaryExpr(_, b)) => a == b,
+            (Expr::BinaryExpr(_, a), Expr::BinaryExpr(_, b)) => a == b,
+            (Expr::TrinaryExpr(_, a), Expr::TrinaryExpr(_, b)) => a == b,
+            (Expr::GroupExpr(_, a), Expr::GroupExpr(_, b)) => a == b,
+            (Expr::AssignExpr(_, a), Expr::AssignExpr(_, b)) => a == b,
+            (Expr::TypeExpr(_, a), Expr::TypeExpr(_, b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Expr {
+    /// Where the expression is, from its first token to its last.
+    pub fn attr(&self) -> &Attr {
         match self {
-            Expr::LiteralExpr(Literal::Bool(b)) => {
-                write!(f, "{}", b)
-            }
-            Expr::LiteralExpr(Literal::Int(i)) => {
-                write!(f, "{}", i)
-            }
-            Expr::LiteralExpr(Literal::Char(c)) => {
-                write!(f, "'{}'", c)
-            }
-            Expr::LiteralExpr(Literal::String(s)) => {
-                write!(f, "\"{}\"", s)
-            }
-            Expr::LiteralExpr(Literal::ID(s)) => {
-                write!(f, "{}", s)
-            }
-            Expr::UnaryExpr(ue) => match ue {
-                Unary::Plus(e) => {
-                    let se = e.to_string();
-                    write!(f, "{}", se)
-                }
-                Unary::Minus(e) => {
-                    let se = e.to_string();
-                    write!(f, "-{}", se)
-                }
-                Unary::Bang(e) => {
-                    let se = e.to_string();
-                    write!(f, "!{}", se)
-                }
-                Unary::BitNot(e) => {
-                    let se = e.to_string();
-                    write!(f, "~{}", se)
-                }
+            Expr::LiteralExpr(attr, _)
+            | Expr::UnaryExpr(attr, _)
+            | Expr::BinaryExpr(attr, _)
+            | Expr::TrinaryExpr(attr, _)
+            | Expr::GroupExpr(attr, _)
+            | Expr::AssignExpr(attr, _)
+            | Expr::TypeExpr(attr, _) => attr,
+        }
+    }
+
+    /// Whether the expression designates an object that can be assigned:
+    /// a variable, a dereference, an element or a member.
+    pub fn is_lvalue(&self) -> bool {
+        match self {
+            Expr::LiteralExpr(_, Literal::ID(_))
+            | Expr::UnaryExpr(_, Unary::Deref(_))
+            | Expr::BinaryExpr(_, Binary::Index(..))
+            | Expr::BinaryExpr(_, Binary::Member(..))
+            | Expr::BinaryExpr(_, Binary::PtrMember(..))
+            | Expr::TypeExpr(_, TypeExpr::CompoundLiteral(..)) => true,
+            Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => e.is_lvalue(),
+            _ => false,
+        }
+    }
+
+    /// How tightly the expression binds, as in the C grammar: an operand
+    /// that binds looser than its operator allows is parenthesised when
+    /// printed. A comma expression binds loosest, at 0.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            // a negative number prints with its sign
+            Expr::LiteralExpr(_, Literal::Int(i)) if *i < 0 => UNARY,
+            Expr::LiteralExpr(_, Literal::Float(v)) if v.is_sign_negative() => UNARY,
+            Expr::LiteralExpr(_, _) | Expr::GroupExpr(_, _) => POSTFIX,
+            Expr::UnaryExpr(_, Unary::PostInc(_) | Unary::PostDec(_)) => POSTFIX,
+            Expr::UnaryExpr(_, _) => UNARY,
+            Expr::BinaryExpr(_, binary) => match infix(binary) {
+                Some((precedence, ..)) => precedence,
+                None => POSTFIX,
             },
-            Expr::BinaryExpr(be) => match be {
-                Binary::Plus(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} + {}", &se1, &se2)
-                }
-                Binary::Minus(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} - {}", &se1, &se2)
-                }
-                Binary::Mul(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} * {}", &se1, &se2)
-                }
-                Binary::Div(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} / {}", &se1, &se2)
-                }
-                Binary::Mod(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} % {}", &se1, &se2)
-                }
-                Binary::Eq(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} == {}", &se1, &se2)
-                }
-                Binary::Neq(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} != {}", &se1, &se2)
-                }
-                Binary::Gt(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} > {}", &se1, &se2)
-                }
-                Binary::Gte(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} >= {}", &se1, &se2)
-                }
-                Binary::Lt(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} < {}", &se1, &se2)
-                }
-                Binary::Lte(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} <= {}", &se1, &se2)
-                }
-                Binary::And(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} && {}", &se1, &se2)
-                }
-                Binary::Or(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} || {}", &se1, &se2)
-                }
-                Binary::BitAnd(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} & {}", &se1, &se2)
-                }
-                Binary::BitOr(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} | {}", &se1, &se2)
-                }
-                Binary::BitXor(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} ^ {}", &se1, &se2)
-                }
-                Binary::LShift(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} << {}", &se1, &se2)
-                }
-                Binary::RShift(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{} >> {}", &se1, &se2)
-                }
-                Binary::Call(e1, e2s) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2s
-                        .iter()
-                        .map(|expr| expr.to_string())
-                        .collect::<Vec<String>>()
-                        .join(", ");
-                    write!(f, "{}({})", &se1, &se2)
-                }
-                Binary::Member(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{}.{}", &se1, &se2)
-                }
-                Binary::PtrMember(e1, e2) => {
-                    let se1 = e1.to_string();
-                    let se2 = e2.to_string();
-                    write!(f, "{}->{}", &se1, &se2)
-                }
+            Expr::TrinaryExpr(_, _) => CONDITIONAL,
+            Expr::AssignExpr(_, _) => ASSIGN,
+            Expr::TypeExpr(_, TypeExpr::Cast(..)) => CAST,
+            Expr::TypeExpr(_, TypeExpr::CompoundLiteral(..)) => POSTFIX,
+            Expr::TypeExpr(_, _) => UNARY,
+        }
+    }
+}
+
+/// How tightly the binary operator `op` binds when written between its
+/// operands, from the `||` just above the conditional operator to the
+/// `*` just below casts. The parser climbs the same precedences.
+pub fn infix_precedence(op: &str) -> Option<u8> {
+    let level = INFIX.iter().position(|ops| ops.contains(&op))?;
+    Some(CONDITIONAL + 1 + level as u8)
+}
+
+/// The precedence, operator and operands of a binary operator written
+/// between its operands. Every one of them is left-associative.
+fn infix(binary: &Binary) -> Option<(u8, &'static str, &Expr, &Expr)> {
+    let (op, l, r) = match binary {
+        Binary::Comma(l, r) => return Some((COMMA, ",", l, r)),
+        Binary::Or(l, r) => ("||", l, r),
+        Binary::And(l, r) => ("&&", l, r),
+        Binary::BitOr(l, r) => ("|", l, r),
+        Binary::BitXor(l, r) => ("^", l, r),
+        Binary::BitAnd(l, r) => ("&", l, r),
+        Binary::Eq(l, r) => ("==", l, r),
+        Binary::Neq(l, r) => ("!=", l, r),
+        Binary::Lt(l, r) => ("<", l, r),
+        Binary::Gt(l, r) => (">", l, r),
+        Binary::Lte(l, r) => ("<=", l, r),
+        Binary::Gte(l, r) => (">=", l, r),
+        Binary::LShift(l, r) => ("<<", l, r),
+        Binary::RShift(l, r) => (">>", l, r),
+        Binary::Plus(l, r) => ("+", l, r),
+        Binary::Minus(l, r) => ("-", l, r),
+        Binary::Mul(l, r) => ("*", l, r),
+        Binary::Div(l, r) => ("/", l, r),
+        Binary::Mod(l, r) => ("%", l, r),
+        Binary::Call(..) | Binary::Member(..) | Binary::PtrMember(..) | Binary::Index(..) => {
+            return None
+        }
+    };
+    Some((infix_precedence(op)?, op, l, r))
+}
+
+/// The spelling of a tag in a type, given to `Type::declaration`.
+type TagSpelling<'a> = &'a dyn Fn(TagKind, TagId) -> String;
+
+/// `expr` as an operand that binds at least as tightly as `min`.
+fn operand(expr: &Expr, min: u8, tag: TagSpelling) -> String {
+    if expr.precedence() < min {
+        format!("({})", expr.to_c(tag))
+    } else {
+        expr.to_c(tag)
+    }
+}
+
+/// `op` before its operand, apart from it where they would make
+/// another token: `- -a` is not `--a`.
+fn prefix(op: &str, expr: &Expr, min: u8, tag: TagSpelling) -> String {
+    let operand = operand(expr, min, tag);
+    match (op.chars().last(), operand.chars().next()) {
+        (Some(l), Some(r)) if l == r && "+-&".contains(l) => format!("{} {}", op, operand),
+        _ => format!("{}{}", op, operand),
+    }
+}
+
+impl Expr {
+    /// The expression as C, with the tags in its types spelled by `tag`.
+    /// Operands are parenthesised where their precedence needs it.
+    pub fn to_c(&self, tag: TagSpelling) -> String {
+        match self {
+            Expr::LiteralExpr(_, literal) => literal.to_string(),
+            Expr::UnaryExpr(_, ue) => match ue {
+                Unary::Plus(e) => prefix("+", e, CAST, tag),
+                Unary::Minus(e) => prefix("-", e, CAST, tag),
+                Unary::Bang(e) => prefix("!", e, CAST, tag),
+                Unary::BitNot(e) => prefix("~", e, CAST, tag),
+                Unary::Deref(e) => prefix("*", e, CAST, tag),
+                Unary::AddressOf(e) => prefix("&", e, CAST, tag),
+                Unary::PreInc(e) => prefix("++", e, UNARY, tag),
+                Unary::PreDec(e) => prefix("--", e, UNARY, tag),
+                Unary::Sizeof(e) => format!("sizeof {}", operand(e, UNARY, tag)),
+                Unary::PostInc(e) => format!("{}++", operand(e, POSTFIX, tag)),
+                Unary::PostDec(e) => format!("{}--", operand(e, POSTFIX, tag)),
             },
-            Expr::TrinaryExpr(te) => match te {
-                Trinary::TrinaryExpr(cond, e1, e2) => {
-                    write!(
-                        f,
-                        "{} ? {} : {}",
-                        cond.to_string(),
-                        e1.to_string(),
-                        e2.to_string()
-                    )
+            Expr::BinaryExpr(_, be) => {
+                if let Some((precedence, op, l, r)) = infix(be) {
+                    let l = operand(l, precedence, tag);
+                    let r = operand(r, precedence + 1, tag);
+                    return match be {
+                        Binary::Comma(..) => format!("{}, {}", l, r),
+                        _ => format!("{} {} {}", l, op, r),
+                    };
                 }
-            },
-            Expr::GroupExpr(ge) => match ge {
-                GroupExpr::GroupExpr(e) => {
-                    write!(f, "({})", e.to_string())
+                match be {
+                    Binary::Call(e1, e2s) => {
+                        let se2 = e2s
+                            .iter()
+                            .map(|expr| operand(expr, ASSIGN, tag))
+                            .collect::<Vec<String>>()
+                            .join(", ");
+                        format!("{}({})", operand(e1, POSTFIX, tag), se2)
+                    }
+                    Binary::Member(e1, e2) => {
+                        format!("{}.{}", operand(e1, POSTFIX, tag), e2.to_c(tag))
+                    }
+                    Binary::PtrMember(e1, e2) => {
+                        format!("{}->{}", operand(e1, POSTFIX, tag), e2.to_c(tag))
+                    }
+                    Binary::Index(e1, e2) => {
+                        format!("{}[{}]", operand(e1, POSTFIX, tag), e2.to_c(tag))
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            Expr::TrinaryExpr(_, Trinary::TrinaryExpr(cond, e1, e2)) => format!(
+                "{} ? {} : {}",
+                operand(cond, CONDITIONAL + 1, tag),
+                e1.to_c(tag),
+                operand(e2, CONDITIONAL, tag)
+            ),
+            Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => format!("({})", e.to_c(tag)),
+            Expr::AssignExpr(_, assign) => {
+                let (target, value) = assign.operands();
+                format!(
+                    "{} {} {}",
+                    operand(target, UNARY, tag),
+                    assign.op(),
+                    operand(value, ASSIGN, tag)
+                )
+            }
+            Expr::TypeExpr(_, te) => match te {
+                TypeExpr::Cast(typ, e) => {
+                    format!("({}){}", typ.declaration("", tag), operand(e, CAST, tag))
                 }
+                TypeExpr::Sizeof(typ) => format!("sizeof({})", typ.declaration("", tag)),
+                TypeExpr::Alignof(typ) => format!("_Alignof({})", typ.declaration("", tag)),
+                TypeExpr::CompoundLiteral(typ, init) => match init {
+                    Initializer::List(_, _) => {
+                        format!("({}){}", typ.declaration("", tag), init.to_c(tag))
+                    }
+                    // a scalar has its braces back
+                    Initializer::Expr(e) => format!(
+                        "({}){{ {} }}",
+                        typ.declaration("", tag),
+                        operand(e, ASSIGN, tag)
+                    ),
+                },
             },
         }
     }
 }
 
+/// The expression as C, with tags by their ids.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_c(&numbered_tag))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::syntax::ast::expr::{
-        binary_expr::Binary, literal_expr::Literal, trinary_expr::Trinary, unary_expr::Unary,
+    use crate::syntax::tokenizer::attr::Attr;
+    use crate::syntax::{
+        ast::{
+            expr::{
+                assign_expr::Assign, binary_expr::Binary, literal_expr::Literal,
+                trinary_expr::Trinary, type_expr::TypeExpr, unary_expr::Unary,
+            },
+            stmt::initializer::Initializer,
+        },
+        typing::types::Type,
     };
 
     use super::Expr;
 
     #[test]
     pub fn test_bool() {
-        let e = Expr::LiteralExpr(Literal::Bool(false));
+        let e = Expr::LiteralExpr(Attr::new(), Literal::Bool(false));
         assert_eq!(&e.to_string(), "false");
     }
 
     #[test]
     pub fn test_int_literal_expr() {
-        let e = Expr::LiteralExpr(Literal::Int(3));
+        let e = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
         assert_eq!(&e.to_string(), "3");
     }
 
     #[test]
     pub fn test_char() {
-        let e = Expr::LiteralExpr(Literal::Char('a'));
+        let e = Expr::LiteralExpr(Attr::new(), Literal::Char('a'));
         assert_eq!(&e.to_string(), "'a'");
     }
 
     #[test]
     pub fn test_string() {
-        let e = Expr::LiteralExpr(Literal::String(String::from("abc")));
+        let e = Expr::LiteralExpr(Attr::new(), Literal::String(String::from("abc")));
         assert_eq!(&e.to_string(), "\"abc\"");
     }
 
     #[test]
     pub fn test_identifier() {
-        let e = Expr::LiteralExpr(Literal::ID(String::from("abc")));
+        let e = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("abc")));
         assert_eq!(&e.to_string(), "abc");
     }
 
     #[test]
     pub fn test_unary_expr() {
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(3));
-            let e = Expr::UnaryExpr(Unary::Plus(Box::new(e1)));
-            assert_eq!(&e.to_string(), "3");
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
+            let e = Expr::UnaryExpr(Attr::new(), Unary::Plus(Box::new(e1)));
+            assert_eq!(&e.to_string(), "+3");
         }
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(3));
-            let e = Expr::UnaryExpr(Unary::Minus(Box::new(e1)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
+            let e = Expr::UnaryExpr(Attr::new(), Unary::Minus(Box::new(e1)));
             assert_eq!(&e.to_string(), "-3");
         }
         {
-            let e1 = Expr::LiteralExpr(Literal::Bool(false));
-            let e = Expr::UnaryExpr(Unary::Bang(Box::new(e1)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Bool(false));
+            let e = Expr::UnaryExpr(Attr::new(), Unary::Bang(Box::new(e1)));
             assert_eq!(&e.to_string(), "!false");
         }
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(3));
-            let e = Expr::UnaryExpr(Unary::BitNot(Box::new(e1)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
+            let e = Expr::UnaryExpr(Attr::new(), Unary::BitNot(Box::new(e1)));
             assert_eq!(&e.to_string(), "~3");
         }
     }
@@ -246,185 +345,308 @@ mod tests {
     #[test]
     pub fn test_binary_expr() {
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Plus(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Plus(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 + 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Minus(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Minus(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 - 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Mul(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Mul(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 * 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Div(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Div(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 / 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Mod(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Mod(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 % 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Eq(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Eq(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 == 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Neq(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Neq(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 != 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Gt(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Gt(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 > 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Gte(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Gte(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 >= 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Lt(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Lt(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 < 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Lte(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Lte(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 <= 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::And(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::And(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 && 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::Or(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Or(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 || 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::BitAnd(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::BitAnd(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 & 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::BitOr(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::BitOr(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 | 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::BitXor(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::BitXor(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 ^ 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::LShift(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::LShift(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 << 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::Int(1));
-            let e2 = Expr::LiteralExpr(Literal::Int(2));
-            let e = Expr::BinaryExpr(Binary::RShift(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::RShift(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "1 >> 2");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::ID(String::from("sum")));
-            let e = Expr::BinaryExpr(Binary::Call(
-                Box::new(e1),
-                vec![
-                    Expr::LiteralExpr(Literal::Int(1)),
-                    Expr::LiteralExpr(Literal::ID(String::from("a"))),
-                    Expr::LiteralExpr(Literal::Int(3)),
-                ],
-            ));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("sum")));
+            let e = Expr::BinaryExpr(
+                Attr::new(),
+                Binary::Call(
+                    Box::new(e1),
+                    vec![
+                        Expr::LiteralExpr(Attr::new(), Literal::Int(1)),
+                        Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("a"))),
+                        Expr::LiteralExpr(Attr::new(), Literal::Int(3)),
+                    ],
+                ),
+            );
             assert_eq!(&e.to_string(), "sum(1, a, 3)");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::ID(String::from("sum")));
-            let e = Expr::BinaryExpr(Binary::Call(
-                Box::new(e1),
-                vec![
-                    Expr::LiteralExpr(Literal::Int(1)),
-                    Expr::LiteralExpr(Literal::String(String::from("a"))),
-                    Expr::LiteralExpr(Literal::Int(3)),
-                ],
-            ));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("sum")));
+            let e = Expr::BinaryExpr(
+                Attr::new(),
+                Binary::Call(
+                    Box::new(e1),
+                    vec![
+                        Expr::LiteralExpr(Attr::new(), Literal::Int(1)),
+                        Expr::LiteralExpr(Attr::new(), Literal::String(String::from("a"))),
+                        Expr::LiteralExpr(Attr::new(), Literal::Int(3)),
+                    ],
+                ),
+            );
             assert_eq!(&e.to_string(), "sum(1, \"a\", 3)");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::ID(String::from("user")));
-            let e2 = Expr::LiteralExpr(Literal::ID(String::from("name")));
-            let e = Expr::BinaryExpr(Binary::Member(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("user")));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("name")));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Member(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "user.name");
         }
 
         {
-            let e1 = Expr::LiteralExpr(Literal::ID(String::from("user")));
-            let e2 = Expr::LiteralExpr(Literal::ID(String::from("name")));
-            let e = Expr::BinaryExpr(Binary::PtrMember(Box::new(e1), Box::new(e2)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("user")));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::ID(String::from("name")));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::PtrMember(Box::new(e1), Box::new(e2)));
             assert_eq!(&e.to_string(), "user->name");
         }
     }
 
     #[test]
     pub fn test_trinary_expr() {
-        let cond = Expr::BinaryExpr(Binary::Gt(
-            Box::new(Expr::LiteralExpr(Literal::Int(1))),
-            Box::new(Expr::LiteralExpr(Literal::Int(5))),
-        ));
-        let e1 = Expr::LiteralExpr(Literal::Int(1));
-        let e2 = Expr::LiteralExpr(Literal::Int(5));
-        let e = Expr::TrinaryExpr(Trinary::TrinaryExpr(
-            Box::new(cond),
-            Box::new(e1),
-            Box::new(e2),
-        ));
+        let cond = Expr::BinaryExpr(
+            Attr::new(),
+            Binary::Gt(
+                Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(1))),
+                Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(5))),
+            ),
+        );
+        let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(1));
+        let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(5));
+        let e = Expr::TrinaryExpr(
+            Attr::new(),
+            Trinary::TrinaryExpr(Box::new(cond), Box::new(e1), Box::new(e2)),
+        );
         assert_eq!(&e.to_string(), "1 > 5 ? 1 : 5");
     }
+
+    #[test]
+    pub fn test_assign_expr() {
+        let id = |name: &str| {
+            Box::new(Expr::LiteralExpr(
+                Attr::new(),
+                Literal::ID(String::from(name)),
+            ))
+        };
+        let index = Expr::BinaryExpr(Attr::new(), Binary::Index(id("a"), id("i")));
+        assert!(index.is_lvalue());
+        let e = Expr::AssignExpr(
+            Attr::new(),
+            Assign::Assign(
+                Box::new(index),
+                Box::new(Expr::AssignExpr(
+                    Attr::new(),
+                    Assign::Assign(
+                        id("b"),
+                        Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(0))),
+                    ),
+                )),
+            ),
+        );
+        assert_eq!(&e.to_string(), "a[i] = b = 0");
+        assert!(!e.is_lvalue());
+
+        let e = Expr::AssignExpr(Attr::new(), Assign::LShiftAssign(id("x"), id("n")));
+        assert_eq!(&e.to_string(), "x <<= n");
+        let e = Expr::UnaryExpr(
+            Attr::new(),
+            Unary::PreDec(Box::new(Expr::UnaryExpr(
+                Attr::new(),
+                Unary::Deref(id("p")),
+            ))),
+        );
+        assert_eq!(&e.to_string(), "--*p");
+        let e = Expr::UnaryExpr(Attr::new(), Unary::PostInc(id("i")));
+        assert_eq!(&e.to_string(), "i++");
+    }
+
+    #[test]
+    pub fn test_parenthesisation() {
+        let id = |name: &str| {
+            Box::new(Expr::LiteralExpr(
+                Attr::new(),
+                Literal::ID(String::from(name)),
+            ))
+        };
+        let int = |i| Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(i)));
+        let binary = |op: fn(Box<Expr>, Box<Expr>) -> Binary, l, r| {
+            Box::new(Expr::BinaryExpr(Attr::new(), op(l, r)))
+        };
+        let unary = |op: fn(Box<Expr>) -> Unary, e| Box::new(Expr::UnaryExpr(Attr::new(), op(e)));
+
+        let sum = binary(Binary::Plus, id("a"), id("b"));
+        assert_eq!(binary(Binary::Mul, sum, id("c")).to_string(), "(a + b) * c");
+        let diff = binary(Binary::Minus, id("b"), id("c"));
+        assert_eq!(
+            binary(Binary::Minus, id("a"), diff).to_string(),
+            "a - (b - c)"
+        );
+        let diff = binary(Binary::Minus, id("a"), id("b"));
+        assert_eq!(
+            binary(Binary::Minus, diff, id("c")).to_string(),
+            "a - b - c"
+        );
+
+        let deref = unary(Unary::Deref, id("p"));
+        assert_eq!(unary(Unary::PostInc, deref).to_string(), "(*p)++");
+        let inc = unary(Unary::PostInc, id("p"));
+        assert_eq!(unary(Unary::Deref, inc).to_string(), "*p++");
+        let neg = unary(Unary::Minus, id("a"));
+        assert_eq!(unary(Unary::Minus, neg).to_string(), "- -a");
+        assert_eq!(unary(Unary::Minus, int(-1)).to_string(), "- -1");
+        let index = binary(Binary::Index, int(-1), id("a"));
+        assert_eq!(index.to_string(), "(-1)[a]");
+        let address = unary(Unary::AddressOf, id("a"));
+        assert_eq!(unary(Unary::AddressOf, address).to_string(), "& &a");
+        let sum = binary(Binary::Plus, id("a"), id("b"));
+        assert_eq!(unary(Unary::Sizeof, sum).to_string(), "sizeof (a + b)");
+
+        let sum = binary(Binary::Plus, id("a"), id("b"));
+        let cast = Expr::TypeExpr(
+            Attr::new(),
+            TypeExpr::Cast(Type::Pointer(Box::new(Type::Int)), sum),
+        );
+        assert_eq!(cast.to_string(), "(int *)(a + b)");
+        let typ = Type::Array(Box::new(Type::Char), None);
+        assert_eq!(
+            Expr::TypeExpr(Attr::new(), TypeExpr::Sizeof(typ)).to_string(),
+            "sizeof(char [])"
+        );
+        let literal = Expr::TypeExpr(
+            Attr::new(),
+            TypeExpr::CompoundLiteral(
+                Type::Int,
+                Initializer::Expr(binary(Binary::Comma, id("a"), id("b"))),
+            ),
+        );
+        assert_eq!(literal.to_string(), "(int){ (a, b) }");
+
+        let comma = binary(Binary::Comma, id("b"), id("c"));
+        let assign = Expr::AssignExpr(Attr::new(), Assign::Assign(id("a"), comma));
+        assert_eq!(assign.to_string(), "a = (b, c)");
+        let comma = binary(Binary::Comma, id("a"), id("b"));
+        let call = Expr::BinaryExpr(Attr::new(), Binary::Call(id("f"), vec![*comma, *id("c")]));
+        assert_eq!(call.to_string(), "f((a, b), c)");
+        let assign = Box::new(Expr::AssignExpr(
+            Attr::new(),
+            Assign::Assign(id("a"), id("b")),
+        ));
+        let cond = Expr::TrinaryExpr(Attr::new(), Trinary::TrinaryExpr(assign, id("c"), id("d")));
+        assert_eq!(cond.to_string(), "(a = b) ? c : d");
+    }
 }
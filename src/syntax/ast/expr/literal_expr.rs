@@ -1,8 +1,83 @@
+use std::fmt;
+
 #[derive(PartialEq, Debug)]
 pub enum Literal {
     Bool(bool),
     Int(i64),
+    // an unsigned constant, which keeps its `u` suffix
+    UInt(u64),
+    Float(f64),
+    // a float and a long double constant, which keep their `f` and `L`
+    // suffixes
+    Float32(f32),
+    LongDouble(f64),
     Char(char),
     String(String),
     ID(String),
 }
+
+/// The chars between the quotes of a char constant or string literal
+/// that `quote` ends, with escapes for what can't be written as it is.
+fn escape(s: &str, quote: char) -> String {
+    let mut escaped = String::new();
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            '\r' => escaped.push_str("\\r"),
+            c if c == quote => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            // an octal escape is never longer than 3 digits, so a digit
+            // after it stays apart. A char of a plain literal up to 0xff
+            // is the byte an escape gave, and one past it the code point
+            // of a universal character name.
+            c if (c.is_control() || !c.is_ascii()) && (c as u32) < 0o400 => {
+                escaped.push_str(&format!("\\{:03o}", c as u32))
+            }
+            c if (c as u32) > 0xffff => escaped.push_str(&format!("\\U{:08x}", c as u32)),
+            c if !c.is_ascii() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl fmt::Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Literal::Bool(b) => write!(f, "{}", b),
+            Literal::Int(i) => write!(f, "{}", i),
+            Literal::UInt(u) => write!(f, "{}u", u),
+            Literal::Float(v) => write!(f, "{:?}", v),
+            Literal::Float32(v) => write!(f, "{:?}f", v),
+            Literal::LongDouble(v) => write!(f, "{:?}L", v),
+            Literal::Char(c) => write!(f, "'{}'", escape(&c.to_string(), '\'')),
+            Literal::String(s) => write!(f, "\"{}\"", escape(s, '"')),
+            Literal::ID(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Literal;
+
+    #[test]
+    pub fn test_escape() {
+        let s = Literal::String(String::from("a\"b\\\n\u{0}1\u{7f}é\u{4e2d}\u{1f600}"));
+        assert_eq!(
+            s.to_string(),
+            "\"a\\\"b\\\\\\n\\0001\\177\\351\\u4e2d\\U0001f600\""
+        );
+        assert_eq!(Literal::Char('\u{ff}').to_string(), "'\\377'");
+        assert_eq!(Literal::Char('\'').to_string(), "'\\''");
+        assert_eq!(Literal::Char('"').to_string(), "'\"'");
+        assert_eq!(Literal::Float(2.0).to_string(), "2.0");
+        assert_eq!(Literal::Float32(0.1).to_string(), "0.1f");
+        assert_eq!(Literal::LongDouble(1e300).to_string(), "1e300L");
+        assert_eq!(Literal::UInt(u64::MAX).to_string(), "18446744073709551615u");
+    }
+}
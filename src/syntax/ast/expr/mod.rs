@@ -1,6 +1,8 @@
+pub mod assign_expr;
 pub mod binary_expr;
 pub mod expr;
 pub mod group_expr;
 pub mod trinary_expr;
+pub mod type_expr;
 pub mod unary_expr;
 pub  mod literal_expr;
\ No newline at end of file
@@ -0,0 +1,16 @@
+use crate::syntax::{ast::stmt::initializer::Initializer, typing::types::Type};
+
+use super::expr::Expr;
+
+/// The expressions with a type name in them.
+#[derive(PartialEq, Debug)]
+pub enum TypeExpr {
+    /// (long)a
+    Cast(Type, Box<Expr>),
+    /// sizeof(int)
+    Sizeof(Type),
+    /// _Alignof(int)
+    Alignof(Type),
+    /// (struct P){1, 2}
+    CompoundLiteral(Type, Initializer),
+}
@@ -10,4 +10,18 @@ pub enum Unary {
     Bang(Box<Expr>),
     /// ~a
     BitNot(Box<Expr>),
+    /// *p
+    Deref(Box<Expr>),
+    /// &a
+    AddressOf(Box<Expr>),
+    /// sizeof a
+    Sizeof(Box<Expr>),
+    /// ++a
+    PreInc(Box<Expr>),
+    /// --a
+    PreDec(Box<Expr>),
+    /// a++
+    PostInc(Box<Expr>),
+    /// a--
+    PostDec(Box<Expr>),
 }
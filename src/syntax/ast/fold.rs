@@ -0,0 +1,298 @@
+use super::{
+    ast_module::CompilationUnit,
+    expr::{
+        assign_expr::Assign, binary_expr::Binary, expr::Expr, group_expr::GroupExpr,
+        trinary_expr::Trinary, type_expr::TypeExpr, unary_expr::Unary,
+    },
+    stmt::{declare_stmt::Declare, initializer::Initializer, stmt::Stmt},
+};
+
+/// Rebuilds a tree it takes by value, like `Visitor` but returning the
+/// new node, for passes that replace nodes with other kinds of nodes.
+/// The default methods fold the children and keep the node as it is.
+pub trait Fold {
+    fn fold_unit(&mut self, unit: CompilationUnit) -> CompilationUnit {
+        walk_unit(self, unit)
+    }
+
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        walk_stmt(self, stmt)
+    }
+
+    fn fold_declare(&mut self, declare: Declare) -> Declare {
+        walk_declare(self, declare)
+    }
+
+    fn fold_initializer(&mut self, init: Initializer) -> Initializer {
+        walk_initializer(self, init)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        walk_expr(self, expr)
+    }
+}
+
+pub fn walk_unit<F: Fold + ?Sized>(folder: &mut F, unit: CompilationUnit) -> CompilationUnit {
+    CompilationUnit {
+        stmts: fold_stmts(folder, unit.stmts),
+        tags: unit.tags,
+    }
+}
+
+pub fn walk_stmt<F: Fold + ?Sized>(folder: &mut F, stmt: Stmt) -> Stmt {
+    match stmt {
+        Stmt::Expr(attr, expr) => Stmt::Expr(attr, fold_box(folder, *expr)),
+        Stmt::Return(attr, expr) => Stmt::Return(attr, expr.map(|e| fold_box(folder, *e))),
+        Stmt::Block(attr, stmts) => Stmt::Block(attr, fold_stmts(folder, stmts)),
+        Stmt::Declare(declare) => Stmt::Declare(folder.fold_declare(declare)),
+        Stmt::If(attr, cond, then, otherwise) => Stmt::If(
+            attr,
+            fold_box(folder, *cond),
+            fold_stmt_box(folder, *then),
+            otherwise.map(|stmt| fold_stmt_box(folder, *stmt)),
+        ),
+        Stmt::While(attr, cond, body) => {
+            Stmt::While(attr, fold_box(folder, *cond), fold_stmt_box(folder, *body))
+        }
+        Stmt::DoWhile(attr, body, cond) => {
+            Stmt::DoWhile(attr, fold_stmt_box(folder, *body), fold_box(folder, *cond))
+        }
+        Stmt::For(attr, init, cond, step, body) => Stmt::For(
+            attr,
+            fold_stmts(folder, init),
+            cond.map(|e| fold_box(folder, *e)),
+            step.map(|e| fold_box(folder, *e)),
+            fold_stmt_box(folder, *body),
+        ),
+        Stmt::Switch(attr, cond, body) => {
+            Stmt::Switch(attr, fold_box(folder, *cond), fold_stmt_box(folder, *body))
+        }
+        Stmt::Case(attr, value, stmt) => {
+            Stmt::Case(attr, fold_box(folder, *value), fold_stmt_box(folder, *stmt))
+        }
+        Stmt::Default(attr, stmt) => Stmt::Default(attr, fold_stmt_box(folder, *stmt)),
+        Stmt::Label(attr, name, stmt) => Stmt::Label(attr, name, fold_stmt_box(folder, *stmt)),
+        stmt @ (Stmt::Directive(..)
+        | Stmt::Break(_)
+        | Stmt::Continue(_)
+        | Stmt::Goto(..)
+        | Stmt::Empty(_)
+        | Stmt::Error(_)) => stmt,
+    }
+}
+
+pub fn walk_declare<F: Fold + ?Sized>(folder: &mut F, declare: Declare) -> Declare {
+    match declare {
+        Declare::DeclareIdentifier(attr, name, typ, init) => Declare::DeclareIdentifier(
+            attr,
+            name,
+            typ,
+            init.map(|init| folder.fold_initializer(init)),
+        ),
+        Declare::DeclareFunction {
+            attr,
+            name,
+            parameters,
+            prototype,
+            return_type,
+            body,
+        } => Declare::DeclareFunction {
+            attr,
+            name,
+            parameters,
+            prototype,
+            return_type,
+            body: fold_stmts(folder, body),
+        },
+        declare @ (Declare::DeclareStruct(..)
+        | Declare::DeclareUnion(..)
+        | Declare::DeclareEnum(..)
+        | Declare::DeclareTypedef(..)) => declare,
+    }
+}
+
+pub fn walk_initializer<F: Fold + ?Sized>(folder: &mut F, init: Initializer) -> Initializer {
+    match init {
+        Initializer::Expr(expr) => Initializer::Expr(fold_box(folder, *expr)),
+        Initializer::List(attr, items) => Initializer::List(
+            attr,
+            items
+                .into_iter()
+                .map(|(designators, init)| (designators, folder.fold_initializer(init)))
+                .collect(),
+        ),
+    }
+}
+
+/// Like `visit::walk_expr`, leaves the name after `.` or `->` alone.
+pub fn walk_expr<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Expr {
+    match expr {
+        expr @ Expr::LiteralExpr(..) => expr,
+        Expr::UnaryExpr(attr, unary) => Expr::UnaryExpr(attr, fold_unary(folder, unary)),
+        Expr::BinaryExpr(attr, binary) => Expr::BinaryExpr(attr, fold_binary(folder, binary)),
+        Expr::TrinaryExpr(attr, Trinary::TrinaryExpr(cond, then, otherwise)) => Expr::TrinaryExpr(
+            attr,
+            Trinary::TrinaryExpr(
+                fold_box(folder, *cond),
+                fold_box(folder, *then),
+                fold_box(folder, *otherwise),
+            ),
+        ),
+        Expr::GroupExpr(attr, GroupExpr::GroupExpr(e)) => {
+            Expr::GroupExpr(attr, GroupExpr::GroupExpr(fold_box(folder, *e)))
+        }
+        Expr::AssignExpr(attr, assign) => Expr::AssignExpr(attr, fold_assign(folder, assign)),
+        Expr::TypeExpr(attr, type_expr) => {
+            let type_expr = match type_expr {
+                TypeExpr::Cast(typ, e) => TypeExpr::Cast(typ, fold_box(folder, *e)),
+                TypeExpr::CompoundLiteral(typ, init) => {
+                    TypeExpr::CompoundLiteral(typ, folder.fold_initializer(init))
+                }
+                type_expr @ (TypeExpr::Sizeof(_) | TypeExpr::Alignof(_)) => type_expr,
+            };
+            Expr::TypeExpr(attr, type_expr)
+        }
+    }
+}
+
+fn fold_stmts<F: Fold + ?Sized>(folder: &mut F, stmts: Vec<Stmt>) -> Vec<Stmt> {
+    stmts
+        .into_iter()
+        .map(|stmt| folder.fold_stmt(stmt))
+        .collect()
+}
+
+fn fold_stmt_box<F: Fold + ?Sized>(folder: &mut F, stmt: Stmt) -> Box<Stmt> {
+    Box::new(folder.fold_stmt(stmt))
+}
+
+fn fold_box<F: Fold + ?Sized>(folder: &mut F, expr: Expr) -> Box<Expr> {
+    Box::new(folder.fold_expr(expr))
+}
+
+fn fold_unary<F: Fold + ?Sized>(folder: &mut F, unary: Unary) -> Unary {
+    let (op, e): (fn(Box<Expr>) -> Unary, _) = match unary {
+        Unary::Plus(e) => (Unary::Plus, e),
+        Unary::Minus(e) => (Unary::Minus, e),
+        Unary::Bang(e) => (Unary::Bang, e),
+        Unary::BitNot(e) => (Unary::BitNot, e),
+        Unary::Deref(e) => (Unary::Deref, e),
+        Unary::AddressOf(e) => (Unary::AddressOf, e),
+        Unary::Sizeof(e) => (Unary::Sizeof, e),
+        Unary::PreInc(e) => (Unary::PreInc, e),
+        Unary::PreDec(e) => (Unary::PreDec, e),
+        Unary::PostInc(e) => (Unary::PostInc, e),
+        Unary::PostDec(e) => (Unary::PostDec, e),
+    };
+    op(fold_box(folder, *e))
+}
+
+// a variant of `Binary` with two operands
+type BinaryOp = fn(Box<Expr>, Box<Expr>) -> Binary;
+
+fn fold_binary<F: Fold + ?Sized>(folder: &mut F, binary: Binary) -> Binary {
+    let (op, l, r): (BinaryOp, _, _) = match binary {
+        Binary::Call(callee, args) => {
+            let callee = fold_box(folder, *callee);
+            let args = args.into_iter().map(|arg| folder.fold_expr(arg)).collect();
+            return Binary::Call(callee, args);
+        }
+        Binary::Member(e, member) => return Binary::Member(fold_box(folder, *e), member),
+        Binary::PtrMember(e, member) => return Binary::PtrMember(fold_box(folder, *e), member),
+        Binary::Plus(l, r) => (Binary::Plus, l, r),
+        Binary::Minus(l, r) => (Binary::Minus, l, r),
+        Binary::Mul(l, r) => (Binary::Mul, l, r),
+        Binary::Div(l, r) => (Binary::Div, l, r),
+        Binary::Mod(l, r) => (Binary::Mod, l, r),
+        Binary::Eq(l, r) => (Binary::Eq, l, r),
+        Binary::Neq(l, r) => (Binary::Neq, l, r),
+        Binary::Gt(l, r) => (Binary::Gt, l, r),
+        Binary::Lt(l, r) => (Binary::Lt, l, r),
+        Binary::Gte(l, r) => (Binary::Gte, l, r),
+        Binary::Lte(l, r) => (Binary::Lte, l, r),
+        Binary::And(l, r) => (Binary::And, l, r),
+        Binary::Or(l, r) => (Binary::Or, l, r),
+        Binary::BitAnd(l, r) => (Binary::BitAnd, l, r),
+        Binary::BitOr(l, r) => (Binary::BitOr, l, r),
+        Binary::BitXor(l, r) => (Binary::BitXor, l, r),
+        Binary::LShift(l, r) => (Binary::LShift, l, r),
+        Binary::RShift(l, r) => (Binary::RShift, l, r),
+        Binary::Index(l, r) => (Binary::Index, l, r),
+        Binary::Comma(l, r) => (Binary::Comma, l, r),
+    };
+    let l = fold_box(folder, *l);
+    op(l, fold_box(folder, *r))
+}
+
+fn fold_assign<F: Fold + ?Sized>(folder: &mut F, assign: Assign) -> Assign {
+    let op: fn(Box<Expr>, Box<Expr>) -> Assign = match assign {
+        Assign::Assign(..) => Assign::Assign,
+        Assign::PlusAssign(..) => Assign::PlusAssign,
+        Assign::MinusAssign(..) => Assign::MinusAssign,
+        Assign::MulAssign(..) => Assign::MulAssign,
+        Assign::DivAssign(..) => Assign::DivAssign,
+        Assign::ModAssign(..) => Assign::ModAssign,
+        Assign::BitAndAssign(..) => Assign::BitAndAssign,
+        Assign::BitOrAssign(..) => Assign::BitOrAssign,
+        Assign::BitXorAssign(..) => Assign::BitXorAssign,
+        Assign::LShiftAssign(..) => Assign::LShiftAssign,
+        Assign::RShiftAssign(..) => Assign::RShiftAssign,
+    };
+    let (target, value) = assign.into_operands();
+    let target = fold_box(folder, *target);
+    op(target, fold_box(folder, *value))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{
+        ast::{
+            expr::{binary_expr::Binary, expr::Expr, literal_expr::Literal},
+            stmt::{declare_stmt::Declare, stmt::Stmt},
+        },
+        parser::parser::Parser,
+    };
+
+    use super::{walk_expr, Fold};
+
+    // folds the sums and products of integer literals
+    struct Constants;
+
+    fn int(expr: &Expr) -> Option<i64> {
+        match expr {
+            Expr::LiteralExpr(_, Literal::Int(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    impl Fold for Constants {
+        fn fold_expr(&mut self, expr: Expr) -> Expr {
+            let expr = walk_expr(self, expr);
+            let value = match &expr {
+                Expr::BinaryExpr(_, Binary::Plus(l, r)) => int(l).zip(int(r)).map(|(l, r)| l + r),
+                Expr::BinaryExpr(_, Binary::Mul(l, r)) => int(l).zip(int(r)).map(|(l, r)| l * r),
+                _ => None,
+            };
+            match value {
+                Some(value) => Expr::LiteralExpr(expr.attr().clone(), Literal::Int(value)),
+                None => expr,
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_fold() {
+        let src = "int a = 1 + 2 * 3, b[] = {x * (2 + 2)}; void f(void) { a += 1 + 1; }";
+        let unit = Constants.fold_unit(Parser::new(src).parse_unit().unwrap());
+        let printed: Vec<String> = unit
+            .stmts
+            .iter()
+            .map(|stmt| match stmt {
+                Stmt::Declare(Declare::DeclareIdentifier(_, _, _, Some(init))) => init.to_string(),
+                Stmt::Declare(Declare::DeclareFunction { body, .. }) => body[0].to_string(),
+                stmt => panic!("unexpected {:?}", stmt),
+            })
+            .collect();
+        assert_eq!(printed, vec!["7", "{ x * (4) }", "a += 2;"]);
+    }
+}
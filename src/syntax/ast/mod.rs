@@ -1,3 +1,7 @@
 pub mod ast_module;
 pub mod expr;
+pub mod fold;
+pub mod printer;
 pub mod stmt;
+pub mod visit;
+pub mod visit_mut;
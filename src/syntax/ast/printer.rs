@@ -0,0 +1,693 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+};
+
+use crate::syntax::typing::{
+    tag::{TagId, TagKind, TagTable},
+    types::Type,
+};
+
+use super::{
+    expr::{expr::Expr, type_expr::TypeExpr},
+    stmt::{
+        declare_stmt::{function_head, Declare},
+        initializer::Initializer,
+        stmt::Stmt,
+    },
+    visit::{walk_declare, walk_expr, Visitor},
+};
+
+/// Prints statements back as indented C that parses to the same tree,
+/// with tags by their names.
+///
+/// The parser puts the definition of a tag before the declaration whose
+/// specifiers define it, as in `struct { int x; } a, b;`. The printer
+/// holds the definition back and writes it in the specifiers of that
+/// declaration again, so anonymous tags stay nameable and tags are
+/// numbered in the same order when the output is parsed.
+///
+/// A tag defined in the type name of a cast, `sizeof` or compound
+/// literal has no declaration of its own, and is defined again where
+/// the type name first uses it: `sizeof(struct { int a; })`.
+pub struct Printer<'a> {
+    tags: &'a TagTable,
+    // the tag definitions not printed yet
+    pending: Vec<TagId>,
+    // the tags defined in type names, until their definitions are printed
+    inline: HashSet<TagId>,
+    // the typedef names of anonymous tags, which have no other name
+    typedefs: HashMap<TagId, String>,
+    out: String,
+    indent: usize,
+}
+
+/// The type a declarator derives the type of a declaration from: `int`
+/// in `int *(*p)[3]`.
+fn base(typ: &Type) -> &Type {
+    match typ {
+        Type::Pointer(typ) | Type::Array(typ, _) | Type::Function(_, typ, _) => base(typ),
+        typ => typ,
+    }
+}
+
+fn tag_of(typ: &Type) -> Option<TagId> {
+    match base(typ) {
+        Type::Struct(id) | Type::Union(id) | Type::Enum(id) => Some(*id),
+        _ => None,
+    }
+}
+
+// the tags that declarations of their own define
+struct Declared(HashSet<TagId>);
+
+impl Visitor for Declared {
+    fn visit_declare(&mut self, declare: &Declare) {
+        if let Declare::DeclareStruct(_, id)
+        | Declare::DeclareUnion(_, id)
+        | Declare::DeclareEnum(_, id) = declare
+        {
+            self.0.insert(*id);
+        }
+        walk_declare(self, declare)
+    }
+}
+
+// the tags of the type names in an expression, in order
+struct TypeNames(Vec<TagId>);
+
+impl Visitor for TypeNames {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let Expr::TypeExpr(_, type_expr) = expr {
+            let typ = match type_expr {
+                TypeExpr::Cast(typ, _)
+                | TypeExpr::Sizeof(typ)
+                | TypeExpr::Alignof(typ)
+                | TypeExpr::CompoundLiteral(typ, _) => typ,
+            };
+            self.0.extend(tag_of(typ));
+        }
+        walk_expr(self, expr)
+    }
+}
+
+/// Whether an `else` after `stmt` would go to an `if` inside it.
+fn ends_with_open_if(stmt: &Stmt) -> bool {
+    match stmt {
+        Stmt::If(_, _, _, None) => true,
+        Stmt::If(_, _, _, Some(stmt))
+        | Stmt::While(_, _, stmt)
+        | Stmt::For(_, _, _, _, stmt)
+        | Stmt::Switch(_, _, stmt)
+        | Stmt::Case(_, _, stmt)
+        | Stmt::Default(_, stmt)
+        | Stmt::Label(_, _, stmt) => ends_with_open_if(stmt),
+        _ => false,
+    }
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(tags: &'a TagTable) -> Printer<'a> {
+        Printer {
+            tags,
+            pending: Vec::new(),
+            inline: HashSet::new(),
+            typedefs: HashMap::new(),
+            out: String::new(),
+            indent: 0,
+        }
+    }
+
+    /// The statements of a translation unit, with blank lines around
+    /// function definitions.
+    pub fn print(mut self, stmts: &[Stmt]) -> String {
+        let mut declared = Declared(HashSet::new());
+        for stmt in stmts {
+            declared.visit_stmt(stmt);
+        }
+        self.inline = (0..self.tags.len() as u32)
+            .map(TagId)
+            .filter(|id| self.tags.tag(*id).complete && !declared.0.contains(id))
+            .collect();
+        self.stmts(stmts);
+        self.out
+    }
+
+    fn line(&mut self, text: &str) {
+        self.out.push_str(&"    ".repeat(self.indent));
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    /// A label goes one level left of the statement it labels.
+    fn label(&mut self, text: &str) {
+        self.indent = self.indent.saturating_sub(1);
+        self.line(text);
+        self.indent += 1;
+    }
+
+    /// `struct S`, or the typedef name of an anonymous tag.
+    fn spelling(&self, kind: TagKind, id: TagId) -> String {
+        let tag = self.tags.tag(id);
+        match (&tag.name, self.typedefs.get(&id)) {
+            (Some(name), _) => format!("{} {}", kind.keyword(), name),
+            (None, Some(name)) => name.clone(),
+            (None, None) => tag.spelling(),
+        }
+    }
+
+    /// The definitions of the tags that the type names `visit` finds
+    /// define, each to be printed in place of the first use of its tag.
+    fn type_names(
+        &mut self,
+        visit: impl FnOnce(&mut TypeNames),
+    ) -> RefCell<HashMap<TagId, String>> {
+        let mut names = TypeNames(Vec::new());
+        visit(&mut names);
+        let mut definitions = HashMap::new();
+        for id in names.0 {
+            if self.inline.contains(&id) {
+                let definition = self.definition(id);
+                definitions.insert(id, definition);
+            }
+        }
+        RefCell::new(definitions)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> String {
+        let definitions = self.type_names(|names| names.visit_expr(expr));
+        expr.to_c(&|kind, id| {
+            let definition = definitions.borrow_mut().remove(&id);
+            definition.unwrap_or_else(|| self.spelling(kind, id))
+        })
+    }
+
+    fn initializer(&mut self, init: &Initializer) -> String {
+        let definitions = self.type_names(|names| names.visit_initializer(init));
+        init.to_c(&|kind, id| {
+            let definition = definitions.borrow_mut().remove(&id);
+            definition.unwrap_or_else(|| self.spelling(kind, id))
+        })
+    }
+
+    /// The declarator that goes after the specifiers of `typ`: `*p[3]`
+    /// for `int *p[3]`.
+    fn declarator(&self, typ: &Type, name: &str) -> String {
+        let tag = |kind, id| self.spelling(kind, id);
+        let declaration = typ.declaration(name, &tag);
+        let specifier = base(typ).declaration("", &tag);
+        declaration[specifier.len()..].trim_start().to_owned()
+    }
+
+    /// The specifiers of `typ`, with the definition of its tag when it
+    /// is held back or defined in a type name.
+    fn specifier(&mut self, typ: &Type) -> String {
+        match tag_of(typ) {
+            Some(id) if self.pending.contains(&id) || self.inline.contains(&id) => {
+                self.definition(id)
+            }
+            _ => base(typ).declaration("", &|kind, id| self.spelling(kind, id)),
+        }
+    }
+
+    /// `specifier declarator`, or just one of them when the other is
+    /// empty.
+    fn join(specifier: String, declarator: &str) -> String {
+        match declarator {
+            "" => specifier,
+            declarator => format!("{} {}", specifier, declarator),
+        }
+    }
+
+    /// The held back tags that the definition of `id` prints, as they
+    /// define the types of its members.
+    fn nested(&self, id: TagId, found: &mut HashSet<TagId>) {
+        for field in &self.tags.tag(id).fields {
+            if let Some(member) = tag_of(&field.typ) {
+                if self.pending.contains(&member) && found.insert(member) {
+                    self.nested(member, found);
+                }
+            }
+        }
+    }
+
+    /// Prints the held back definitions on their own, apart from `keep`
+    /// and the ones it prints.
+    fn flush(&mut self, keep: Option<TagId>) {
+        let mut kept = HashSet::new();
+        if let Some(id) = keep {
+            kept.insert(id);
+            self.nested(id, &mut kept);
+        }
+        // the later definitions print the earlier ones they use
+        while let Some(id) = self.pending.iter().rev().find(|id| !kept.contains(id)) {
+            let definition = self.definition(*id);
+            self.line(&format!("{};", definition));
+        }
+    }
+
+    /// Holds back the definition of `id` for the declaration after it,
+    /// or for the definition of a tag whose member it is.
+    fn define(&mut self, id: TagId) {
+        self.flush(Some(id));
+        self.pending.push(id);
+    }
+
+    /// `struct S { int x; }` or `enum { A, B = 3 }`, indented to go on
+    /// the current line.
+    fn definition(&mut self, id: TagId) -> String {
+        self.pending.retain(|pending| *pending != id);
+        self.inline.remove(&id);
+        let tag = self.tags.tag(id);
+        let mut lines = Vec::new();
+        self.indent += 1;
+        let pad = "    ".repeat(self.indent);
+        match tag.kind {
+            TagKind::Enum => {
+                let mut next = 0;
+                for (name, value) in &tag.enumerators {
+                    if *value == next {
+                        lines.push(format!("{}{},", pad, name));
+                    } else {
+                        lines.push(format!("{}{} = {},", pad, name, value));
+                    }
+                    next = value.wrapping_add(1);
+                }
+            }
+            TagKind::Struct | TagKind::Union => {
+                for field in &tag.fields {
+                    let name = field.name.as_deref().unwrap_or("");
+                    let specifier = self.specifier(&field.typ);
+                    let mut member = Printer::join(specifier, &self.declarator(&field.typ, name));
+                    if let Some(bits) = field.bits {
+                        member = format!("{} : {}", member, bits);
+                    }
+                    lines.push(format!("{}{};", pad, member));
+                }
+            }
+        }
+        self.indent -= 1;
+        let head = match &tag.name {
+            Some(name) => format!("{} {}", tag.kind.keyword(), name),
+            None => String::from(tag.kind.keyword()),
+        };
+        if lines.is_empty() {
+            return format!("{} {{}}", head);
+        }
+        let end = "    ".repeat(self.indent);
+        format!("{} {{\n{}\n{}}}", head, lines.join("\n"), end)
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        let mut i = 0;
+        while i < stmts.len() {
+            match &stmts[i] {
+                Stmt::Declare(
+                    Declare::DeclareStruct(_, id)
+                    | Declare::DeclareUnion(_, id)
+                    | Declare::DeclareEnum(_, id),
+                ) => {
+                    self.define(*id);
+                    i += 1;
+                }
+                Stmt::Declare(Declare::DeclareIdentifier(..) | Declare::DeclareTypedef(..)) => {
+                    let (declaration, n) = self.declaration(&stmts[i..], false);
+                    self.line(&format!("{};", declaration));
+                    i += n;
+                }
+                stmt @ Stmt::Declare(Declare::DeclareFunction { .. }) => {
+                    if i > 0 && !self.out.ends_with("\n\n") {
+                        self.out.push('\n');
+                    }
+                    self.stmt(stmt);
+                    i += 1;
+                    if i < stmts.len() {
+                        self.out.push('\n');
+                    }
+                }
+                stmt => {
+                    self.flush(None);
+                    self.stmt(stmt);
+                    i += 1;
+                }
+            }
+        }
+        self.flush(None);
+    }
+
+    /// The declaration of the declarators at the start of `stmts`, and
+    /// how many of them it takes. The declarators after the first are
+    /// taken when `all` is set, or when the specifiers define a tag.
+    fn declaration(&mut self, stmts: &[Stmt], all: bool) -> (String, usize) {
+        let (typedef, typ) = match &stmts[0] {
+            Stmt::Declare(Declare::DeclareTypedef(_, _, typ)) => (true, typ),
+            Stmt::Declare(Declare::DeclareIdentifier(_, _, typ, _)) => (false, typ),
+            stmt => unreachable!("{:?} is not a declaration", stmt),
+        };
+        let keep = tag_of(typ).filter(|id| self.pending.contains(id));
+        self.flush(keep);
+        let mut specifier = self.specifier(typ);
+        if typedef {
+            specifier = format!("typedef {}", specifier);
+        }
+        let mut declarators = Vec::new();
+        for stmt in stmts {
+            let declarator = match stmt {
+                Stmt::Declare(Declare::DeclareTypedef(_, name, other))
+                    if typedef && base(other) == base(typ) =>
+                {
+                    let declarator = self.declarator(other, name);
+                    if let Some(id) = tag_of(other).filter(|_| base(other) == other) {
+                        if self.tags.tag(id).name.is_none() {
+                            self.typedefs.entry(id).or_insert_with(|| name.clone());
+                        }
+                    }
+                    declarator
+                }
+                Stmt::Declare(Declare::DeclareIdentifier(_, name, other, init))
+                    if !typedef && base(other) == base(typ) =>
+                {
+                    let declarator = self.declarator(other, name);
+                    match init {
+                        Some(init) => format!("{} = {}", declarator, self.initializer(init)),
+                        None => declarator,
+                    }
+                }
+                _ => break,
+            };
+            declarators.push(declarator);
+            if !all && keep.is_none() {
+                break;
+            }
+        }
+        let n = declarators.len();
+        (format!("{} {}", specifier, declarators.join(", ")), n)
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Expr(_, e) => {
+                let e = self.expr(e);
+                self.line(&format!("{};", e));
+            }
+            Stmt::Return(_, Some(e)) => {
+                let e = self.expr(e);
+                self.line(&format!("return {};", e));
+            }
+            Stmt::Return(_, None) => self.line("return;"),
+            Stmt::Directive(..) => self.line(&stmt.to_string()),
+            Stmt::Block(_, stmts) => {
+                self.line("{");
+                self.block(stmts);
+                self.line("}");
+            }
+            Stmt::Declare(Declare::DeclareFunction {
+                name,
+                parameters,
+                prototype,
+                return_type,
+                body,
+                ..
+            }) => {
+                let keep = tag_of(return_type).filter(|id| self.pending.contains(id));
+                self.flush(keep);
+                let specifier = self.specifier(return_type);
+                let head = function_head(name, parameters, *prototype, return_type, &|kind, id| {
+                    self.spelling(kind, id)
+                });
+                let declarator = self.declarator_of(return_type, &head);
+                self.line(&format!("{} {{", Printer::join(specifier, &declarator)));
+                self.block(body);
+                self.line("}");
+            }
+            Stmt::Declare(_) => self.stmts(std::slice::from_ref(stmt)),
+            Stmt::If(_, cond, then, otherwise) => self.if_stmt("", cond, then, otherwise),
+            Stmt::While(_, cond, body) => {
+                let head = format!("while ({})", self.expr(cond));
+                self.body(&head, body);
+            }
+            Stmt::DoWhile(_, body, cond) => {
+                let cond = self.expr(cond);
+                match body.as_ref() {
+                    Stmt::Block(_, stmts) => {
+                        self.line("do {");
+                        self.block(stmts);
+                        self.line(&format!("}} while ({});", cond));
+                    }
+                    body => {
+                        self.body("do", body);
+                        self.line(&format!("while ({});", cond));
+                    }
+                }
+            }
+            Stmt::For(_, init, cond, step, body) => {
+                let mut head = String::from("for (");
+                match init.first() {
+                    Some(Stmt::Expr(_, e)) => head.push_str(&self.expr(e)),
+                    Some(_) => {
+                        // a tag the declaration defines comes first
+                        let start = init
+                            .iter()
+                            .take_while(|stmt| {
+                                matches!(
+                                    stmt,
+                                    Stmt::Declare(
+                                        Declare::DeclareStruct(..)
+                                            | Declare::DeclareUnion(..)
+                                            | Declare::DeclareEnum(..)
+                                    )
+                                )
+                            })
+                            .count();
+                        for stmt in &init[..start] {
+                            if let Stmt::Declare(
+                                Declare::DeclareStruct(_, id)
+                                | Declare::DeclareUnion(_, id)
+                                | Declare::DeclareEnum(_, id),
+                            ) = stmt
+                            {
+                                self.define(*id);
+                            }
+                        }
+                        let (declaration, _) = self.declaration(&init[start..], true);
+                        head.push_str(&declaration);
+                    }
+                    None => {}
+                }
+                head.push(';');
+                if let Some(cond) = cond {
+                    head.push_str(&format!(" {}", self.expr(cond)));
+                }
+                head.push(';');
+                if let Some(step) = step {
+                    head.push_str(&format!(" {}", self.expr(step)));
+                }
+                head.push(')');
+                self.body(&head, body);
+            }
+            Stmt::Switch(_, e, body) => {
+                let head = format!("switch ({})", self.expr(e));
+                self.body(&head, body);
+            }
+            Stmt::Case(_, e, stmt) => {
+                let e = self.expr(e);
+                self.label(&format!("case {}:", e));
+                self.stmt(stmt);
+            }
+            Stmt::Default(_, stmt) => {
+                self.label("default:");
+                self.stmt(stmt);
+            }
+            Stmt::Label(_, name, stmt) => {
+                self.label(&format!("{}:", name));
+                self.stmt(stmt);
+            }
+            Stmt::Break(_) => self.line("break;"),
+            Stmt::Continue(_) => self.line("continue;"),
+            Stmt::Goto(_, name) => self.line(&format!("goto {};", name)),
+            Stmt::Empty(_) => self.line(";"),
+            Stmt::Error(_) => self.line("/* error */"),
+        }
+    }
+
+    /// The declarator of a function definition that returns `typ`, whose
+    /// name and parameters are `head` with the return type around them.
+    fn declarator_of(&self, typ: &Type, head: &str) -> String {
+        let specifier = base(typ).declaration("", &|kind, id| self.spelling(kind, id));
+        head[specifier.len()..].trim_start().to_owned()
+    }
+
+    /// The statements of a block, one level in.
+    fn block(&mut self, stmts: &[Stmt]) {
+        self.indent += 1;
+        self.stmts(stmts);
+        self.indent -= 1;
+    }
+
+    /// `head {` and a block, or `head` and a statement one level in.
+    fn body(&mut self, head: &str, body: &Stmt) {
+        match body {
+            Stmt::Block(_, stmts) => {
+                self.line(&format!("{} {{", head));
+                self.block(stmts);
+                self.line("}");
+            }
+            body => {
+                self.line(head);
+                self.indent += 1;
+                self.stmt(body);
+                self.indent -= 1;
+            }
+        }
+    }
+
+    /// `if` after `prefix`, which is `} else ` in an `else if` chain.
+    fn if_stmt(&mut self, prefix: &str, cond: &Expr, then: &Stmt, otherwise: &Option<Box<Stmt>>) {
+        let head = format!("{}if ({})", prefix, self.expr(cond));
+        let otherwise = match otherwise {
+            Some(otherwise) => otherwise,
+            None => return self.body(&head, then),
+        };
+        let braced = match then {
+            Stmt::Block(..) => {
+                self.body(&head, then);
+                true
+            }
+            // the `else` would go to the `if` inside without the braces
+            then if ends_with_open_if(then) => {
+                self.line(&format!("{} {{", head));
+                self.block(std::slice::from_ref(then));
+                self.line("}");
+                true
+            }
+            then => {
+                self.body(&head, then);
+                false
+            }
+        };
+        let prefix = if braced {
+            // the `}` goes on the line of the `else`
+            let end = format!("{}}}\n", "    ".repeat(self.indent));
+            self.out.truncate(self.out.len() - end.len());
+            "} else"
+        } else {
+            "else"
+        };
+        match otherwise.as_ref() {
+            Stmt::If(_, cond, then, otherwise) => {
+                self.if_stmt(&format!("{} ", prefix), cond, then, otherwise)
+            }
+            otherwise => self.body(prefix, otherwise),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::syntax::parser::parser::Parser;
+
+    /// Parses `src`, prints it and parses the output again, which must
+    /// give the same tree and print the same.
+    fn round_trip(src: &str) -> String {
+        let unit = Parser::new(src).parse_unit().unwrap();
+        let printed = unit.to_string();
+        let reparsed = match Parser::new(&printed).parse_unit() {
+            Ok(unit) => unit,
+            Err(errors) => panic!("{:?} in\n{}", errors, printed),
+        };
+        assert_eq!(reparsed, unit, "\n{}", printed);
+        assert_eq!(reparsed.to_string(), printed);
+        printed
+    }
+
+    #[test]
+    pub fn test_print() {
+        let src = "struct P { int x, y; } origin = {0}; int f(struct P *p, int n) {
+            int i; for (i = 0; i < n; i++) if (p[i].x) { p->y += -i; } else if (n) break;
+            switch (n) { case 1: case 2: n = (n + 1) * 2; default: ; }
+            return sizeof(struct P) + (int)'\\n';
+        }";
+        assert_eq!(
+            round_trip(src),
+            "struct P {
+    int x;
+    int y;
+} origin = { 0 };
+
+int f(struct P *p, int n) {
+    int i;
+    for (i = 0; i < n; i++)
+        if (p[i].x) {
+            p->y += -i;
+        } else if (n)
+            break;
+    switch (n) {
+    case 1:
+    case 2:
+        n = (n + 1) * 2;
+    default:
+        ;
+    }
+    return sizeof(struct P) + (int)'\\n';
+}
+"
+        );
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        round_trip(&fs::read_to_string("examples/c1.c").unwrap());
+        round_trip("int a[] = {1, [3] = 2}, *p = &a[1], (*q)[4] = &a; char *s = \"a\\\"\\0\";");
+        round_trip("int (*signal(int sig, void (*f)(int)))(int); void (*h[2])(void);");
+        assert_eq!(
+            round_trip("int printf(char *, ...); int f(int n, ...) { return printf(\"\", n); }"),
+            "int printf(char *, ...);\n\nint f(int n, ...) {\n    return printf(\"\", n);\n}\n"
+        );
+        assert_eq!(
+            round_trip("int f(); int g(void) { return f(1); } int (*h(void))();"),
+            "int f();\n\nint g(void) {\n    return f(1);\n}\n\nint (*h(void))();\n"
+        );
+        assert_eq!(
+            round_trip("char c = '\\377'; char *s = \"\\303\\251é\\u4e2d\";"),
+            "char c = '\\377';\nchar *s = \"\\303\\251\\351\\u4e2d\";\n"
+        );
+        assert_eq!(
+            round_trip("float f = 1.5f; long double l = 1.0L; double d = 2.5e-3 + 0x1p4F;"),
+            "float f = 1.5f;\nlong double l = 1.0L;\ndouble d = 0.0025 + 16.0f;\n"
+        );
+        assert_eq!(
+            round_trip("unsigned long u = 18446744073709551615u; int a[2u];"),
+            "unsigned long u = 18446744073709551615u;\nint a[2];\n"
+        );
+        round_trip("typedef struct { int x; } P, *PP; P p = {1}; PP q = &p; P r[2];");
+        round_trip("struct { int a; union { char c; float f; } u; } x, y; enum { A, B = 3, C } e;");
+        round_trip("struct L { struct L *next; struct V { int v : 3; unsigned : 2; } v; };");
+        round_trip("enum E { X = -1, Y }; struct S; struct S *s; struct S { enum E e; };");
+        round_trip("void g(void) { struct U { int u; }; g(); { enum { K }; return; } }");
+        assert_eq!(
+            round_trip("int n = sizeof(struct { int a; }); int f(void) { return (struct { int a; }){1}.a; }"),
+            "int n = sizeof(struct {\n    int a;\n});\n\nint f(void) {\n    return (struct {\n        int a;\n    }){ 1 }.a;\n}\n"
+        );
+        round_trip(
+            "struct S { int s; }; int m = sizeof(struct Q { struct { int b; } r; enum { E } e; }) + E;
+            void g(struct S *p) { p = (struct S *)(struct Q *)0; g((struct { union { int u; } v; } *)0); }",
+        );
+        // a tag defined in the parameters would have nowhere to be printed
+        round_trip("struct P { int x; }; void f(struct P *p); void h(struct P *);");
+        assert!(Parser::new("void f(struct P { int x; } *p);")
+            .parse_unit()
+            .is_err());
+        round_trip(
+            "void f(int n) {
+                struct T { int t; } t = {n};
+                for (int i = 0, j = n; i < j; i++, j--) while (i) do --i; while (i > 2);
+                { int k = (t.t, n) ? n : -n; goto end; }
+                end: if (n) if (n > 1) n = 0; else n = 1;
+                n = (struct T){ 2 }.t + -(-n) - - -n + sizeof n + _Alignof(long);
+                n = 1 ? 2 : 3 ? 4 : 5; n = (1 ? 2 : 3) ? 4 : 5; n = (n = 2) + (n, 3);
+            }",
+        );
+    }
+}
@@ -1,34 +1,146 @@
 use core::fmt;
 
-use crate::syntax::{ast::expr::expr::Expr, typing::types::Type};
+use crate::syntax::{
+    tokenizer::attr::Attr,
+    typing::{
+        tag::{TagId, TagKind},
+        types::{numbered_tag, parameter_list, Prototype, Type},
+    },
+};
 
-use super::stmt::Stmt;
+use super::{initializer::Initializer, stmt::Stmt};
 
-#[derive(PartialEq, Debug)]
+/// A declaration. Its attr covers the declarator and its initializer,
+/// the whole definition of a function, or a tag definition from its
+/// keyword to the `}`. Declarations compare equal whatever their attrs.
+#[derive(Debug)]
 pub enum Declare {
-    DeclareIdentifier(String, Type, Option<Expr>),
+    DeclareIdentifier(Attr, String, Type, Option<Initializer>),
     DeclareFunction {
+        attr: Attr,
         name: String,
         parameters: Vec<(String, Type)>,
+        // whether the parameters have a prototype, and end in `...`
+        prototype: Prototype,
         return_type: Type,
         body: Vec<Stmt>,
     },
-    DeclareVector(String, Type, i32),
-    DeclarePointer(String, Type),
-    DeclareStruct(String, Vec<(String, Type)>),
-    DeclareUnion(String, Vec<(String, Type)>),
-    DeclareEnum(String, Vec<(String, Option<i32>)>),
-    DeclareTypedef(String, Type),
+    // the definition of a tag, whose members are in the tag table
+    DeclareStruct(Attr, TagId),
+    DeclareUnion(Attr, TagId),
+    DeclareEnum(Attr, TagId),
+    DeclareTypedef(Attr, String, Type),
 }
 
+impl PartialEq for Declare {
+    fn eq(&self, other: &Declare) -> bool {
+        match (self, other) {
+            (
+                Declare::DeclareIdentifier(_, name, typ, init),
+                Declare::DeclareIdentifier(_, other_name, other_typ, other_init),
+            ) => (name, typ, init) == (other_name, other_typ, other_init),
+            (
+                Declare::DeclareFunction {
+                    name,
+                    parameters,
+                    prototype,
+                    return_type,
+                    body,
+                    ..
+                },
+                Declare::DeclareFunction {
+                    name: other_name,
+                    parameters: other_parameters,
+                    prototype: other_prototype,
+                    return_type: other_return_type,
+                    body: other_body,
+                    ..
+                },
+            ) => {
+                (name, parameters, prototype, return_type, body)
+                    == (
+                        other_name,
+                        other_parameters,
+                        other_prototype,
+                        other_return_type,
+                        other_body,
+                    )
+            }
+            (Declare::DeclareStruct(_, a), Declare::DeclareStruct(_, b))
+            | (Declare::DeclareUnion(_, a), Declare::DeclareUnion(_, b))
+            | (Declare::DeclareEnum(_, a), Declare::DeclareEnum(_, b)) => a == b,
+            (
+                Declare::DeclareTypedef(_, name, typ),
+                Declare::DeclareTypedef(_, other, other_typ),
+            ) => (name, typ) == (other, other_typ),
+            _ => false,
+        }
+    }
+}
+
+impl Declare {
+    pub fn attr(&self) -> &Attr {
+        match self {
+            Declare::DeclareIdentifier(attr, ..)
+            | Declare::DeclareFunction { attr, .. }
+            | Declare::DeclareStruct(attr, _)
+            | Declare::DeclareUnion(attr, _)
+            | Declare::DeclareEnum(attr, _)
+            | Declare::DeclareTypedef(attr, ..) => attr,
+        }
+    }
+}
+
+/// `int *f(int a, char *b)`, the head of a function definition, with
+/// the tags in its types spelled by `tag`.
+pub fn function_head(
+    name: &str,
+    parameters: &[(String, Type)],
+    prototype: Prototype,
+    return_type: &Type,
+    tag: &dyn Fn(TagKind, TagId) -> String,
+) -> String {
+    let parameters = parameters
+        .iter()
+        .map(|(name, typ)| typ.declaration(name, tag))
+        .collect();
+    let parameters = parameter_list(parameters, prototype);
+    return_type.declaration(&format!("{}({})", name, parameters), tag)
+}
+
+/// The declaration as C without its `;`, with tags by their ids.
 impl fmt::Display for Declare {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Declare::DeclareIdentifier(name, typ, expr) => {
-                write!(f, "int {}", name)
+            Declare::DeclareIdentifier(_, name, typ, init) => {
+                write!(f, "{}", typ.declaration(name, &numbered_tag))?;
+                match init {
+                    Some(init) => write!(f, " = {}", init),
+                    None => Ok(()),
+                }
             }
-            _ => {
-                write!(f, "not implemented yet")
+            Declare::DeclareFunction {
+                name,
+                parameters,
+                prototype,
+                return_type,
+                body,
+                ..
+            } => {
+                let head = function_head(name, parameters, *prototype, return_type, &numbered_tag);
+                writeln!(f, "{} {{", head)?;
+                for stmt in body {
+                    for line in stmt.to_string().lines() {
+                        writeln!(f, "    {}", line)?;
+                    }
+                }
+                write!(f, "}}")
+            }
+            Declare::DeclareStruct(_, id) => write!(f, "{}", numbered_tag(TagKind::Struct, *id)),
+            Declare::DeclareUnion(_, id) => write!(f, "{}", numbered_tag(TagKind::Union, *id)),
+            Declare::DeclareEnum(_, id) => write!(f, "{}", numbered_tag(TagKind::Enum, *id)),
+            Declare::DeclareTypedef(_, name, typ) => {
+                write!(f, "typedef {}", typ.declaration(name, &numbered_tag))
             }
         }
     }
@@ -36,13 +148,40 @@ impl fmt::Display for Declare {
 
 #[cfg(test)]
 mod tests {
-    use crate::syntax::typing::types::Type;
+    use crate::syntax::ast::stmt::stmt::Stmt;
+    use crate::syntax::tokenizer::attr::Attr;
+    use crate::syntax::typing::{
+        tag::TagId,
+        types::{Prototype, Type},
+    };
 
     use super::Declare;
 
     #[test]
     pub fn test_declare_identifier() {
-        let s1 = Declare::DeclareIdentifier(String::from("a"), Type::Int, None);
+        let s1 = Declare::DeclareIdentifier(Attr::new(), String::from("a"), Type::Int, None);
         assert_eq!(s1.to_string(), "int a");
     }
+
+    #[test]
+    pub fn test_display() {
+        let p = Declare::DeclareIdentifier(
+            Attr::new(),
+            String::from("p"),
+            Type::Pointer(Box::new(Type::Array(Box::new(Type::Char), Some(3)))),
+            None,
+        );
+        assert_eq!(p.to_string(), "char (*p)[3]");
+        let f = Declare::DeclareFunction {
+            attr: Attr::new(),
+            name: String::from("f"),
+            parameters: vec![(String::from("s"), Type::Struct(TagId(0)))],
+            prototype: Prototype::Variadic,
+            return_type: Type::Pointer(Box::new(Type::Int)),
+            body: vec![Stmt::Break(Attr::new())],
+        };
+        assert_eq!(f.to_string(), "int *f(struct #0 s, ...) {\n    break;\n}");
+        let t = Declare::DeclareTypedef(Attr::new(), String::from("T"), Type::ULong);
+        assert_eq!(t.to_string(), "typedef unsigned long T");
+    }
 }
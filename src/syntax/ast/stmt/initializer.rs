@@ -0,0 +1,126 @@
+use core::fmt;
+
+use crate::syntax::{
+    ast::expr::{binary_expr::Binary, expr::Expr},
+    tokenizer::attr::Attr,
+    typing::{
+        tag::{TagId, TagKind},
+        types::numbered_tag,
+    },
+};
+
+/// Picks the member an initializer in a list is for.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Designator {
+    // .x
+    Field(String),
+    // [3]
+    Index(i64),
+}
+
+/// The initializer of a declaration. Members a list leaves out are
+/// zero-initialized.
+#[derive(Debug)]
+pub enum Initializer {
+    // = 1
+    // = "abc"
+    Expr(Box<Expr>),
+    // = { 1, .x = 2, [3] = 4, { 5 } }, with the attr of the braces and
+    // what is between them
+    List(Attr, Vec<(Vec<Designator>, Initializer)>),
+}
+
+/// Like expressions, initializers compare equal whatever their attrs.
+impl PartialEq for Initializer {
+    fn eq(&self, other: &Initializer) -> bool {
+        match (self, other) {
+            (Initializer::Expr(a), Initializer::Expr(b)) => a == b,
+            (Initializer::List(_, a), Initializer::List(_, b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Initializer {
+    pub fn attr(&self) -> &Attr {
+        match self {
+            Initializer::Expr(expr) => expr.attr(),
+            Initializer::List(attr, _) => attr,
+        }
+    }
+}
+
+impl fmt::Display for Designator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Designator::Field(name) => write!(f, ".{}", name),
+            Designator::Index(index) => write!(f, "[{}]", index),
+        }
+    }
+}
+
+impl Initializer {
+    /// The initializer as C, with the tags in its types spelled by `tag`.
+    pub fn to_c(&self, tag: &dyn Fn(TagKind, TagId) -> String) -> String {
+        match self {
+            Initializer::Expr(expr) => match expr.as_ref() {
+                // a comma would separate the items
+                Expr::BinaryExpr(_, Binary::Comma(..)) => format!("({})", expr.to_c(tag)),
+                expr => expr.to_c(tag),
+            },
+            Initializer::List(_, items) if items.is_empty() => String::from("{}"),
+            Initializer::List(_, items) => {
+                let items = items
+                    .iter()
+                    .map(|(designators, init)| {
+                        let designators: String =
+                            designators.iter().map(|d| d.to_string()).collect();
+                        if designators.is_empty() {
+                            init.to_c(tag)
+                        } else {
+                            format!("{} = {}", designators, init.to_c(tag))
+                        }
+                    })
+                    .collect::<Vec<String>>();
+                format!("{{ {} }}", items.join(", "))
+            }
+        }
+    }
+}
+
+impl fmt::Display for Initializer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_c(&numbered_tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::ast::expr::{expr::Expr, literal_expr::Literal};
+    use crate::syntax::tokenizer::attr::Attr;
+
+    use super::{Designator, Initializer};
+
+    fn int(i: i64) -> Initializer {
+        Initializer::Expr(Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(i))))
+    }
+
+    #[test]
+    pub fn test_initializer() {
+        assert_eq!(int(1).to_string(), "1");
+        assert_eq!(Initializer::List(Attr::new(), vec![]).to_string(), "{}");
+
+        let init = Initializer::List(
+            Attr::new(),
+            vec![
+                (vec![], int(1)),
+                (vec![Designator::Field(String::from("x"))], int(2)),
+                (
+                    vec![Designator::Index(3), Designator::Field(String::from("y"))],
+                    Initializer::List(Attr::new(), vec![(vec![], int(4))]),
+                ),
+            ],
+        );
+        assert_eq!(init.to_string(), "{ 1, .x = 2, [3].y = { 4 } }");
+    }
+}
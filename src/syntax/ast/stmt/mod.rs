@@ -1,4 +1,5 @@
 pub mod declare_stmt;
 pub mod directive_stmt;
 pub mod expr_stmt;
+pub mod initializer;
 pub mod stmt;
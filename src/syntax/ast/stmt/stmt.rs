@@ -1,28 +1,296 @@
-use crate::syntax::ast::expr::expr::Expr;
+use std::fmt;
+
+use crate::syntax::{ast::expr::expr::Expr, tokenizer::attr::Attr};
 
 use super::{declare_stmt::Declare, directive_stmt::Directive};
 
-#[derive(PartialEq, Debug)]
+/// A statement, with the attr of the tokens it was parsed from, which
+/// ends at its `;` or `}`. Statements compare equal whatever their
+/// attrs.
+#[derive(Debug)]
 pub enum Stmt {
     // 1
     // 'a'
     // "abc"
     // a
     // 1 + 1
-    // 1 - 2
-    // 1 * 2
-    // 1 / 2
-    Expr(Box<Expr>),
-    Directive(Directive),
     // a = b
-    Assign(Box<Expr>, Box<Expr>),
+    // i++
+    Expr(Attr, Box<Expr>),
+    Directive(Attr, Directive),
     // return a
-    Return(Box<Expr>),
+    // return
+    Return(Attr, Option<Box<Expr>>),
     // {
     //   a = 1;
     //   return a;
     // }
-    Block(Vec<Stmt>),
+    Block(Attr, Vec<Stmt>),
     // int a;
+    // the declaration has the attr
     Declare(Declare),
+    // if (a) b; else c;
+    If(Attr, Box<Expr>, Box<Stmt>, Option<Box<Stmt>>),
+    // while (a) b;
+    While(Attr, Box<Expr>, Box<Stmt>),
+    // do a; while (b);
+    DoWhile(Attr, Box<Stmt>, Box<Expr>),
+    // for (int i = 0; i < n; i++) a;
+    // for (;;) a;
+    // the init is empty, an expression statement or the declarations of
+    // one declaration
+    For(
+        Attr,
+        Vec<Stmt>,
+        Option<Box<Expr>>,
+        Option<Box<Expr>>,
+        Box<Stmt>,
+    ),
+    // switch (a) { ... }
+    Switch(Attr, Box<Expr>, Box<Stmt>),
+    // case 1: a;
+    Case(Attr, Box<Expr>, Box<Stmt>),
+    // default: a;
+    Default(Attr, Box<Stmt>),
+    // break;
+    Break(Attr),
+    // continue;
+    Continue(Attr),
+    // goto a;
+    Goto(Attr, String),
+    // a: b;
+    Label(Attr, String, Box<Stmt>),
+    // ;
+    Empty(Attr),
+    // a declaration or statement with a syntax error, up to where the
+    // parser went on
+    Error(Attr),
+}
+
+impl PartialEq for Stmt {
+    fn eq(&self, other: &Stmt) -> bool {
+        match (self, other) {
+            (Stmt::Expr(_, a), Stmt::Expr(_, b)) => a == b,
+            (Stmt::Directive(_, a), Stmt::Directive(_, b)) => a == b,
+            (Stmt::Return(_, a), Stmt::Return(_, b)) => a == b,
+            (Stmt::Block(_, a), Stmt::Block(_, b)) => a == b,
+            (Stmt::Declare(a), Stmt::Declare(b)) => a == b,
+            (Stmt::If(_, a, b, c), Stmt::If(_, x, y, z)) => (a, b, c) == (x, y, z),
+            (Stmt::While(_, a, b), Stmt::While(_, x, y))
+            | (Stmt::Switch(_, a, b), Stmt::Switch(_, x, y))
+            | (Stmt::Case(_, a, b), Stmt::Case(_, x, y)) => (a, b) == (x, y),
+            (Stmt::DoWhile(_, a, b), Stmt::DoWhile(_, x, y)) => (a, b) == (x, y),
+            (Stmt::For(_, a, b, c, d), Stmt::For(_, w, x, y, z)) => (a, b, c, d) == (w, x, y, z),
+            (Stmt::Default(_, a), Stmt::Default(_, b)) => a == b,
+            (Stmt::Goto(_, a), Stmt::Goto(_, b)) => a == b,
+            (Stmt::Label(_, a, b), Stmt::Label(_, x, y)) => (a, b) == (x, y),
+            (Stmt::Break(_), Stmt::Break(_))
+            | (Stmt::Continue(_), Stmt::Continue(_))
+            | (Stmt::Empty(_), Stmt::Empty(_))
+            | (Stmt::Error(_), Stmt::Error(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Stmt {
+    pub fn attr(&self) -> &Attr {
+        match self {
+            Stmt::Declare(declare) => declare.attr(),
+            Stmt::Expr(attr, _)
+            | Stmt::Directive(attr, _)
+            | Stmt::Return(attr, _)
+            | Stmt::Block(attr, _)
+            | Stmt::If(attr, ..)
+            | Stmt::While(attr, ..)
+            | Stmt::DoWhile(attr, ..)
+            | Stmt::For(attr, ..)
+            | Stmt::Switch(attr, ..)
+            | Stmt::Case(attr, ..)
+            | Stmt::Default(attr, _)
+            | Stmt::Break(attr)
+            | Stmt::Continue(attr)
+            | Stmt::Goto(attr, _)
+            | Stmt::Label(attr, ..)
+            | Stmt::Empty(attr)
+            | Stmt::Error(attr) => attr,
+        }
+    }
+}
+
+impl fmt::Display for Stmt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stmt::Expr(_, e) => write!(f, "{};", e),
+            Stmt::Directive(_, Directive::Include(name)) => write!(f, "#include <{}>", name),
+            Stmt::Directive(_, Directive::PragmaOnce) => write!(f, "#pragma once"),
+            Stmt::Return(_, Some(e)) => write!(f, "return {};", e),
+            Stmt::Return(_, None) => write!(f, "return;"),
+            Stmt::Block(_, stmts) => {
+                writeln!(f, "{{")?;
+                for stmt in stmts {
+                    for line in stmt.to_string().lines() {
+                        writeln!(f, "    {}", line)?;
+                    }
+                }
+                write!(f, "}}")
+            }
+            Stmt::Declare(declare @ Declare::DeclareFunction { .. }) => write!(f, "{}", declare),
+            Stmt::Declare(declare) => write!(f, "{};", declare),
+            Stmt::If(_, cond, then, None) => write!(f, "if ({}) {}", cond, then),
+            Stmt::If(_, cond, then, Some(otherwise)) => {
+                write!(f, "if ({}) {} else {}", cond, then, otherwise)
+            }
+            Stmt::While(_, cond, body) => write!(f, "while ({}) {}", cond, body),
+            Stmt::DoWhile(_, body, cond) => write!(f, "do {} while ({});", body, cond),
+            Stmt::For(_, init, cond, step, body) => {
+                write!(f, "for (")?;
+                if init.is_empty() {
+                    write!(f, ";")?;
+                }
+                for stmt in init {
+                    write!(f, "{}", stmt)?;
+                }
+                match cond {
+                    Some(cond) => write!(f, " {};", cond)?,
+                    None => write!(f, ";")?,
+                }
+                if let Some(step) = step {
+                    write!(f, " {}", step)?;
+                }
+                write!(f, ") {}", body)
+            }
+            Stmt::Switch(_, e, body) => write!(f, "switch ({}) {}", e, body),
+            Stmt::Case(_, e, stmt) => write!(f, "case {}: {}", e, stmt),
+            Stmt::Default(_, stmt) => write!(f, "default: {}", stmt),
+            Stmt::Break(_) => write!(f, "break;"),
+            Stmt::Continue(_) => write!(f, "continue;"),
+            Stmt::Goto(_, label) => write!(f, "goto {};", label),
+            Stmt::Label(_, label, stmt) => write!(f, "{}: {}", label, stmt),
+            Stmt::Empty(_) => write!(f, ";"),
+            Stmt::Error(_) => write!(f, "<error>"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::ast::expr::{
+        assign_expr::Assign, binary_expr::Binary, expr::Expr, literal_expr::Literal,
+    };
+    use crate::syntax::tokenizer::attr::Attr;
+
+    use super::Stmt;
+
+    fn id(name: &str) -> Box<Expr> {
+        Box::new(Expr::LiteralExpr(
+            Attr::new(),
+            Literal::ID(String::from(name)),
+        ))
+    }
+
+    fn assign(target: &str, value: &str) -> Box<Expr> {
+        Box::new(Expr::AssignExpr(
+            Attr::new(),
+            Assign::Assign(id(target), id(value)),
+        ))
+    }
+
+    fn call(name: &str) -> Box<Stmt> {
+        Box::new(Stmt::Expr(
+            Attr::new(),
+            Box::new(Expr::BinaryExpr(
+                Attr::new(),
+                Binary::Call(id(name), vec![]),
+            )),
+        ))
+    }
+
+    #[test]
+    pub fn test_simple() {
+        assert_eq!(Stmt::Expr(Attr::new(), id("a")).to_string(), "a;");
+        assert_eq!(
+            Stmt::Expr(Attr::new(), assign("a", "b")).to_string(),
+            "a = b;"
+        );
+        assert_eq!(
+            Stmt::Return(Attr::new(), Some(id("a"))).to_string(),
+            "return a;"
+        );
+        assert_eq!(Stmt::Return(Attr::new(), None).to_string(), "return;");
+        assert_eq!(Stmt::Break(Attr::new()).to_string(), "break;");
+        assert_eq!(Stmt::Continue(Attr::new()).to_string(), "continue;");
+        assert_eq!(
+            Stmt::Goto(Attr::new(), String::from("out")).to_string(),
+            "goto out;"
+        );
+        assert_eq!(
+            Stmt::Label(
+                Attr::new(),
+                String::from("out"),
+                Box::new(Stmt::Empty(Attr::new()))
+            )
+            .to_string(),
+            "out: ;"
+        );
+    }
+
+    #[test]
+    pub fn test_control_flow() {
+        let s = Stmt::If(Attr::new(), id("a"), call("f"), Some(call("g")));
+        assert_eq!(s.to_string(), "if (a) f(); else g();");
+        let s = Stmt::While(
+            Attr::new(),
+            id("a"),
+            Box::new(Stmt::Block(Attr::new(), vec![*call("f")])),
+        );
+        assert_eq!(s.to_string(), "while (a) {\n    f();\n}");
+        let s = Stmt::DoWhile(Attr::new(), call("f"), id("a"));
+        assert_eq!(s.to_string(), "do f(); while (a);");
+        let s = Stmt::For(
+            Attr::new(),
+            vec![Stmt::Expr(Attr::new(), assign("i", "n"))],
+            Some(id("i")),
+            Some(assign("i", "j")),
+            call("f"),
+        );
+        assert_eq!(s.to_string(), "for (i = n; i; i = j) f();");
+        let s = Stmt::For(
+            Attr::new(),
+            vec![],
+            None,
+            None,
+            Box::new(Stmt::Empty(Attr::new())),
+        );
+        assert_eq!(s.to_string(), "for (;;) ;");
+    }
+
+    #[test]
+    pub fn test_switch() {
+        let s = Stmt::Switch(
+            Attr::new(),
+            id("a"),
+            Box::new(Stmt::Block(
+                Attr::new(),
+                vec![
+                    Stmt::Case(
+                        Attr::new(),
+                        Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(1))),
+                        Box::new(Stmt::Break(Attr::new())),
+                    ),
+                    Stmt::Default(
+                        Attr::new(),
+                        Box::new(Stmt::Block(
+                            Attr::new(),
+                            vec![*call("f"), Stmt::Break(Attr::new())],
+                        )),
+                    ),
+                ],
+            )),
+        );
+        assert_eq!(
+            s.to_string(),
+            "switch (a) {\n    case 1: break;\n    default: {\n        f();\n        break;\n    }\n}"
+        );
+    }
 }
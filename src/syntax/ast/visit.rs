@@ -0,0 +1,226 @@
+use super::{
+    ast_module::CompilationUnit,
+    expr::{
+        binary_expr::Binary, expr::Expr, group_expr::GroupExpr, trinary_expr::Trinary,
+        type_expr::TypeExpr, unary_expr::Unary,
+    },
+    stmt::{declare_stmt::Declare, initializer::Initializer, stmt::Stmt},
+};
+
+/// Walks a tree by reference. Each method visits the children of its
+/// node with the matching `walk_` function by default, so an analysis
+/// overrides the nodes it cares about and calls `walk_` to go deeper.
+pub trait Visitor {
+    fn visit_unit(&mut self, unit: &CompilationUnit) {
+        walk_unit(self, unit)
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        walk_stmt(self, stmt)
+    }
+
+    fn visit_declare(&mut self, declare: &Declare) {
+        walk_declare(self, declare)
+    }
+
+    fn visit_initializer(&mut self, init: &Initializer) {
+        walk_initializer(self, init)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        walk_expr(self, expr)
+    }
+}
+
+pub fn walk_unit<V: Visitor + ?Sized>(visitor: &mut V, unit: &CompilationUnit) {
+    for stmt in &unit.stmts {
+        visitor.visit_stmt(stmt);
+    }
+}
+
+pub fn walk_stmt<V: Visitor + ?Sized>(visitor: &mut V, stmt: &Stmt) {
+    match stmt {
+        Stmt::Expr(_, expr) => visitor.visit_expr(expr),
+        Stmt::Return(_, expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr(expr);
+            }
+        }
+        Stmt::Block(_, stmts) => {
+            for stmt in stmts {
+                visitor.visit_stmt(stmt);
+            }
+        }
+        Stmt::Declare(declare) => visitor.visit_declare(declare),
+        Stmt::If(_, cond, then, otherwise) => {
+            visitor.visit_expr(cond);
+            visitor.visit_stmt(then);
+            if let Some(otherwise) = otherwise {
+                visitor.visit_stmt(otherwise);
+            }
+        }
+        Stmt::While(_, cond, body) | Stmt::Switch(_, cond, body) | Stmt::Case(_, cond, body) => {
+            visitor.visit_expr(cond);
+            visitor.visit_stmt(body);
+        }
+        Stmt::DoWhile(_, body, cond) => {
+            visitor.visit_stmt(body);
+            visitor.visit_expr(cond);
+        }
+        Stmt::For(_, init, cond, step, body) => {
+            for stmt in init {
+                visitor.visit_stmt(stmt);
+            }
+            if let Some(cond) = cond {
+                visitor.visit_expr(cond);
+            }
+            if let Some(step) = step {
+                visitor.visit_expr(step);
+            }
+            visitor.visit_stmt(body);
+        }
+        Stmt::Default(_, stmt) | Stmt::Label(_, _, stmt) => visitor.visit_stmt(stmt),
+        Stmt::Directive(..)
+        | Stmt::Break(_)
+        | Stmt::Continue(_)
+        | Stmt::Goto(..)
+        | Stmt::Empty(_)
+        | Stmt::Error(_) => {}
+    }
+}
+
+/// The members of tags are in the tag table, so only initializers and
+/// function bodies have children.
+pub fn walk_declare<V: Visitor + ?Sized>(visitor: &mut V, declare: &Declare) {
+    match declare {
+        Declare::DeclareIdentifier(_, _, _, init) => {
+            if let Some(init) = init {
+                visitor.visit_initializer(init);
+            }
+        }
+        Declare::DeclareFunction { body, .. } => {
+            for stmt in body {
+                visitor.visit_stmt(stmt);
+            }
+        }
+        Declare::DeclareStruct(..)
+        | Declare::DeclareUnion(..)
+        | Declare::DeclareEnum(..)
+        | Declare::DeclareTypedef(..) => {}
+    }
+}
+
+pub fn walk_initializer<V: Visitor + ?Sized>(visitor: &mut V, init: &Initializer) {
+    match init {
+        Initializer::Expr(expr) => visitor.visit_expr(expr),
+        Initializer::List(_, items) => {
+            for (_, init) in items {
+                visitor.visit_initializer(init);
+            }
+        }
+    }
+}
+
+/// The name after `.` or `->` is not an expression of its own, so it is
+/// not visited.
+pub fn walk_expr<V: Visitor + ?Sized>(visitor: &mut V, expr: &Expr) {
+    match expr {
+        Expr::LiteralExpr(..) => {}
+        Expr::UnaryExpr(_, unary) => match unary {
+            Unary::Plus(e)
+            | Unary::Minus(e)
+            | Unary::Bang(e)
+            | Unary::BitNot(e)
+            | Unary::Deref(e)
+            | Unary::AddressOf(e)
+            | Unary::Sizeof(e)
+            | Unary::PreInc(e)
+            | Unary::PreDec(e)
+            | Unary::PostInc(e)
+            | Unary::PostDec(e) => visitor.visit_expr(e),
+        },
+        Expr::BinaryExpr(_, binary) => match binary {
+            Binary::Call(callee, args) => {
+                visitor.visit_expr(callee);
+                for arg in args {
+                    visitor.visit_expr(arg);
+                }
+            }
+            Binary::Member(e, _) | Binary::PtrMember(e, _) => visitor.visit_expr(e),
+            Binary::Plus(l, r)
+            | Binary::Minus(l, r)
+            | Binary::Mul(l, r)
+            | Binary::Div(l, r)
+            | Binary::Mod(l, r)
+            | Binary::Eq(l, r)
+            | Binary::Neq(l, r)
+            | Binary::Gt(l, r)
+            | Binary::Lt(l, r)
+            | Binary::Gte(l, r)
+            | Binary::Lte(l, r)
+            | Binary::And(l, r)
+            | Binary::Or(l, r)
+            | Binary::BitAnd(l, r)
+            | Binary::BitOr(l, r)
+            | Binary::BitXor(l, r)
+            | Binary::LShift(l, r)
+            | Binary::RShift(l, r)
+            | Binary::Index(l, r)
+            | Binary::Comma(l, r) => {
+                visitor.visit_expr(l);
+                visitor.visit_expr(r);
+            }
+        },
+        Expr::TrinaryExpr(_, Trinary::TrinaryExpr(cond, then, otherwise)) => {
+            visitor.visit_expr(cond);
+            visitor.visit_expr(then);
+            visitor.visit_expr(otherwise);
+        }
+        Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => visitor.visit_expr(e),
+        Expr::AssignExpr(_, assign) => {
+            let (target, value) = assign.operands();
+            visitor.visit_expr(target);
+            visitor.visit_expr(value);
+        }
+        Expr::TypeExpr(_, type_expr) => match type_expr {
+            TypeExpr::Cast(_, e) => visitor.visit_expr(e),
+            TypeExpr::CompoundLiteral(_, init) => visitor.visit_initializer(init),
+            TypeExpr::Sizeof(_) | TypeExpr::Alignof(_) => {}
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{
+        ast::expr::{expr::Expr, literal_expr::Literal},
+        parser::parser::Parser,
+    };
+
+    use super::{walk_expr, Visitor};
+
+    // the names an expression reads or writes
+    struct Names(Vec<String>);
+
+    impl Visitor for Names {
+        fn visit_expr(&mut self, expr: &Expr) {
+            if let Expr::LiteralExpr(_, Literal::ID(name)) = expr {
+                self.0.push(name.clone());
+            }
+            walk_expr(self, expr)
+        }
+    }
+
+    #[test]
+    pub fn test_visit() {
+        let src = "int a = b + f(c), d[] = {e, (int[]){g}};
+            void h(void) { for (i = 0; i < n; i++) s.x = p->y ? -q : sizeof(int); }";
+        let unit = Parser::new(src).parse_unit().unwrap();
+        let mut names = Names(Vec::new());
+        names.visit_unit(&unit);
+        assert_eq!(
+            names.0,
+            vec!["b", "f", "c", "e", "g", "i", "i", "n", "i", "s", "p", "q"]
+        );
+    }
+}
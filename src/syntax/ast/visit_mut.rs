@@ -0,0 +1,243 @@
+use super::{
+    ast_module::CompilationUnit,
+    expr::{
+        binary_expr::Binary, expr::Expr, group_expr::GroupExpr, trinary_expr::Trinary,
+        type_expr::TypeExpr, unary_expr::Unary,
+    },
+    stmt::{declare_stmt::Declare, initializer::Initializer, stmt::Stmt},
+};
+
+/// Walks a tree by mutable reference, like `Visitor`, for passes that
+/// rewrite nodes in place.
+pub trait VisitorMut {
+    fn visit_unit_mut(&mut self, unit: &mut CompilationUnit) {
+        walk_unit_mut(self, unit)
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        walk_stmt_mut(self, stmt)
+    }
+
+    fn visit_declare_mut(&mut self, declare: &mut Declare) {
+        walk_declare_mut(self, declare)
+    }
+
+    fn visit_initializer_mut(&mut self, init: &mut Initializer) {
+        walk_initializer_mut(self, init)
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr)
+    }
+}
+
+pub fn walk_unit_mut<V: VisitorMut + ?Sized>(visitor: &mut V, unit: &mut CompilationUnit) {
+    for stmt in &mut unit.stmts {
+        visitor.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn walk_stmt_mut<V: VisitorMut + ?Sized>(visitor: &mut V, stmt: &mut Stmt) {
+    match stmt {
+        Stmt::Expr(_, expr) => visitor.visit_expr_mut(expr),
+        Stmt::Return(_, expr) => {
+            if let Some(expr) = expr {
+                visitor.visit_expr_mut(expr);
+            }
+        }
+        Stmt::Block(_, stmts) => {
+            for stmt in stmts {
+                visitor.visit_stmt_mut(stmt);
+            }
+        }
+        Stmt::Declare(declare) => visitor.visit_declare_mut(declare),
+        Stmt::If(_, cond, then, otherwise) => {
+            visitor.visit_expr_mut(cond);
+            visitor.visit_stmt_mut(then);
+            if let Some(otherwise) = otherwise {
+                visitor.visit_stmt_mut(otherwise);
+            }
+        }
+        Stmt::While(_, cond, body) | Stmt::Switch(_, cond, body) | Stmt::Case(_, cond, body) => {
+            visitor.visit_expr_mut(cond);
+            visitor.visit_stmt_mut(body);
+        }
+        Stmt::DoWhile(_, body, cond) => {
+            visitor.visit_stmt_mut(body);
+            visitor.visit_expr_mut(cond);
+        }
+        Stmt::For(_, init, cond, step, body) => {
+            for stmt in init {
+                visitor.visit_stmt_mut(stmt);
+            }
+            if let Some(cond) = cond {
+                visitor.visit_expr_mut(cond);
+            }
+            if let Some(step) = step {
+                visitor.visit_expr_mut(step);
+            }
+            visitor.visit_stmt_mut(body);
+        }
+        Stmt::Default(_, stmt) | Stmt::Label(_, _, stmt) => visitor.visit_stmt_mut(stmt),
+        Stmt::Directive(..)
+        | Stmt::Break(_)
+        | Stmt::Continue(_)
+        | Stmt::Goto(..)
+        | Stmt::Empty(_)
+        | Stmt::Error(_) => {}
+    }
+}
+
+pub fn walk_declare_mut<V: VisitorMut + ?Sized>(visitor: &mut V, declare: &mut Declare) {
+    match declare {
+        Declare::DeclareIdentifier(_, _, _, init) => {
+            if let Some(init) = init {
+                visitor.visit_initializer_mut(init);
+            }
+        }
+        Declare::DeclareFunction { body, .. } => {
+            for stmt in body {
+                visitor.visit_stmt_mut(stmt);
+            }
+        }
+        Declare::DeclareStruct(..)
+        | Declare::DeclareUnion(..)
+        | Declare::DeclareEnum(..)
+        | Declare::DeclareTypedef(..) => {}
+    }
+}
+
+pub fn walk_initializer_mut<V: VisitorMut + ?Sized>(visitor: &mut V, init: &mut Initializer) {
+    match init {
+        Initializer::Expr(expr) => visitor.visit_expr_mut(expr),
+        Initializer::List(_, items) => {
+            for (_, init) in items {
+                visitor.visit_initializer_mut(init);
+            }
+        }
+    }
+}
+
+pub fn walk_expr_mut<V: VisitorMut + ?Sized>(visitor: &mut V, expr: &mut Expr) {
+    match expr {
+        Expr::LiteralExpr(..) => {}
+        Expr::UnaryExpr(_, unary) => match unary {
+            Unary::Plus(e)
+            | Unary::Minus(e)
+            | Unary::Bang(e)
+            | Unary::BitNot(e)
+            | Unary::Deref(e)
+            | Unary::AddressOf(e)
+            | Unary::Sizeof(e)
+            | Unary::PreInc(e)
+            | Unary::PreDec(e)
+            | Unary::PostInc(e)
+            | Unary::PostDec(e) => visitor.visit_expr_mut(e),
+        },
+        Expr::BinaryExpr(_, binary) => match binary {
+            Binary::Call(callee, args) => {
+                visitor.visit_expr_mut(callee);
+                for arg in args {
+                    visitor.visit_expr_mut(arg);
+                }
+            }
+            Binary::Member(e, _) | Binary::PtrMember(e, _) => visitor.visit_expr_mut(e),
+            Binary::Plus(l, r)
+            | Binary::Minus(l, r)
+            | Binary::Mul(l, r)
+            | Binary::Div(l, r)
+            | Binary::Mod(l, r)
+            | Binary::Eq(l, r)
+            | Binary::Neq(l, r)
+            | Binary::Gt(l, r)
+            | Binary::Lt(l, r)
+            | Binary::Gte(l, r)
+            | Binary::Lte(l, r)
+            | Binary::And(l, r)
+            | Binary::Or(l, r)
+            | Binary::BitAnd(l, r)
+            | Binary::BitOr(l, r)
+            | Binary::BitXor(l, r)
+            | Binary::LShift(l, r)
+            | Binary::RShift(l, r)
+            | Binary::Index(l, r)
+            | Binary::Comma(l, r) => {
+                visitor.visit_expr_mut(l);
+                visitor.visit_expr_mut(r);
+            }
+        },
+        Expr::TrinaryExpr(_, Trinary::TrinaryExpr(cond, then, otherwise)) => {
+            visitor.visit_expr_mut(cond);
+            visitor.visit_expr_mut(then);
+            visitor.visit_expr_mut(otherwise);
+        }
+        Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => visitor.visit_expr_mut(e),
+        Expr::AssignExpr(_, assign) => {
+            let (target, value) = assign.operands_mut();
+            visitor.visit_expr_mut(target);
+            visitor.visit_expr_mut(value);
+        }
+        Expr::TypeExpr(_, type_expr) => match type_expr {
+            TypeExpr::Cast(_, e) => visitor.visit_expr_mut(e),
+            TypeExpr::CompoundLiteral(_, init) => visitor.visit_initializer_mut(init),
+            TypeExpr::Sizeof(_) | TypeExpr::Alignof(_) => {}
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{
+        ast::{
+            expr::{expr::Expr, literal_expr::Literal},
+            stmt::{declare_stmt::Declare, stmt::Stmt},
+        },
+        parser::parser::Parser,
+    };
+
+    use super::{walk_declare_mut, walk_expr_mut, VisitorMut};
+
+    struct Rename<'a>(&'a str, &'a str);
+
+    impl VisitorMut for Rename<'_> {
+        fn visit_declare_mut(&mut self, declare: &mut Declare) {
+            if let Declare::DeclareIdentifier(_, name, ..) = declare {
+                if name == self.0 {
+                    *name = self.1.to_string();
+                }
+            }
+            walk_declare_mut(self, declare)
+        }
+
+        fn visit_expr_mut(&mut self, expr: &mut Expr) {
+            if let Expr::LiteralExpr(_, Literal::ID(name)) = expr {
+                if name == self.0 {
+                    *name = self.1.to_string();
+                }
+            }
+            walk_expr_mut(self, expr)
+        }
+    }
+
+    #[test]
+    pub fn test_visit_mut() {
+        let src = "void f(int n) { int a = n; while (a) a = a - g(a); }";
+        let mut unit = Parser::new(src).parse_unit().unwrap();
+        Rename("a", "b").visit_unit_mut(&mut unit);
+        let body = match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => body,
+            stmt => panic!("unexpected {:?}", stmt),
+        };
+        match (&body[0], &body[1]) {
+            (
+                Stmt::Declare(Declare::DeclareIdentifier(_, name, ..)),
+                Stmt::While(_, cond, stmt),
+            ) => {
+                assert_eq!(name, "b");
+                assert_eq!(cond.to_string(), "b");
+                assert_eq!(stmt.to_string(), "b = b - g(b);");
+            }
+            stmts => panic!("unexpected {:?}", stmts),
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt;
+
+use crate::syntax::{
+    source::source_map::SourceMap,
+    tokenizer::{attr::Attr, error::LexError},
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// A message about the source at `attr`, reported by any stage after the
+/// tokenizer.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub attr: Attr,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, attr: Attr, message: String) -> Diagnostic {
+        Diagnostic {
+            severity,
+            attr,
+            message,
+        }
+    }
+
+    pub fn error(attr: Attr, message: String) -> Diagnostic {
+        Diagnostic::new(Severity::Error, attr, message)
+    }
+
+    pub fn warning(attr: Attr, message: String) -> Diagnostic {
+        Diagnostic::new(Severity::Warning, attr, message)
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Formats the diagnostic as `file:row:col: severity: message`, then
+    /// walks the macro expansions its location came through, innermost
+    /// first, with a note at each invocation.
+    pub fn render(&self, source_map: &SourceMap) -> String {
+        let mut text = format!("{}: {}\n", source_map.location(&self.attr), self);
+        for expansion in source_map.expansion_chain(&self.attr) {
+            let (file, pos) = source_map.presumed(&expansion.definition);
+            text.push_str(&format!(
+                "{}: {}: in expansion of macro '{}' defined at {}:{}\n",
+                source_map.location(&expansion.invocation),
+                Severity::Note,
+                expansion.name,
+                file,
+                pos.row
+            ));
+        }
+        text
+    }
+}
+
+impl From<LexError> for Diagnostic {
+    fn from(error: LexError) -> Diagnostic {
+        let severity = if error.kind.is_warning() {
+            Severity::Warning
+        } else {
+            Severity::Error
+        };
+        Diagnostic::new(severity, error.attr, error.kind.to_string())
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{
+        preprocessor::preprocessor::Preprocessor,
+        source::expansion::ExpansionId,
+        tokenizer::{attr::Attr, token::Token},
+    };
+
+    use super::Diagnostic;
+
+    #[test]
+    pub fn test_render() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("a.c", "x;\n");
+        let attr = tokens[0].attr().unwrap().clone();
+        let diagnostic = Diagnostic::error(attr, String::from("bad"));
+        assert_eq!(diagnostic.render(pp.source_map()), "a.c:1:1: error: bad\n");
+    }
+
+    #[test]
+    pub fn test_render_expansions() {
+        let mut pp = Preprocessor::new();
+        pp.preprocess_str("a.c", "#define B 1/0\n#define A B\n#if A\n#endif\n");
+        assert_eq!(
+            pp.diagnostics()[0].render(pp.source_map()),
+            "a.c:1:13: error: division by zero in #if\n\
+             a.c:2:11: note: in expansion of macro 'B' defined at a.c:1\n\
+             a.c:3:5: note: in expansion of macro 'A' defined at a.c:2\n"
+        );
+    }
+
+    #[test]
+    pub fn test_arguments_are_not_in_the_expansion() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("a.c", "#define F(x) (x)\nF(y)\n");
+        let chains: Vec<usize> = tokens
+            .iter()
+            .filter_map(Token::attr)
+            .map(|a: &Attr| pp.source_map().expansion_chain(a).len())
+            .collect();
+        assert_eq!(chains, [1, 0, 1]);
+        let render = Diagnostic::error(tokens[0].attr().unwrap().clone(), String::from("e"))
+            .render(pp.source_map());
+        assert_eq!(
+            render,
+            "a.c:1:14: error: e\na.c:2:1: note: in expansion of macro 'F' defined at a.c:1\n"
+        );
+    }
+
+    #[test]
+    pub fn test_bare_function_like_name_is_not_an_expansion() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("a.c", "#define f(x) x\n#define g 1\nf g\n");
+        let chain = pp.source_map().expansion_chain(tokens[1].attr().unwrap());
+        assert_eq!(chain.len(), 1);
+        assert_eq!(pp.source_map().expansion(ExpansionId(0)).name, "g");
+    }
+}
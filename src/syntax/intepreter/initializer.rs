@@ -0,0 +1,360 @@
+use std::collections::VecDeque;
+
+use crate::syntax::{
+    ast::stmt::initializer::Designator,
+    typing::{
+        tag::{Field, TagId, TagTable},
+        types::Type,
+    },
+};
+
+use super::value::value::Value;
+
+/// An initializer whose expressions are evaluated, in the order they
+/// appear.
+#[derive(Debug)]
+pub enum Init {
+    Value(Value),
+    List(Vec<(Vec<Designator>, Init)>),
+}
+
+type Items = VecDeque<(Vec<Designator>, Init)>;
+
+fn is_aggregate(typ: &Type) -> bool {
+    matches!(typ, Type::Array(..) | Type::Struct(_) | Type::Union(_))
+}
+
+fn is_char_array(typ: &Type) -> bool {
+    matches!(typ, Type::Array(elem, _) if matches!(**elem, Type::Char | Type::UChar))
+}
+
+fn is_integer(typ: &Type) -> bool {
+    matches!(zero_scalar(typ), Some(Value::Int(_)))
+}
+
+/// Unnamed bit-fields are skipped by initializers.
+fn is_initialized(field: &Field) -> bool {
+    field.name.is_some() || field.bits.is_none()
+}
+
+fn zero_scalar(typ: &Type) -> Option<Value> {
+    match typ {
+        Type::Int
+        | Type::UInt
+        | Type::Short
+        | Type::UShort
+        | Type::Long
+        | Type::ULong
+        | Type::LongLong
+        | Type::ULongLong
+        | Type::Char
+        | Type::UChar
+        | Type::Enum(_) => Some(Value::Int(0)),
+        Type::Float | Type::Double | Type::LongDouble => Some(Value::Float(0.0)),
+        Type::Bool => Some(Value::Bool(false)),
+        _ => None,
+    }
+}
+
+/// The value of a `typ` that is zero all the way down, which is what the
+/// members an initializer leaves out hold. Pointers are `Null`.
+pub fn zero(typ: &Type, tags: &TagTable) -> Value {
+    match typ {
+        Type::Array(elem, size) => {
+            Value::Array((0..size.unwrap_or(0)).map(|_| zero(elem, tags)).collect())
+        }
+        Type::Struct(id) => Value::Struct(
+            tags.tag(*id)
+                .fields
+                .iter()
+                .map(|field| zero(&field.typ, tags))
+                .collect(),
+        ),
+        Type::Union(id) => union_member(*id, 0, tags),
+        typ => zero_scalar(typ).unwrap_or(Value::Null),
+    }
+}
+
+pub fn union_member(id: TagId, i: usize, tags: &TagTable) -> Value {
+    match tags.tag(id).fields.get(i) {
+        Some(field) => Value::Union(i, Box::new(zero(&field.typ, tags))),
+        None => Value::Union(0, Box::new(Value::Null)),
+    }
+}
+
+/// The value a declaration of a `typ` starts with.
+pub fn initialize(typ: &Type, init: Init, tags: &TagTable) -> Value {
+    let mut value = zero(typ, tags);
+    match init {
+        Init::List(items) => braced(typ, &mut value, items, tags),
+        Init::Value(v) => assign(typ, &mut value, v),
+    }
+    value
+}
+
+/// Initializes `value` from the list in its braces.
+fn braced(typ: &Type, value: &mut Value, items: Vec<(Vec<Designator>, Init)>, tags: &TagTable) {
+    let mut items: Items = items.into();
+    // char s[] = {"abc"}
+    if let [(designators, Init::Value(Value::String(_)))] = items.make_contiguous() {
+        if designators.is_empty() && is_char_array(typ) {
+            let (_, init) = items.pop_front().unwrap();
+            member(typ, value, init, &mut items, tags);
+        }
+    }
+    if is_aggregate(typ) {
+        fill(typ, value, &mut items, 0, true, tags);
+    } else if let Some((designators, init)) = items.pop_front() {
+        if let Some(designator) = designators.first() {
+            panic!("designator '{}' in the initializer of a scalar", designator);
+        }
+        member(typ, value, init, &mut items, tags);
+    }
+    if !items.is_empty() {
+        panic!("excess elements in initializer");
+    }
+}
+
+/// Initializes the members of an aggregate `value` from `start` on with
+/// the front of `items`. Those are the list in its braces, or the list
+/// of an enclosing aggregate when its braces are left out, in which
+/// case it stops at a designator, which is for the enclosing one.
+fn fill(
+    typ: &Type,
+    value: &mut Value,
+    items: &mut Items,
+    start: usize,
+    braced: bool,
+    tags: &TagTable,
+) {
+    let mut i = start;
+    while let Some((designators, _)) = items.front() {
+        if !designators.is_empty() {
+            if !braced {
+                return;
+            }
+            let (designators, init) = items.pop_front().unwrap();
+            i = designate(typ, value, &designators, init, items, tags) + 1;
+            continue;
+        }
+        if let Type::Struct(id) = typ {
+            let fields = &tags.tag(*id).fields;
+            while i < fields.len() && !is_initialized(&fields[i]) {
+                i += 1;
+            }
+        }
+        if i >= members(typ, tags) {
+            return;
+        }
+        let (_, init) = items.pop_front().unwrap();
+        let (typ, slot) = member_mut(typ, value, i, tags);
+        member(&typ, slot, init, items, tags);
+        i += 1;
+    }
+}
+
+/// Initializes the member of `value` that `designators` pick with
+/// `init`, and the members after it with the items that follow. Returns
+/// the member the first designator picks.
+fn designate(
+    typ: &Type,
+    value: &mut Value,
+    designators: &[Designator],
+    init: Init,
+    items: &mut Items,
+    tags: &TagTable,
+) -> usize {
+    let (first, rest) = designators.split_first().unwrap();
+    let path = match (typ, first) {
+        (Type::Array(_, size), Designator::Index(i)) => {
+            if *i as usize >= size.unwrap_or(0) {
+                panic!("array index {} in initializer exceeds array bounds", i);
+            }
+            vec![*i as usize]
+        }
+        (Type::Struct(id) | Type::Union(id), Designator::Field(name)) => {
+            field_path(*id, name, tags).unwrap_or_else(|| {
+                panic!(
+                    "no member named '{}' in '{}'",
+                    name,
+                    tags.tag(*id).spelling()
+                )
+            })
+        }
+        (_, designator) => panic!("designator '{}' does not fit the type", designator),
+    };
+    designate_path(typ, value, &path, rest, init, items, tags)
+}
+
+/// Like `designate`, with the first designator as the members it goes
+/// through, which are more than one for a member of an anonymous struct.
+fn designate_path(
+    typ: &Type,
+    value: &mut Value,
+    path: &[usize],
+    rest: &[Designator],
+    init: Init,
+    items: &mut Items,
+    tags: &TagTable,
+) -> usize {
+    let (i, deeper) = path.split_first().unwrap();
+    let (typ, slot) = member_mut(typ, value, *i, tags);
+    // the items after a designated member go on from inside the
+    // aggregate it is in
+    if !deeper.is_empty() {
+        let j = designate_path(&typ, slot, deeper, rest, init, items, tags);
+        fill(&typ, slot, items, j + 1, false, tags);
+    } else if !rest.is_empty() {
+        let j = designate(&typ, slot, rest, init, items, tags);
+        fill(&typ, slot, items, j + 1, false, tags);
+    } else {
+        member(&typ, slot, init, items, tags);
+    }
+    *i
+}
+
+/// The members from a struct or union to its member `name`, going
+/// through anonymous members.
+pub fn field_path(id: TagId, name: &str, tags: &TagTable) -> Option<Vec<usize>> {
+    for (i, field) in tags.tag(id).fields.iter().enumerate() {
+        match (&field.name, &field.typ) {
+            (Some(n), _) if n == name => return Some(vec![i]),
+            (None, Type::Struct(inner) | Type::Union(inner)) => {
+                if let Some(mut path) = field_path(*inner, name, tags) {
+                    path.insert(0, i);
+                    return Some(path);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// How many members of an aggregate are initialized without
+/// designators.
+fn members(typ: &Type, tags: &TagTable) -> usize {
+    match typ {
+        Type::Array(_, size) => size.unwrap_or(0),
+        Type::Struct(id) => tags.tag(*id).fields.len(),
+        Type::Union(id) => tags.tag(*id).fields.len().min(1),
+        _ => 0,
+    }
+}
+
+/// The type and value of the `i`th member of an aggregate. Picking
+/// another member of a union makes it the one the union holds.
+fn member_mut<'v>(
+    typ: &Type,
+    value: &'v mut Value,
+    i: usize,
+    tags: &TagTable,
+) -> (Type, &'v mut Value) {
+    if let Type::Union(id) = typ {
+        if !matches!(value, Value::Union(j, _) if *j == i) {
+            *value = union_member(*id, i, tags);
+        }
+    }
+    match (typ, value) {
+        (Type::Array(elem, _), Value::Array(values)) => ((**elem).clone(), &mut values[i]),
+        (Type::Struct(id), Value::Struct(values)) => {
+            (tags.tag(*id).fields[i].typ.clone(), &mut values[i])
+        }
+        (Type::Union(id), Value::Union(_, value)) => {
+            (tags.tag(*id).fields[i].typ.clone(), value.as_mut())
+        }
+        (typ, value) => panic!("{:?} is not a value of {:?}", value, typ),
+    }
+}
+
+/// Initializes a member with an item of a list. A scalar for an
+/// aggregate member starts the list of the member without its braces.
+fn member(typ: &Type, slot: &mut Value, init: Init, items: &mut Items, tags: &TagTable) {
+    match init {
+        Init::List(list) => {
+            *slot = zero(typ, tags);
+            braced(typ, slot, list, tags);
+        }
+        Init::Value(v) => {
+            let whole = matches!(v, Value::Array(_) | Value::Struct(_) | Value::Union(..))
+                || (is_char_array(typ) && matches!(v, Value::String(_)));
+            if is_aggregate(typ) && !whole {
+                items.push_front((vec![], Init::Value(v)));
+                fill(typ, slot, items, 0, false, tags);
+            } else {
+                assign(typ, slot, v);
+            }
+        }
+    }
+}
+
+/// Stores `v` in a `typ`, converting between numbers. A string fills a
+/// `char` array and the rest of it is zero.
+fn assign(typ: &Type, slot: &mut Value, v: Value) {
+    *slot = match (typ, v) {
+        (Type::Array(_, size), Value::String(s)) if is_char_array(typ) => {
+            let bytes = s.as_bytes();
+            let size = size.unwrap_or(0);
+            // the terminating zero is dropped when there is no room
+            if bytes.len() > size {
+                panic!("initializer-string for char array is too long");
+            }
+            let byte = |i| Value::Int(bytes.get(i).copied().unwrap_or(0) as i64);
+            Value::Array((0..size).map(byte).collect())
+        }
+        (typ, v) => convert(typ, v),
+    };
+}
+
+/// `v` as a value of the number type `typ`; other values stay as they
+/// are.
+pub fn convert(typ: &Type, v: Value) -> Value {
+    match (typ, v) {
+        (Type::Bool, Value::Int(i)) => Value::Bool(i != 0),
+        (Type::Float | Type::Double | Type::LongDouble, Value::Int(i)) => Value::Float(i as f64),
+        (typ, Value::Float(f)) if is_integer(typ) => Value::Int(f as i64),
+        (typ, Value::Bool(b)) if is_integer(typ) => Value::Int(b as i64),
+        (_, v) => v,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{
+        intepreter::value::value::Value,
+        typing::{
+            tag::{Field, Tag, TagKind, TagTable},
+            types::Type,
+        },
+    };
+
+    use super::{initialize, zero, Init};
+
+    #[test]
+    pub fn test_zero() {
+        let mut tags = TagTable::new();
+        let mut tag = Tag::new(TagKind::Struct, None);
+        tag.fields
+            .push(Field::new(Some(String::from("b")), Type::Bool, None));
+        tag.fields.push(Field::new(
+            Some(String::from("p")),
+            Type::Pointer(Box::new(Type::Int)),
+            None,
+        ));
+        let id = tags.add(tag);
+
+        let typ = Type::Array(Box::new(Type::Struct(id)), Some(2));
+        let s = Value::Struct(vec![Value::Bool(false), Value::Null]);
+        assert_eq!(zero(&typ, &tags), Value::Array(vec![s.clone(), s]));
+        assert_eq!(zero(&Type::Double, &tags), Value::Float(0.0));
+
+        let init = Init::List(vec![(vec![], Init::Value(Value::Int(1)))]);
+        assert_eq!(
+            initialize(&typ, init, &tags),
+            Value::Array(vec![
+                Value::Struct(vec![Value::Bool(true), Value::Null]),
+                Value::Struct(vec![Value::Bool(false), Value::Null]),
+            ])
+        );
+    }
+}
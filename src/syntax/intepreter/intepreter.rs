@@ -1,165 +1,552 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
 use super::env::local::Local;
+use super::initializer::{self, Init};
+use super::place::{self, Place};
 use super::value::value::Value;
 use crate::syntax::ast::ast_module::CompilationUnit;
+use crate::syntax::ast::expr::assign_expr::Assign;
 use crate::syntax::ast::expr::binary_expr::Binary;
 use crate::syntax::ast::expr::expr::Expr;
 use crate::syntax::ast::expr::group_expr::GroupExpr;
 use crate::syntax::ast::expr::literal_expr::Literal;
 use crate::syntax::ast::expr::trinary_expr::Trinary;
+use crate::syntax::ast::expr::type_expr::TypeExpr;
 use crate::syntax::ast::expr::unary_expr::Unary;
+use crate::syntax::ast::stmt::declare_stmt::Declare;
+use crate::syntax::ast::stmt::initializer::Initializer;
 use crate::syntax::ast::stmt::stmt::Stmt;
 use crate::syntax::intepreter::env::env::Env;
+use crate::syntax::tokenizer::attr::Attr;
+use crate::syntax::typing::tag::{TagId, TagTable};
+use crate::syntax::typing::types::Type;
 
 pub struct Intepreter {
     env: Box<dyn Env>,
+    // the structs and unions of the unit being run
+    tags: TagTable,
+    // the declared types of the variables, which stores convert to
+    types: HashMap<String, Type>,
+    // how many compound literals have been given variables
+    temporaries: usize,
 }
 
 impl Intepreter {
     pub fn new() -> Intepreter {
         Intepreter {
             env: Box::new(Local::new()),
+            tags: TagTable::new(),
+            types: HashMap::new(),
+            temporaries: 0,
         }
     }
 
     pub fn inteprete(&mut self, astModule: CompilationUnit) -> Option<Value> {
+        self.tags = astModule.tags;
+        for stmt in astModule.stmts {
+            self.intepreter_stmt(stmt);
+        }
         Some(Value::Null)
     }
 
     fn intepreter_stmt(&mut self, stmt: Stmt) {
         match stmt {
-            Stmt::Assign(name, e) => {
-                if let Expr::LiteralExpr(Literal::ID(name)) = *name {
-                    let v = self.inteprete_expr(*e);
-                    self.env.set(&name, v)
-                } else {
-                    panic!("the assign left side must be a Identifier")
-                }
+            Stmt::Expr(_, e) => {
+                self.inteprete_expr(*e);
+            }
+            Stmt::Return(_, e) => {}
+            Stmt::Directive(_, directive) => {}
+            Stmt::Block(_, stmts) => {}
+            Stmt::Declare(Declare::DeclareIdentifier(_, name, typ, init)) => {
+                let v = match init {
+                    Some(init) => {
+                        let init = self.evaluate_initializer(init);
+                        initializer::initialize(&typ, init, &self.tags)
+                    }
+                    None => initializer::zero(&typ, &self.tags),
+                };
+                self.env.set(&name, v);
+                self.types.insert(name, typ);
             }
-            Stmt::Expr(e) => {}
-            Stmt::Return(e) => {}
-            Stmt::Directive(directive) => {}
-            Stmt::Block(stmts) => {}
             Stmt::Declare(declare) => {}
+            Stmt::If(..)
+            | Stmt::While(..)
+            | Stmt::DoWhile(..)
+            | Stmt::For(..)
+            | Stmt::Switch(..)
+            | Stmt::Case(..)
+            | Stmt::Default(..)
+            | Stmt::Break(_)
+            | Stmt::Continue(_)
+            | Stmt::Goto(..)
+            | Stmt::Label(..)
+            | Stmt::Empty(_)
+            | Stmt::Error(_) => {}
+        }
+    }
+
+    fn evaluate_initializer(&mut self, init: Initializer) -> Init {
+        match init {
+            Initializer::Expr(e) => Init::Value(self.inteprete_expr(*e)),
+            Initializer::List(_, items) => Init::List(
+                items
+                    .into_iter()
+                    .map(|(designators, init)| (designators, self.evaluate_initializer(init)))
+                    .collect(),
+            ),
         }
     }
 
     fn inteprete_expr(&mut self, expr: Expr) -> Value {
         match expr {
-            Expr::LiteralExpr(Literal::Bool(b)) => Value::Bool(b),
-            Expr::LiteralExpr(Literal::Char(c)) => Value::Int(c.to_digit(10).unwrap() as i64),
-            Expr::LiteralExpr(Literal::Int(i)) => Value::Int(i),
-            Expr::LiteralExpr(Literal::String(s)) => Value::String(s),
-            Expr::LiteralExpr(Literal::ID(name)) => self.env.get(&name),
-            Expr::UnaryExpr(ue) => match ue {
-                Unary::Minus(e) => {
-                    let v = self.inteprete_expr(*e);
-                    if let Value::Int(pv) = v {
-                        Value::Int(-pv)
-                    } else {
-                        panic!("the value must be numberic");
-                    }
-                }
+            Expr::LiteralExpr(_, Literal::Bool(b)) => Value::Bool(b),
+            Expr::LiteralExpr(_, Literal::Char(c)) => Value::Int(c as i64),
+            Expr::LiteralExpr(_, Literal::Int(i)) => Value::Int(i),
+            // unsigned values wrap to the same bits
+            Expr::LiteralExpr(_, Literal::UInt(u)) => Value::Int(u as i64),
+            Expr::LiteralExpr(_, Literal::Float(v) | Literal::LongDouble(v)) => Value::Float(v),
+            Expr::LiteralExpr(_, Literal::Float32(v)) => Value::Float(v as f64),
+            Expr::LiteralExpr(_, Literal::String(s)) => Value::String(s),
+            Expr::LiteralExpr(_, Literal::ID(name)) => self.env.get(&name),
+            Expr::AssignExpr(attr, assign) => self.assign(&attr, assign),
+            expr @ (Expr::UnaryExpr(_, Unary::Deref(_))
+            | Expr::BinaryExpr(
+                _,
+                Binary::Index(..) | Binary::Member(..) | Binary::PtrMember(..),
+            )
+            | Expr::TypeExpr(_, TypeExpr::CompoundLiteral(..))) => {
+                let attr = expr.attr().clone();
+                let (place, _) = self.place(expr, false);
+                self.read(&place, &attr)
+            }
+            Expr::UnaryExpr(_, Unary::AddressOf(e)) => {
+                let (place, _) = self.place(*e, false);
+                Value::Pointer(place)
+            }
+            Expr::BinaryExpr(_, Binary::Comma(l, r)) => {
+                self.inteprete_expr(*l);
+                self.inteprete_expr(*r)
+            }
+            Expr::TypeExpr(_, TypeExpr::Cast(typ, e)) => {
+                let v = self.inteprete_expr(*e);
+                initializer::convert(&typ, v)
+            }
+            Expr::TypeExpr(attr, TypeExpr::Sizeof(typ)) => match typ.size(&self.tags) {
+                Some(size) => Value::Int(size as i64),
+                None => panic!("{}: sizeof an incomplete type", attr.start()),
+            },
+            Expr::TypeExpr(attr, TypeExpr::Alignof(typ)) => match typ.align(&self.tags) {
+                Some(align) => Value::Int(align as i64),
+                None => panic!("{}: _Alignof an incomplete type", attr.start()),
+            },
+            Expr::UnaryExpr(attr, Unary::PreInc(e)) => self.increment(&attr, *e, 1, false),
+            Expr::UnaryExpr(attr, Unary::PreDec(e)) => self.increment(&attr, *e, -1, false),
+            Expr::UnaryExpr(attr, Unary::PostInc(e)) => self.increment(&attr, *e, 1, true),
+            Expr::UnaryExpr(attr, Unary::PostDec(e)) => self.increment(&attr, *e, -1, true),
+            Expr::UnaryExpr(attr, ue) => match ue {
                 Unary::Plus(e) => {
                     let v = self.inteprete_expr(*e);
-                    if let Value::Int(pv) = v {
-                        Value::Int(pv)
-                    } else {
-                        panic!("the value must be numberic");
-                    }
-                }
-                _ => panic!("not supported yet"),
-            },
-            Expr::BinaryExpr(be) => match be {
-                Binary::Plus(e1, e2) => {
-                    let left = self.inteprete_expr(*e1);
-                    let right = self.inteprete_expr(*e2);
-                    if let (Value::Int(vleft), Value::Int(vright)) = (left, right) {
-                        Value::Int(vleft + vright)
-                    } else {
-                        panic!("the left value and right value must be numberic");
-                    }
+                    arithmetic(&attr, "+", Value::Int(0), v)
                 }
-                Binary::Div(e1, e2) => {
-                    let left = self.inteprete_expr(*e1);
-                    let right = self.inteprete_expr(*e2);
-                    if let (Value::Int(vleft), Value::Int(vright)) = (left, right) {
-                        Value::Int(vleft / vright)
-                    } else {
-                        panic!("the left value and right value must be numberic");
-                    }
-                }
-                Binary::Minus(e1, e2) => {
-                    let left = self.inteprete_expr(*e1);
-                    let right = self.inteprete_expr(*e2);
-                    if let (Value::Int(vleft), Value::Int(vright)) = (left, right) {
-                        Value::Int(vleft - vright)
-                    } else {
-                        panic!("the left value and right value must be numberic");
-                    }
+                // `x * -1` keeps the sign of a float zero, unlike `0 - x`
+                Unary::Minus(e) => {
+                    let v = self.inteprete_expr(*e);
+                    arithmetic(&attr, "*", v, Value::Int(-1))
                 }
-                Binary::Mul(e1, e2) => {
-                    let left = self.inteprete_expr(*e1);
-                    let right = self.inteprete_expr(*e2);
-                    if let (Value::Int(vleft), Value::Int(vright)) = (left, right) {
-                        Value::Int(vleft * vright)
-                    } else {
-                        panic!("the left value and right value must be numberic");
+                Unary::BitNot(e) => match self.inteprete_expr(*e) {
+                    Value::Float(_) => {
+                        panic!("{}: the operand of '~' must be an integer", attr.start())
                     }
+                    v => arithmetic(&attr, "^", v, Value::Int(-1)),
+                },
+                Unary::Bang(e) => {
+                    let v = self.operand(*e);
+                    Value::Int(!truth(&attr, v) as i64)
                 }
-                _ => panic!("not supported yet"),
+                _ => panic!("{}: not supported yet", attr.start()),
             },
-            Expr::GroupExpr(ge) => match ge {
-                GroupExpr::GroupExpr(e) => {
-                    let v = self.inteprete_expr(*e);
-                    if let Value::Int(pv) = v {
-                        Value::Int(pv)
+            // the right operand only runs when the left one does not decide
+            Expr::BinaryExpr(attr, Binary::And(l, r)) => {
+                let l = self.operand(*l);
+                Value::Int((truth(&attr, l) && truth(&attr, self.operand(*r))) as i64)
+            }
+            Expr::BinaryExpr(attr, Binary::Or(l, r)) => {
+                let l = self.operand(*l);
+                Value::Int((truth(&attr, l) || truth(&attr, self.operand(*r))) as i64)
+            }
+            Expr::BinaryExpr(attr, be) => {
+                let (op, e1, e2) = match be {
+                    Binary::Plus(l, r) => ("+", l, r),
+                    Binary::Minus(l, r) => ("-", l, r),
+                    Binary::Mul(l, r) => ("*", l, r),
+                    Binary::Div(l, r) => ("/", l, r),
+                    Binary::Mod(l, r) => ("%", l, r),
+                    Binary::Eq(l, r) => ("==", l, r),
+                    Binary::Neq(l, r) => ("!=", l, r),
+                    Binary::Gt(l, r) => (">", l, r),
+                    Binary::Lt(l, r) => ("<", l, r),
+                    Binary::Gte(l, r) => (">=", l, r),
+                    Binary::Lte(l, r) => ("<=", l, r),
+                    Binary::BitAnd(l, r) => ("&", l, r),
+                    Binary::BitOr(l, r) => ("|", l, r),
+                    Binary::BitXor(l, r) => ("^", l, r),
+                    Binary::LShift(l, r) => ("<<", l, r),
+                    Binary::RShift(l, r) => (">>", l, r),
+                    _ => panic!("{}: not supported yet", attr.start()),
+                };
+                let left = self.operand(*e1);
+                let right = self.operand(*e2);
+                arithmetic(&attr, op, left, right)
+            }
+            Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => self.inteprete_expr(*e),
+            Expr::TrinaryExpr(attr, te) => match te {
+                // only the arm the condition picks is evaluated
+                Trinary::TrinaryExpr(cond, left, right) => {
+                    let cond = self.operand(*cond);
+                    if truth(&attr, cond) {
+                        self.inteprete_expr(*left)
                     } else {
-                        panic!("the value must be numberic")
+                        self.inteprete_expr(*right)
                     }
                 }
             },
-            Expr::TrinaryExpr(te) => match te {
-                Trinary::TrinaryExpr(cond, left, right) => {
-                    let cond = self.inteprete_expr(*cond);
-                    let left = self.inteprete_expr(*left);
-                    let right = self.inteprete_expr(*right);
-                    if let (Value::Bool(b), Value::Int(vleft), Value::Int(vright)) =
-                        (cond, left, right)
-                    {
-                        if b {
-                            Value::Int(vleft)
-                        } else {
-                            Value::Int(vright)
-                        }
-                    } else {
-                        panic!("the left value and right value must be numberic")
+        }
+    }
+
+    /// The value of an operand of an arithmetic operator, where an array
+    /// is a pointer to its first element.
+    fn operand(&mut self, expr: Expr) -> Value {
+        if !expr.is_lvalue() {
+            return self.inteprete_expr(expr);
+        }
+        let attr = expr.attr().clone();
+        match self.place(expr, false) {
+            (place, Some(Type::Array(..))) => Value::Pointer(place.at(0)),
+            (place, _) => self.read(&place, &attr),
+        }
+    }
+
+    /// Stores the value in the target and evaluates to what it stored.
+    fn assign(&mut self, attr: &Attr, assign: Assign) -> Value {
+        // `+=` is `+` and then a store
+        let op = assign.op().trim_end_matches('=');
+        let (target, value) = assign.into_operands();
+        let value = self.inteprete_expr(*value);
+        let (place, typ) = self.place(*target, true);
+        let value = match op {
+            "" => value,
+            op => arithmetic(attr, op, self.read(&place, attr), value),
+        };
+        self.store(&place, typ, value, attr)
+    }
+
+    /// `++e` and `--e` evaluate to the new value, `e++` and `e--` to the
+    /// old one. A pointer moves to the next or the previous element.
+    fn increment(&mut self, attr: &Attr, expr: Expr, delta: i64, post: bool) -> Value {
+        let (place, typ) = self.place(expr, true);
+        let old = self.read(&place, attr);
+        let new = arithmetic(attr, "+", old.clone(), Value::Int(delta));
+        let new = self.store(&place, typ, new, attr);
+        if post {
+            old
+        } else {
+            new
+        }
+    }
+
+    /// The place an lvalue designates, and its type when it is known.
+    /// When the place is `written`, the union members on the way become
+    /// the ones their unions hold.
+    fn place(&mut self, expr: Expr, written: bool) -> (Place, Option<Type>) {
+        match expr {
+            Expr::LiteralExpr(_, Literal::ID(name)) => {
+                let typ = self.types.get(&name).cloned();
+                (Place::new(&name), typ)
+            }
+            Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => self.place(*e, written),
+            Expr::UnaryExpr(_, Unary::Deref(e)) => self.deref(*e),
+            Expr::TypeExpr(_, TypeExpr::CompoundLiteral(typ, init)) => {
+                // the object is a variable that no name refers to
+                let name = format!("(compound literal {})", self.temporaries);
+                self.temporaries += 1;
+                let init = self.evaluate_initializer(init);
+                let value = initializer::initialize(&typ, init, &self.tags);
+                self.env.set(&name, value);
+                self.types.insert(name.clone(), typ.clone());
+                (Place::new(&name), Some(typ))
+            }
+            Expr::BinaryExpr(attr, Binary::Index(e, index)) => {
+                let index = match self.inteprete_expr(*index) {
+                    Value::Int(i) => i,
+                    v => panic!("{}: the index {:?} must be an integer", attr.start(), v),
+                };
+                let (place, typ) = self.deref(*e);
+                (place.offset(index, &attr), typ)
+            }
+            Expr::BinaryExpr(_, Binary::Member(e, member)) => {
+                let (place, typ) = self.place(*e, written);
+                self.member(place, typ, *member, written)
+            }
+            Expr::BinaryExpr(_, Binary::PtrMember(e, member)) => {
+                let (place, typ) = self.deref(*e);
+                self.member(place, typ, *member, written)
+            }
+            expr => panic!("{}: {} is not assignable", expr.attr().start(), expr),
+        }
+    }
+
+    /// The place a pointer points to, and its type when it is known. An
+    /// array is a pointer to its first element.
+    fn deref(&mut self, expr: Expr) -> (Place, Option<Type>) {
+        let attr = expr.attr().clone();
+        if !expr.is_lvalue() {
+            return match self.inteprete_expr(expr) {
+                Value::Pointer(target) => (target, None),
+                v => panic!("{}: {:?} is not a pointer", attr.start(), v),
+            };
+        }
+        let (place, typ) = self.place(expr, false);
+        match typ {
+            Some(Type::Array(elem, _)) => (place.at(0), Some(*elem)),
+            typ => {
+                let pointee = match typ {
+                    Some(Type::Pointer(pointee)) => Some(*pointee),
+                    _ => None,
+                };
+                match self.read(&place, &attr) {
+                    Value::Pointer(target) => (target, pointee),
+                    Value::Array(_) => (place.at(0), None),
+                    v => panic!("{}: {:?} is not a pointer", attr.start(), v),
+                }
+            }
+        }
+    }
+
+    /// The member `member` of the struct or union at `place`.
+    fn member(
+        &mut self,
+        mut place: Place,
+        typ: Option<Type>,
+        member: Expr,
+        written: bool,
+    ) -> (Place, Option<Type>) {
+        let (attr, name) = match member {
+            Expr::LiteralExpr(attr, Literal::ID(name)) => (attr, name),
+            expr => panic!("{}: {} is not a member name", expr.attr().start(), expr),
+        };
+        let mut typ = match typ {
+            Some(typ @ (Type::Struct(_) | Type::Union(_))) => typ,
+            _ => panic!(
+                "{}: the member '{}' of a value that is not a struct or union",
+                attr.start(),
+                name
+            ),
+        };
+        let id = match typ {
+            Type::Struct(id) | Type::Union(id) => id,
+            _ => unreachable!(),
+        };
+        let path = initializer::field_path(id, &name, &self.tags).unwrap_or_else(|| {
+            panic!(
+                "{}: no member named '{}' in '{}'",
+                attr.start(),
+                name,
+                self.tags.tag(id).spelling()
+            )
+        });
+        for i in path {
+            let id = match typ {
+                Type::Struct(id) => id,
+                Type::Union(id) => {
+                    if written {
+                        self.activate(&place, id, i, &attr);
                     }
+                    id
                 }
-            },
+                _ => unreachable!(),
+            };
+            typ = self.tags.tag(id).fields[i].typ.clone();
+            place = place.at(i);
+        }
+        (place, Some(typ))
+    }
+
+    /// Makes the `i`th member the one the union at `place` holds, zero if
+    /// it did not hold it.
+    fn activate(&mut self, place: &Place, id: TagId, i: usize, attr: &Attr) {
+        let mut root = self.env.get(&place.name);
+        let value = place::get_mut(&mut root, &place.path, attr);
+        if !matches!(value, Value::Union(j, _) if *j == i) {
+            *value = initializer::union_member(id, i, &self.tags);
+            self.env.set(&place.name, root);
+        }
+    }
+
+    /// The value at `place`, read by the expression at `attr`.
+    fn read(&self, place: &Place, attr: &Attr) -> Value {
+        place::get(&self.env.get(&place.name), &place.path, attr).clone()
+    }
+
+    fn write(&mut self, place: &Place, v: Value, attr: &Attr) {
+        let mut root = self.env.get(&place.name);
+        *place::get_mut(&mut root, &place.path, attr) = v;
+        self.env.set(&place.name, root);
+    }
+
+    /// Writes `v` converted to the type of the place, and returns what it
+    /// wrote.
+    fn store(&mut self, place: &Place, typ: Option<Type>, v: Value, attr: &Attr) -> Value {
+        let v = match typ {
+            Some(typ) => initializer::convert(&typ, v),
+            None => v,
+        };
+        self.write(place, v.clone(), attr);
+        v
+    }
+}
+
+/// Whether a condition holds: a nonzero number or a pointer that is not
+/// null.
+fn truth(attr: &Attr, v: Value) -> bool {
+    match v {
+        Value::Bool(b) => b,
+        Value::Int(i) => i != 0,
+        Value::Float(f) => f != 0.0,
+        Value::Pointer(_) => true,
+        Value::Null => false,
+        v => panic!("{}: {:?} is not a scalar", attr.start(), v),
+    }
+}
+
+/// A number as a float, for the expression at `attr`.
+fn float(attr: &Attr, v: Value) -> f64 {
+    match v {
+        Value::Int(i) => i as f64,
+        Value::Float(f) => f,
+        v => panic!("{}: {:?} is not a number", attr.start(), v),
+    }
+}
+
+/// `l op r` for the relational and equality operators, which give 1 or
+/// 0. Pointers into the same object compare by their places, and are
+/// never equal to a null pointer.
+fn compare(attr: &Attr, op: &str, l: Value, r: Value) -> Value {
+    let ordering = match (l, r) {
+        (Value::Int(l), Value::Int(r)) => l.partial_cmp(&r),
+        (Value::Pointer(l), Value::Pointer(r)) => l.partial_cmp(&r),
+        (Value::Null, Value::Null | Value::Int(0)) | (Value::Int(0), Value::Null) => {
+            Some(Ordering::Equal)
+        }
+        (Value::Pointer(_), Value::Null | Value::Int(0))
+        | (Value::Null | Value::Int(0), Value::Pointer(_)) => None,
+        (l, r) => float(attr, l).partial_cmp(&float(attr, r)),
+    };
+    let holds = match op {
+        "==" => ordering == Some(Ordering::Equal),
+        "!=" => ordering != Some(Ordering::Equal),
+        "<" => ordering == Some(Ordering::Less),
+        ">" => ordering == Some(Ordering::Greater),
+        "<=" => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        ">=" => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+        op => panic!("{}: unknown operator '{}'", attr.start(), op),
+    };
+    Value::Int(holds as i64)
+}
+
+/// `l op r` for the arithmetic, bitwise, relational and equality
+/// operators of the expression at `attr`. Integers wrap around, and a
+/// pointer moves by `r` elements for `+` and `-`.
+fn arithmetic(attr: &Attr, op: &str, l: Value, r: Value) -> Value {
+    let int = |v| match v {
+        Value::Bool(b) => Value::Int(b as i64),
+        v => v,
+    };
+    if let "==" | "!=" | "<" | ">" | "<=" | ">=" = op {
+        return compare(attr, op, int(l), int(r));
+    }
+    match (int(l), int(r)) {
+        (Value::Int(l), Value::Int(r)) => Value::Int(match op {
+            "+" => l.wrapping_add(r),
+            "-" => l.wrapping_sub(r),
+            "*" => l.wrapping_mul(r),
+            "/" | "%" if r == 0 => panic!("{}: division by zero", attr.start()),
+            "/" => l.wrapping_div(r),
+            "%" => l.wrapping_rem(r),
+            "&" => l & r,
+            "|" => l | r,
+            "^" => l ^ r,
+            "<<" => l.wrapping_shl(r as u32),
+            ">>" => l.wrapping_shr(r as u32),
+            op => panic!("{}: unknown operator '{}'", attr.start(), op),
+        }),
+        (Value::Pointer(place), Value::Int(n)) | (Value::Int(n), Value::Pointer(place))
+            if op == "+" =>
+        {
+            Value::Pointer(place.offset(n, attr))
+        }
+        (Value::Pointer(place), Value::Int(n)) if op == "-" => {
+            Value::Pointer(place.offset(-n, attr))
+        }
+        (l, r) => {
+            let (l, r) = (float(attr, l), float(attr, r));
+            Value::Float(match op {
+                "+" => l + r,
+                "-" => l - r,
+                "*" => l * r,
+                "/" => l / r,
+                op => panic!(
+                    "{}: the operands of '{}' must be integers",
+                    attr.start(),
+                    op
+                ),
+            })
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::syntax::tokenizer::attr::Attr;
     use crate::syntax::{
         ast::{
             ast_module::CompilationUnit,
             expr::{
-                binary_expr::Binary, expr::Expr, group_expr::GroupExpr, literal_expr::Literal,
-                trinary_expr::Trinary, unary_expr::Unary,
+                assign_expr::Assign, binary_expr::Binary, expr::Expr, group_expr::GroupExpr,
+                literal_expr::Literal, trinary_expr::Trinary, unary_expr::Unary,
             },
             stmt::stmt::Stmt,
         },
-        intepreter::value::value::Value,
+        intepreter::{place::Place, value::value::Value},
+        parser::parser::Parser,
+        typing::tag::TagTable,
     };
 
     use super::Intepreter;
 
+    fn run(src: &str) -> Intepreter {
+        let unit = Parser::new(src).parse_unit().unwrap();
+        let mut intepreter = Intepreter::new();
+        intepreter.inteprete(unit);
+        intepreter
+    }
+
+    fn eval(intepreter: &mut Intepreter, src: &str) -> Value {
+        let expr = Parser::new(src).parse_expr().unwrap();
+        intepreter.inteprete_expr(expr)
+    }
+
+    fn ints(values: &[i64]) -> Value {
+        Value::Array(values.iter().map(|v| Value::Int(*v)).collect())
+    }
+
     #[test]
     pub fn test_intepreter() {
         let mut intepreter = Intepreter::new();
-        if let Some(value) = intepreter.inteprete(CompilationUnit { stmts: Vec::new() }) {
+        if let Some(value) = intepreter.inteprete(CompilationUnit {
+            stmts: Vec::new(),
+            tags: TagTable::new(),
+        }) {
             assert_eq!(value, Value::Null);
         }
     }
@@ -170,7 +557,7 @@ mod tests {
 
         {
             let expect_value = 3;
-            let e = Expr::LiteralExpr(Literal::Int(expect_value));
+            let e = Expr::LiteralExpr(Attr::new(), Literal::Int(expect_value));
             let v = intepreter.inteprete_expr(e);
             if let Value::Int(actual_value) = v {
                 assert_eq!(actual_value, expect_value);
@@ -179,8 +566,8 @@ mod tests {
 
         {
             let expect_value: i64 = -3;
-            let left = Expr::LiteralExpr(Literal::Int(3));
-            let e = Expr::UnaryExpr(Unary::Minus(Box::new(left)));
+            let left = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
+            let e = Expr::UnaryExpr(Attr::new(), Unary::Minus(Box::new(left)));
             let v = intepreter.inteprete_expr(e);
             if let Value::Int(actual_value) = v {
                 assert_eq!(actual_value, expect_value);
@@ -189,9 +576,9 @@ mod tests {
 
         {
             let expect_value: i64 = 8;
-            let left = Expr::LiteralExpr(Literal::Int(2));
-            let right = Expr::LiteralExpr(Literal::Int(4));
-            let e = Expr::BinaryExpr(Binary::Mul(Box::new(left), Box::new(right)));
+            let left = Expr::LiteralExpr(Attr::new(), Literal::Int(2));
+            let right = Expr::LiteralExpr(Attr::new(), Literal::Int(4));
+            let e = Expr::BinaryExpr(Attr::new(), Binary::Mul(Box::new(left), Box::new(right)));
             let v = intepreter.inteprete_expr(e);
             if let Value::Int(actual_value) = v {
                 assert_eq!(actual_value, expect_value);
@@ -200,8 +587,8 @@ mod tests {
 
         {
             let expect_value = 3;
-            let e1 = Expr::LiteralExpr(Literal::Int(expect_value));
-            let e = Expr::GroupExpr(GroupExpr::GroupExpr(Box::new(e1)));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(expect_value));
+            let e = Expr::GroupExpr(Attr::new(), GroupExpr::GroupExpr(Box::new(e1)));
             let v = intepreter.inteprete_expr(e);
             if let Value::Int(actual_value) = v {
                 assert_eq!(actual_value, expect_value);
@@ -209,14 +596,13 @@ mod tests {
         }
 
         {
-            let cond = Expr::LiteralExpr(Literal::Bool(true));
-            let e1 = Expr::LiteralExpr(Literal::Int(5));
-            let e2 = Expr::LiteralExpr(Literal::Int(3));
-            let e = Expr::TrinaryExpr(Trinary::TrinaryExpr(
-                Box::new(cond),
-                Box::new(e1),
-                Box::new(e2),
-            ));
+            let cond = Expr::LiteralExpr(Attr::new(), Literal::Bool(true));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(5));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
+            let e = Expr::TrinaryExpr(
+                Attr::new(),
+                Trinary::TrinaryExpr(Box::new(cond), Box::new(e1), Box::new(e2)),
+            );
             let v = intepreter.inteprete_expr(e);
             if let Value::Int(actual_value) = v {
                 assert_eq!(actual_value, 5);
@@ -224,14 +610,13 @@ mod tests {
         }
 
         {
-            let cond = Expr::LiteralExpr(Literal::Bool(false));
-            let e1 = Expr::LiteralExpr(Literal::Int(5));
-            let e2 = Expr::LiteralExpr(Literal::Int(3));
-            let e = Expr::TrinaryExpr(Trinary::TrinaryExpr(
-                Box::new(cond),
-                Box::new(e1),
-                Box::new(e2),
-            ));
+            let cond = Expr::LiteralExpr(Attr::new(), Literal::Bool(false));
+            let e1 = Expr::LiteralExpr(Attr::new(), Literal::Int(5));
+            let e2 = Expr::LiteralExpr(Attr::new(), Literal::Int(3));
+            let e = Expr::TrinaryExpr(
+                Attr::new(),
+                Trinary::TrinaryExpr(Box::new(cond), Box::new(e1), Box::new(e2)),
+            );
             let v = intepreter.inteprete_expr(e);
             if let Value::Int(actual_value) = v {
                 assert_eq!(actual_value, 3);
@@ -244,9 +629,18 @@ mod tests {
         let mut intepreter = Intepreter::new();
         {
             let expect_value = 10;
-            let s = Stmt::Assign(
-                Box::new(Expr::LiteralExpr(Literal::ID(String::from("age")))),
-                Box::new(Expr::LiteralExpr(Literal::Int(expect_value))),
+            let s = Stmt::Expr(
+                Attr::new(),
+                Box::new(Expr::AssignExpr(
+                    Attr::new(),
+                    Assign::Assign(
+                        Box::new(Expr::LiteralExpr(
+                            Attr::new(),
+                            Literal::ID(String::from("age")),
+                        )),
+                        Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(expect_value))),
+                    ),
+                )),
             );
             intepreter.intepreter_stmt(s);
             if let Value::Int(actual_value) = intepreter.env.get("age") {
@@ -254,4 +648,249 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    pub fn test_initializers() {
+        let intepreter = run("int a[4] = {1, [2] = 3}; int b[] = {[1] = 2}; int c; double d = 1;");
+        assert_eq!(intepreter.env.get("a"), ints(&[1, 0, 3, 0]));
+        assert_eq!(intepreter.env.get("b"), ints(&[0, 2]));
+        assert_eq!(intepreter.env.get("c"), Value::Int(0));
+        assert_eq!(intepreter.env.get("d"), Value::Float(1.0));
+
+        let intepreter = run("char s[] = \"hi\"; char t[4] = {\"ab\"}; char u[2] = \"ab\";");
+        assert_eq!(intepreter.env.get("s"), ints(&[104, 105, 0]));
+        assert_eq!(intepreter.env.get("t"), ints(&[97, 98, 0, 0]));
+        assert_eq!(intepreter.env.get("u"), ints(&[97, 98]));
+
+        let intepreter = run("char c = 'a'; char v[] = {'h', 'i', 0};");
+        assert_eq!(intepreter.env.get("c"), Value::Int(97));
+        assert_eq!(intepreter.env.get("v"), ints(&[104, 105, 0]));
+
+        let intepreter =
+            run("int m[2][2] = {1, 2, 3}; int n[2][2] = {{1}, [1][1] = 4}; int x = {5};");
+        assert_eq!(
+            intepreter.env.get("m"),
+            Value::Array(vec![ints(&[1, 2]), ints(&[3, 0])])
+        );
+        assert_eq!(
+            intepreter.env.get("n"),
+            Value::Array(vec![ints(&[1, 0]), ints(&[0, 4])])
+        );
+        assert_eq!(intepreter.env.get("x"), Value::Int(5));
+    }
+
+    #[test]
+    pub fn test_aggregate_initializers() {
+        let point = |x, y| Value::Struct(vec![Value::Int(x), Value::Int(y)]);
+        let intepreter = run(
+            "struct p { int x, y; } a = {.y = 2}, b[2] = {[0].y = 1, 2, 3}, c[] = {1, 2, {3}, 4};",
+        );
+        assert_eq!(intepreter.env.get("a"), point(0, 2));
+        assert_eq!(
+            intepreter.env.get("b"),
+            Value::Array(vec![point(0, 1), point(2, 3)])
+        );
+        assert_eq!(
+            intepreter.env.get("c"),
+            Value::Array(vec![point(1, 2), point(3, 0), point(4, 0)])
+        );
+
+        let intepreter = run(
+            "struct q { int a[2]; struct { int b, c; }; int : 3; int d; } q = {{1}, .c = 3, 4};",
+        );
+        assert_eq!(
+            intepreter.env.get("q"),
+            Value::Struct(vec![
+                ints(&[1, 0]),
+                Value::Struct(vec![Value::Int(0), Value::Int(3)]),
+                Value::Int(0),
+                Value::Int(4),
+            ])
+        );
+
+        let intepreter = run("union u { int i; double f; } u = {.f = 1}, v = {2}, w;");
+        assert_eq!(
+            intepreter.env.get("u"),
+            Value::Union(1, Box::new(Value::Float(1.0)))
+        );
+        assert_eq!(
+            intepreter.env.get("v"),
+            Value::Union(0, Box::new(Value::Int(2)))
+        );
+        assert_eq!(
+            intepreter.env.get("w"),
+            Value::Union(0, Box::new(Value::Int(0)))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "excess elements in initializer")]
+    pub fn test_excess_initializers() {
+        run("int a[2] = {1, 2, 3};");
+    }
+
+    #[test]
+    pub fn test_assign() {
+        let intepreter =
+            run("int a[3]; int b; int i = 1; int r = a[i] = b = 5; double d = b /= 2;");
+        assert_eq!(intepreter.env.get("a"), ints(&[0, 5, 0]));
+        assert_eq!(intepreter.env.get("b"), Value::Int(2));
+        assert_eq!(intepreter.env.get("r"), Value::Int(5));
+        assert_eq!(intepreter.env.get("d"), Value::Float(2.0));
+
+        let intepreter = run("int c = 7; int m = c %= 4; int n = c <<= 2; _Bool t = n;");
+        assert_eq!(intepreter.env.get("c"), Value::Int(12));
+        assert_eq!(intepreter.env.get("m"), Value::Int(3));
+        assert_eq!(intepreter.env.get("t"), Value::Bool(true));
+
+        // the plain operators agree with the compound ones
+        let intepreter = run(
+            "int c = 7 % 4 << 2 | 1; double h = 1.5 * 2; int a[3] = {1, 2, 3}; int *p = a + 1;
+             int *q = 1 + p; int r = *(p + 1) ^ 1;",
+        );
+        assert_eq!(intepreter.env.get("c"), Value::Int(13));
+        assert_eq!(intepreter.env.get("h"), Value::Float(3.0));
+        assert_eq!(
+            intepreter.env.get("p"),
+            Value::Pointer(Place::new("a").at(1))
+        );
+        assert_eq!(
+            intepreter.env.get("q"),
+            Value::Pointer(Place::new("a").at(2))
+        );
+        assert_eq!(intepreter.env.get("r"), Value::Int(2));
+
+        let intepreter =
+            run("struct p { int x, y[2]; } s = {1}; int t = s.x += 2; int u = s.y[1] = 4;");
+        assert_eq!(
+            intepreter.env.get("s"),
+            Value::Struct(vec![Value::Int(3), ints(&[0, 4])])
+        );
+        assert_eq!(intepreter.env.get("t"), Value::Int(3));
+        assert_eq!(intepreter.env.get("u"), Value::Int(4));
+
+        let intepreter = run("union u { int i; double f; } u = {1}; double d = u.f = 2;");
+        assert_eq!(
+            intepreter.env.get("u"),
+            Value::Union(1, Box::new(Value::Float(2.0)))
+        );
+        assert_eq!(intepreter.env.get("d"), Value::Float(2.0));
+    }
+
+    #[test]
+    pub fn test_increments() {
+        let intepreter = run("int i = 1; int j = i++; int k = ++i; int l = i--;");
+        assert_eq!(intepreter.env.get("i"), Value::Int(2));
+        assert_eq!(intepreter.env.get("j"), Value::Int(1));
+        assert_eq!(intepreter.env.get("k"), Value::Int(3));
+        assert_eq!(intepreter.env.get("l"), Value::Int(3));
+    }
+
+    #[test]
+    pub fn test_pointers() {
+        let mut intepreter =
+            run("int n = 5; int *p; int a[3]; int *q; struct p { int x; } s, *sp;");
+        intepreter.env.set("p", Value::Pointer(Place::new("n")));
+        intepreter
+            .env
+            .set("q", Value::Pointer(Place::new("a").at(0)));
+        intepreter.env.set("sp", Value::Pointer(Place::new("s")));
+
+        assert_eq!(eval(&mut intepreter, "--*p"), Value::Int(4));
+        assert_eq!(intepreter.env.get("n"), Value::Int(4));
+
+        assert_eq!(eval(&mut intepreter, "*++q = 7"), Value::Int(7));
+        assert_eq!(eval(&mut intepreter, "q[1] = *q + 1"), Value::Int(8));
+        assert_eq!(intepreter.env.get("a"), ints(&[0, 7, 8]));
+
+        assert_eq!(eval(&mut intepreter, "sp->x = 3"), Value::Int(3));
+        assert_eq!(intepreter.env.get("s"), Value::Struct(vec![Value::Int(3)]));
+    }
+
+    #[test]
+    pub fn test_address_of() {
+        let intepreter = run(
+            "int n = 5; int *p = &n; int m = (*p += 1, n * 2); double d = (double)n;
+             int a[3] = {1, 2, 3}; int *q = &a[1]; int r = q[1]; int s = (int[]){7, 8}[1];
+             int *t = &(int){9}; int u = *t;",
+        );
+        assert_eq!(intepreter.env.get("p"), Value::Pointer(Place::new("n")));
+        assert_eq!(intepreter.env.get("n"), Value::Int(6));
+        assert_eq!(intepreter.env.get("m"), Value::Int(12));
+        assert_eq!(intepreter.env.get("d"), Value::Float(6.0));
+        assert_eq!(intepreter.env.get("r"), Value::Int(3));
+        assert_eq!(intepreter.env.get("s"), Value::Int(8));
+        assert_eq!(intepreter.env.get("u"), Value::Int(9));
+    }
+
+    #[test]
+    pub fn test_operators() {
+        let intepreter = run(
+            "double d = -1.5; double z = -0.0; int a = 1 < 2; int b = 2.5 >= 3; int c = +'a';
+             int e = ~5 + !0 + !2.5; int n = 0; int f = n && (n = 1); int g = 2 || (n = 2);
+             int h = 1 == 1.0 && 1.5 != 2; int x[2]; int *p = &x[0]; int i = p < x + 1 && p != 0;",
+        );
+        assert_eq!(intepreter.env.get("d"), Value::Float(-1.5));
+        assert_eq!(intepreter.env.get("z"), Value::Float(-0.0));
+        assert_eq!(intepreter.env.get("a"), Value::Int(1));
+        assert_eq!(intepreter.env.get("b"), Value::Int(0));
+        assert_eq!(intepreter.env.get("c"), Value::Int(97));
+        assert_eq!(intepreter.env.get("e"), Value::Int(-5));
+        // neither right operand ran
+        assert_eq!(intepreter.env.get("n"), Value::Int(0));
+        assert_eq!(intepreter.env.get("f"), Value::Int(0));
+        assert_eq!(intepreter.env.get("g"), Value::Int(1));
+        assert_eq!(intepreter.env.get("h"), Value::Int(1));
+        assert_eq!(intepreter.env.get("i"), Value::Int(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "1:9: the operand of '~' must be an integer")]
+    pub fn test_bit_not_float() {
+        run("int a = ~1.5;");
+    }
+
+    #[test]
+    pub fn test_conditional() {
+        // only the arm the condition picks runs, whatever its type
+        let intepreter = run(
+            "int n = 0; int *p = &n; int a = 2 ? n++ : n--; double d = 0.0 ? 1 : 2.5;
+             int *q = p ? p : 0; int b = 0 ? 1 / 0 : 3; int c = (int[]){0} ? 4 : 5;",
+        );
+        assert_eq!(intepreter.env.get("n"), Value::Int(1));
+        assert_eq!(intepreter.env.get("a"), Value::Int(0));
+        assert_eq!(intepreter.env.get("d"), Value::Float(2.5));
+        assert_eq!(intepreter.env.get("q"), Value::Pointer(Place::new("n")));
+        assert_eq!(intepreter.env.get("b"), Value::Int(3));
+        assert_eq!(intepreter.env.get("c"), Value::Int(4));
+    }
+
+    #[test]
+    pub fn test_sizeof() {
+        let intepreter = run(
+            "struct s { char c; double d; }; int n = sizeof(int); int m = sizeof(struct s[2]);
+             int a = _Alignof(struct s) + _Alignof(char);",
+        );
+        assert_eq!(intepreter.env.get("n"), Value::Int(4));
+        assert_eq!(intepreter.env.get("m"), Value::Int(32));
+        assert_eq!(intepreter.env.get("a"), Value::Int(9));
+    }
+
+    #[test]
+    #[should_panic(expected = "1:19: sizeof an incomplete type")]
+    pub fn test_sizeof_incomplete() {
+        run("struct s; int n = sizeof(struct s);");
+    }
+
+    #[test]
+    #[should_panic(expected = "1:19: the index 3 is out of bounds")]
+    pub fn test_out_of_bounds() {
+        run("int a[3]; int b = a[3] = 1;");
+    }
+
+    #[test]
+    #[should_panic(expected = "1:20: division by zero")]
+    pub fn test_division_by_zero() {
+        run("int a = 1; int b = a / 0;");
+    }
 }
@@ -1,3 +1,5 @@
 pub mod env;
+pub mod initializer;
 pub mod intepreter;
+pub mod place;
 pub mod value;
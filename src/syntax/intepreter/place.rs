@@ -0,0 +1,103 @@
+use crate::syntax::tokenizer::attr::Attr;
+
+use super::value::value::Value;
+
+/// An object that can be read and written: a variable, or an element or
+/// member somewhere inside one.
+#[derive(Debug, PartialEq, PartialOrd, Clone)]
+pub struct Place {
+    pub name: String,
+    // the element and member indices from the variable inwards
+    pub path: Vec<usize>,
+}
+
+impl Place {
+    pub fn new(name: &str) -> Place {
+        Place {
+            name: name.to_owned(),
+            path: Vec::new(),
+        }
+    }
+
+    /// The `i`th element or member of this place.
+    pub fn at(&self, i: usize) -> Place {
+        let mut place = self.clone();
+        place.path.push(i);
+        place
+    }
+
+    /// The element `n` after this one in the same array, as moved by the
+    /// expression at `attr`.
+    pub fn offset(&self, n: i64, attr: &Attr) -> Place {
+        let mut place = self.clone();
+        match place.path.last_mut() {
+            Some(i) => match usize::try_from(*i as i64 + n) {
+                Ok(moved) => *i = moved,
+                Err(_) => panic!(
+                    "{}: the pointer goes before the start of '{}'",
+                    attr.start(),
+                    self.name
+                ),
+            },
+            None if n == 0 => {}
+            None => panic!(
+                "{}: the pointer to '{}' is not into an array",
+                attr.start(),
+                self.name
+            ),
+        }
+        place
+    }
+}
+
+/// The value at `path` inside `value`, for the expression at `attr`. A
+/// union is the member it holds.
+pub fn get<'v>(value: &'v Value, path: &[usize], attr: &Attr) -> &'v Value {
+    match (value, path.split_first()) {
+        (value, None) => value,
+        (Value::Array(values) | Value::Struct(values), Some((i, rest))) => match values.get(*i) {
+            Some(value) => get(value, rest, attr),
+            None => panic!("{}: the index {} is out of bounds", attr.start(), i),
+        },
+        (Value::Union(_, value), Some((_, rest))) => get(value, rest, attr),
+        (value, Some(_)) => panic!("{}: {:?} has no members", attr.start(), value),
+    }
+}
+
+pub fn get_mut<'v>(value: &'v mut Value, path: &[usize], attr: &Attr) -> &'v mut Value {
+    match (value, path.split_first()) {
+        (value, None) => value,
+        (Value::Array(values) | Value::Struct(values), Some((i, rest))) => {
+            match values.get_mut(*i) {
+                Some(value) => get_mut(value, rest, attr),
+                None => panic!("{}: the index {} is out of bounds", attr.start(), i),
+            }
+        }
+        (Value::Union(_, value), Some((_, rest))) => get_mut(value, rest, attr),
+        (value, Some(_)) => panic!("{}: {:?} has no members", attr.start(), value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{intepreter::value::value::Value, tokenizer::attr::Attr};
+
+    use super::{get, get_mut, Place};
+
+    #[test]
+    pub fn test_place() {
+        let attr = Attr::new();
+        let place = Place::new("a").at(1).at(2);
+        assert_eq!(place.path, vec![1, 2]);
+        assert_eq!(place.offset(-2, &attr).path, vec![1, 0]);
+        assert_eq!(Place::new("a").offset(0, &attr), Place::new("a"));
+
+        let mut value = Value::Struct(vec![
+            Value::Int(1),
+            Value::Union(0, Box::new(Value::Array(vec![Value::Int(2), Value::Int(3)]))),
+        ]);
+        assert_eq!(get(&value, &[1, 0, 1], &attr), &Value::Int(3));
+        *get_mut(&mut value, &[0], &attr) = Value::Int(4);
+        assert_eq!(get(&value, &[0], &attr), &Value::Int(4));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::syntax::intepreter::place::Place;
+
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub enum Value {
     Null,
@@ -5,6 +7,12 @@ pub enum Value {
     Int(i64),
     Float(f64),
     String(String),
+    // the elements of an array and the members of a struct
+    Array(Vec<Value>),
+    Struct(Vec<Value>),
+    // the member a union holds
+    Union(usize, Box<Value>),
+    Pointer(Place),
 }
 
 #[cfg(test)]
@@ -1,8 +1,11 @@
 pub mod ast;
 pub mod codegen;
+pub mod diagnostic;
 pub mod intepreter;
 pub mod ir;
 pub mod parser;
+pub mod preprocessor;
 pub mod resolver;
+pub mod source;
 pub mod tokenizer;
 pub mod typing;
@@ -0,0 +1,956 @@
+use crate::syntax::{
+    ast::{
+        expr::{
+            binary_expr::Binary, expr::Expr, group_expr::GroupExpr, literal_expr::Literal,
+            trinary_expr::Trinary, type_expr::TypeExpr, unary_expr::Unary,
+        },
+        stmt::{declare_stmt::Declare, stmt::Stmt},
+    },
+    diagnostic::diagnostic::Diagnostic,
+    tokenizer::{attr::Attr, token::Token},
+    typing::types::{Prototype, Type},
+};
+
+use super::parser::{Ordinary, ParseResult, Parser};
+
+/// Parameters with their names, which are empty for unnamed ones.
+type Params = Vec<(String, Type)>;
+
+/// A declarator applied to the type of its specifiers. `params` are the
+/// named parameters when the declared name is itself a function, as in
+/// `int (*f(int a))(char)`. `attr` is where the declarator starts.
+pub(super) struct Declarator {
+    pub(super) attr: Attr,
+    pub(super) name: Option<String>,
+    pub(super) typ: Type,
+    params: Option<Params>,
+}
+
+/// One step of a declarator from the type of its specifiers towards
+/// the declared name.
+enum Derivation {
+    Pointer,
+    Array(Option<usize>),
+    // the parameters, and whether they have a prototype
+    Function(Params, Prototype),
+}
+
+/// The specifiers seen so far in a declaration: type specifier keywords,
+/// or a typedef name or tag, and whether it is a `typedef`.
+#[derive(Default)]
+struct Specifiers {
+    keywords: Vec<Token>,
+    named: Option<(String, Type)>,
+    typedef: bool,
+}
+
+impl Specifiers {
+    fn is_empty(&self) -> bool {
+        self.keywords.is_empty() && self.named.is_none()
+    }
+
+    fn add(&mut self, token: Token) -> Result<(), String> {
+        let longs = self.count(|t| matches!(t, Token::Long(_)));
+        for seen in &self.keywords {
+            if matches!(token, Token::Long(_)) && matches!(seen, Token::Long(_)) {
+                if longs == 2 {
+                    return Err(String::from("'long long long' is too long"));
+                }
+            } else if token.to_string() == seen.to_string() {
+                return Err(format!("duplicate '{}'", token));
+            } else if !compatible(&token, seen) {
+                return Err(format!("cannot combine '{}' with '{}'", token, seen));
+            }
+        }
+        self.keywords.push(token);
+        Ok(())
+    }
+
+    fn count(&self, f: fn(&Token) -> bool) -> usize {
+        self.keywords.iter().filter(|t| f(t)).count()
+    }
+
+    fn has(&self, f: fn(&Token) -> bool) -> bool {
+        self.count(f) > 0
+    }
+
+    /// The type the specifiers name; the keywords are known to be
+    /// compatible.
+    fn to_type(&self) -> Type {
+        if let Some((_, typ)) = &self.named {
+            return typ.clone();
+        }
+        let unsigned = self.has(|t| matches!(t, Token::Unsigned(_)));
+        let longs = self.count(|t| matches!(t, Token::Long(_)));
+        if self.has(|t| matches!(t, Token::Void(_))) {
+            Type::Void
+        } else if self.has(|t| matches!(t, Token::BoolKw(_))) {
+            Type::Bool
+        } else if self.has(|t| matches!(t, Token::CharKw(_))) {
+            if unsigned {
+                Type::UChar
+            } else {
+                Type::Char
+            }
+        } else if self.has(|t| matches!(t, Token::FloatKw(_))) {
+            Type::Float
+        } else if self.has(|t| matches!(t, Token::Double(_))) {
+            if longs > 0 {
+                Type::LongDouble
+            } else {
+                Type::Double
+            }
+        } else if self.has(|t| matches!(t, Token::Short(_))) {
+            if unsigned {
+                Type::UShort
+            } else {
+                Type::Short
+            }
+        } else {
+            match (longs, unsigned) {
+                (0, false) => Type::Int,
+                (0, true) => Type::UInt,
+                (1, false) => Type::Long,
+                (1, true) => Type::ULong,
+                (_, false) => Type::LongLong,
+                (_, true) => Type::ULongLong,
+            }
+        }
+    }
+}
+
+/// Whether two different type specifier keywords may appear together.
+fn compatible(a: &Token, b: &Token) -> bool {
+    let allowed = |a: &Token, b: &Token| match a {
+        Token::Signed(_) | Token::Unsigned(_) => matches!(
+            b,
+            Token::CharKw(_) | Token::Short(_) | Token::Int(_) | Token::Long(_)
+        ),
+        Token::Short(_) => matches!(b, Token::Int(_)),
+        Token::Long(_) => matches!(b, Token::Int(_) | Token::Double(_)),
+        _ => false,
+    };
+    allowed(a, b) || allowed(b, a)
+}
+
+fn is_type_specifier(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Void(_)
+            | Token::CharKw(_)
+            | Token::Short(_)
+            | Token::Int(_)
+            | Token::Long(_)
+            | Token::FloatKw(_)
+            | Token::Double(_)
+            | Token::Signed(_)
+            | Token::Unsigned(_)
+            | Token::BoolKw(_)
+    )
+}
+
+/// Storage classes, qualifiers and function specifiers, which the AST
+/// does not record.
+fn is_ignored_specifier(token: &Token) -> bool {
+    matches!(
+        token,
+        Token::Static(_)
+            | Token::Extern(_)
+            | Token::Auto(_)
+            | Token::Register(_)
+            | Token::ThreadLocal(_)
+            | Token::Const(_)
+            | Token::Volatile(_)
+            | Token::Restrict(_)
+            | Token::Inline(_)
+            | Token::Noreturn(_)
+    )
+}
+
+impl Parser {
+    /// Whether the next token starts a declaration.
+    pub(super) fn is_declaration_start(&self) -> bool {
+        match self.peek() {
+            Token::Typedef(_) => true,
+            // `T:` is a label even if `T` is a typedef name
+            Token::ID(_, name) => self.typedef(name).is_some() && !self.is_nth(1, Token::Colon),
+            Token::Struct(_) | Token::Union(_) | Token::Enum(_) => true,
+            token => is_type_specifier(token) || is_ignored_specifier(token),
+        }
+    }
+
+    /// Whether the `n`th token starts a type name, which tells a cast
+    /// from a parenthesised expression.
+    pub(super) fn is_type_name_start(&self, n: usize) -> bool {
+        match self.peek_nth(n) {
+            Token::ID(_, name) => self.typedef(name).is_some(),
+            Token::Struct(_) | Token::Union(_) | Token::Enum(_) => true,
+            token => {
+                is_type_specifier(token)
+                    || matches!(
+                        token,
+                        Token::Const(_) | Token::Volatile(_) | Token::Restrict(_)
+                    )
+            }
+        }
+    }
+
+    /// A declaration or function definition at file scope.
+    pub(super) fn parse_external_declaration(&mut self) -> ParseResult<Vec<Stmt>> {
+        if !self.is_declaration_start() {
+            return Err(self.error(format!("expected a declaration, found {}", self.found())));
+        }
+        let start = self.attr();
+        let specifiers = self.parse_specifiers()?;
+        let mut stmts = self.take_definitions();
+        if self.eat(Token::Semicolon) {
+            return Ok(stmts);
+        }
+        let declarator = self.parse_declarator(&specifiers.to_type())?;
+        match declarator {
+            Declarator {
+                name: Some(name),
+                typ: Type::Function(_, return_type, prototype),
+                params: Some(parameters),
+                ..
+            } if self.is(Token::LBrace) && !specifiers.typedef => {
+                self.declare_name(&name, Ordinary::Variable);
+                self.push_scope();
+                for (param, _) in &parameters {
+                    self.declare_name(param, Ordinary::Variable);
+                }
+                let body = self.parse_block();
+                self.pop_scope();
+                let body = body?;
+                stmts.push(Stmt::Declare(Declare::DeclareFunction {
+                    attr: self.attr_from(&start),
+                    name,
+                    parameters,
+                    prototype,
+                    return_type: *return_type,
+                    body,
+                }));
+            }
+            declarator => stmts.extend(self.parse_init_declarators(&specifiers, declarator)?),
+        }
+        Ok(stmts)
+    }
+
+    /// A declaration inside a block, after the definitions of the tags
+    /// in its specifiers.
+    pub(super) fn parse_declaration(&mut self) -> ParseResult<Vec<Stmt>> {
+        let specifiers = self.parse_specifiers()?;
+        let mut stmts = self.take_definitions();
+        if self.eat(Token::Semicolon) {
+            return Ok(stmts);
+        }
+        let declarator = self.parse_declarator(&specifiers.to_type())?;
+        stmts.extend(self.parse_init_declarators(&specifiers, declarator)?);
+        Ok(stmts)
+    }
+
+    /// A type without a name, as in casts and `sizeof`: `int`,
+    /// `char *[3]` or `int (*)(int)`.
+    pub fn parse_type_name(&mut self) -> ParseResult<Type> {
+        let base = self.dropping_definitions(Parser::parse_unstored_specifiers)?;
+        let attr = self.attr();
+        let declarator = self.parse_declarator(&base)?;
+        match declarator.name {
+            Some(name) => Err(Diagnostic::error(
+                attr,
+                format!("unexpected name '{}' in a type name", name),
+            )),
+            None => Ok(declarator.typ),
+        }
+    }
+
+    /// The rest of `int a = 1, *b, c[3];` after its first declarator,
+    /// one `Stmt::Declare` per declarator. The names are declared as
+    /// they are parsed, so `typedef int T, *P;` declares two typedefs.
+    fn parse_init_declarators(
+        &mut self,
+        specifiers: &Specifiers,
+        first: Declarator,
+    ) -> ParseResult<Vec<Stmt>> {
+        let base = specifiers.to_type();
+        let mut stmts = Vec::new();
+        let mut declarator = first;
+        loop {
+            let name = match declarator.name {
+                Some(name) => name,
+                None => {
+                    return Err(self.error(format!("expected identifier, found {}", self.found())))
+                }
+            };
+            if specifiers.typedef {
+                if self.is(Token::Assign) {
+                    return Err(self.error(format!("typedef '{}' is initialized", name)));
+                }
+                self.declare_name(&name, Ordinary::Typedef(declarator.typ.clone()));
+                stmts.push(Stmt::Declare(Declare::DeclareTypedef(
+                    self.attr_from(&declarator.attr),
+                    name,
+                    declarator.typ,
+                )));
+                if !self.eat(Token::Comma) {
+                    break;
+                }
+                declarator = self.parse_declarator(&base)?;
+                continue;
+            }
+            self.declare_name(&name, Ordinary::Variable);
+            let mut typ = declarator.typ;
+            let init = if self.eat(Token::Assign) {
+                if let Type::Function(..) = typ {
+                    return Err(self.error(format!(
+                        "function '{}' is initialized like a variable",
+                        name
+                    )));
+                }
+                let init = self.parse_initializer()?;
+                typ = self.complete_type(typ, &init);
+                Some(init)
+            } else {
+                None
+            };
+            stmts.push(Stmt::Declare(Declare::DeclareIdentifier(
+                self.attr_from(&declarator.attr),
+                name,
+                typ,
+                init,
+            )));
+            if !self.eat(Token::Comma) {
+                break;
+            }
+            declarator = self.parse_declarator(&base)?;
+        }
+        self.expect(Token::Semicolon)?;
+        Ok(stmts)
+    }
+
+    /// The specifiers of a declaration. A typedef name is only one when
+    /// no other type specifier came before it, so in `unsigned T;` the
+    /// `T` is the declared name.
+    fn parse_specifiers(&mut self) -> ParseResult<Specifiers> {
+        let mut specifiers = Specifiers::default();
+        loop {
+            let attr = self.attr();
+            match self.peek() {
+                Token::Typedef(_) => specifiers.typedef = true,
+                token if is_ignored_specifier(token) => {}
+                token if is_type_specifier(token) => {
+                    if let Some((name, _)) = &specifiers.named {
+                        let message = format!("cannot combine '{}' with '{}'", token, name);
+                        return Err(Diagnostic::error(attr, message));
+                    }
+                    let token = token.clone();
+                    specifiers
+                        .add(token)
+                        .map_err(|message| Diagnostic::error(attr, message))?;
+                }
+                Token::ID(_, name) if specifiers.is_empty() => match self.typedef(name) {
+                    Some(typ) => specifiers.named = Some((name.clone(), typ.clone())),
+                    None => break,
+                },
+                token @ (Token::Struct(_) | Token::Union(_) | Token::Enum(_)) => {
+                    let seen = match (specifiers.keywords.first(), &specifiers.named) {
+                        (Some(keyword), _) => Some(keyword.to_string()),
+                        (None, Some((name, _))) => Some(name.clone()),
+                        (None, None) => None,
+                    };
+                    if let Some(seen) = seen {
+                        let message = format!("cannot combine '{}' with '{}'", token, seen);
+                        return Err(Diagnostic::error(attr, message));
+                    }
+                    let id = self.parse_tag_specifier()?;
+                    let tag = self.tag(id);
+                    specifiers.named = Some((tag.spelling(), tag.typ(id)));
+                    continue;
+                }
+                _ => break,
+            }
+            self.advance();
+        }
+        if specifiers.is_empty() {
+            return Err(self.error(format!("expected a type, found {}", self.found())));
+        }
+        Ok(specifiers)
+    }
+
+    /// The specifiers of a parameter, member or type name, where
+    /// `typedef` is not allowed, as the type they name.
+    pub(super) fn parse_unstored_specifiers(&mut self) -> ParseResult<Type> {
+        let attr = self.attr();
+        let specifiers = self.parse_specifiers()?;
+        if specifiers.typedef {
+            return Err(Diagnostic::error(
+                attr,
+                String::from("'typedef' is not allowed here"),
+            ));
+        }
+        Ok(specifiers.to_type())
+    }
+
+    /// A declarator such as `*p`, `a[3][4]`, `f(int x)` or `(*fp)(int)`,
+    /// applied to `base`. The name is left out in abstract declarators.
+    pub(super) fn parse_declarator(&mut self, base: &Type) -> ParseResult<Declarator> {
+        let start = self.attr();
+        let (name, derivations) = self.parse_derivations()?;
+        // the parameters are named when the name is declared a function
+        let params = match derivations.last() {
+            Some(Derivation::Function(params, _)) => Some(params.clone()),
+            _ => None,
+        };
+        let mut typ = base.clone();
+        for derivation in derivations {
+            typ = match derivation {
+                Derivation::Pointer => Type::Pointer(Box::new(typ)),
+                Derivation::Array(size) => Type::Array(Box::new(typ), size),
+                Derivation::Function(params, prototype) => {
+                    let types = params.into_iter().map(|(_, t)| Box::new(t)).collect();
+                    Type::Function(types, Box::new(typ), prototype)
+                }
+            };
+        }
+        Ok(Declarator {
+            attr: start,
+            name,
+            typ,
+            params,
+        })
+    }
+
+    /// The name of a declarator and its derivations in the order they
+    /// apply to the base type. The suffixes after a parenthesised
+    /// declarator apply before the declarator itself, so `*(*x)[2]` is
+    /// the outer `*`, then `[2]`, then the inner `*`.
+    fn parse_derivations(&mut self) -> ParseResult<(Option<String>, Vec<Derivation>)> {
+        let mut derivations = self.parse_pointers();
+        let (name, inner) = if self.is(Token::LParen) && self.is_nested_declarator() {
+            self.advance();
+            let nested = self.parse_derivations()?;
+            self.expect(Token::RParen)?;
+            nested
+        } else {
+            let name = match self.peek() {
+                Token::ID(_, name) => {
+                    let name = name.clone();
+                    self.advance();
+                    Some(name)
+                }
+                _ => None,
+            };
+            (name, Vec::new())
+        };
+        derivations.extend(self.parse_suffixes()?.into_iter().rev());
+        derivations.extend(inner);
+        Ok((name, derivations))
+    }
+
+    /// Whether the `(` next starts a parenthesised declarator, rather
+    /// than the parameters of an abstract function declarator.
+    fn is_nested_declarator(&self) -> bool {
+        match self.peek_nth(1) {
+            Token::Mul(_) | Token::LParen(_) => true,
+            Token::ID(_, name) => self.typedef(name).is_none(),
+            _ => false,
+        }
+    }
+
+    fn parse_pointers(&mut self) -> Vec<Derivation> {
+        let mut pointers = Vec::new();
+        while self.eat(Token::Mul) {
+            while matches!(
+                self.peek(),
+                Token::Const(_) | Token::Volatile(_) | Token::Restrict(_)
+            ) {
+                self.advance();
+            }
+            pointers.push(Derivation::Pointer);
+        }
+        pointers
+    }
+
+    /// `[3][4]` or `(int a)`, in the order they are written.
+    fn parse_suffixes(&mut self) -> ParseResult<Vec<Derivation>> {
+        let mut suffixes = Vec::new();
+        loop {
+            if self.eat(Token::LParen) {
+                let (params, prototype) = self.parse_parameters()?;
+                suffixes.push(Derivation::Function(params, prototype));
+            } else if self.eat(Token::LBraket) {
+                let size = if self.is(Token::RBraket) {
+                    None
+                } else {
+                    Some(self.parse_array_size()?)
+                };
+                self.expect(Token::RBraket)?;
+                suffixes.push(Derivation::Array(size));
+            } else {
+                return Ok(suffixes);
+            }
+        }
+    }
+
+    fn parse_array_size(&mut self) -> ParseResult<usize> {
+        let attr = self.attr();
+        let expr = self.parse_conditional()?;
+        let size = self.constant(&expr).ok_or_else(|| {
+            Diagnostic::error(
+                attr.clone(),
+                String::from("array size must be an integer constant"),
+            )
+        })?;
+        usize::try_from(size)
+            .map_err(|_| Diagnostic::error(attr, format!("array size {} is negative", size)))
+    }
+
+    /// The parameters after `(`, up to and including `)`, and whether
+    /// they have a prototype. Unnamed parameters get an empty name.
+    fn parse_parameters(&mut self) -> ParseResult<(Params, Prototype)> {
+        // the names are in scope for the rest of the parameters, and the
+        // tags would be too, with nowhere in the AST to print them
+        self.push_scope();
+        let parameters =
+            self.forbidding_definitions(Parser::parse_parameter_list, "a parameter list");
+        self.pop_scope();
+        parameters
+    }
+
+    fn parse_parameter_list(&mut self) -> ParseResult<(Params, Prototype)> {
+        let mut parameters = Vec::new();
+        if self.eat(Token::RParen) {
+            return Ok((parameters, Prototype::Unspecified));
+        }
+        if self.is(Token::Void) && self.is_nth(1, Token::RParen) {
+            self.advance();
+            self.advance();
+            return Ok((parameters, Prototype::Fixed));
+        }
+        let mut prototype = Prototype::Fixed;
+        loop {
+            if self.eat(Token::Ellipsis) {
+                prototype = Prototype::Variadic;
+                break;
+            }
+            let base = self.parse_unstored_specifiers()?;
+            let declarator = self.parse_declarator(&base)?;
+            if let Some(name) = &declarator.name {
+                self.declare_name(name, Ordinary::Variable);
+            }
+            // array and function parameters are pointers
+            let typ = match declarator.typ {
+                Type::Array(element, _) => Type::Pointer(element),
+                typ @ Type::Function(..) => Type::Pointer(Box::new(typ)),
+                typ => typ,
+            };
+            parameters.push((declarator.name.unwrap_or_default(), typ));
+            if !self.eat(Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RParen)?;
+        Ok((parameters, prototype))
+    }
+
+    /// The value of an integer constant expression, or `None` if `expr` is
+    /// not one. Identifiers are the enum constants in scope.
+    pub(super) fn constant(&self, expr: &Expr) -> Option<i64> {
+        match expr {
+            Expr::LiteralExpr(_, Literal::Int(i)) => Some(*i),
+            Expr::LiteralExpr(_, Literal::UInt(u)) => i64::try_from(*u).ok(),
+            Expr::LiteralExpr(_, Literal::Char(c)) => Some(*c as i64),
+            Expr::LiteralExpr(_, Literal::Bool(b)) => Some(*b as i64),
+            Expr::LiteralExpr(_, Literal::ID(name)) => self.enum_constant(name),
+            Expr::LiteralExpr(_, _) => None,
+            Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => self.constant(e),
+            Expr::UnaryExpr(_, unary) => match unary {
+                Unary::Plus(e) => self.constant(e),
+                Unary::Minus(e) => self.constant(e)?.checked_neg(),
+                Unary::Bang(e) => Some((self.constant(e)? == 0) as i64),
+                Unary::BitNot(e) => Some(!self.constant(e)?),
+                Unary::Deref(_)
+                | Unary::AddressOf(_)
+                | Unary::Sizeof(_)
+                | Unary::PreInc(_)
+                | Unary::PreDec(_)
+                | Unary::PostInc(_)
+                | Unary::PostDec(_) => None,
+            },
+            Expr::TrinaryExpr(_, Trinary::TrinaryExpr(cond, then, otherwise)) => {
+                if self.constant(cond)? != 0 {
+                    self.constant(then)
+                } else {
+                    self.constant(otherwise)
+                }
+            }
+            Expr::BinaryExpr(_, binary) => match binary {
+                Binary::And(l, r) => {
+                    Some((self.constant(l)? != 0 && self.constant(r)? != 0) as i64)
+                }
+                Binary::Or(l, r) => Some((self.constant(l)? != 0 || self.constant(r)? != 0) as i64),
+                Binary::Plus(l, r) => self.constant(l)?.checked_add(self.constant(r)?),
+                Binary::Minus(l, r) => self.constant(l)?.checked_sub(self.constant(r)?),
+                Binary::Mul(l, r) => self.constant(l)?.checked_mul(self.constant(r)?),
+                Binary::Div(l, r) => self.constant(l)?.checked_div(self.constant(r)?),
+                Binary::Mod(l, r) => self.constant(l)?.checked_rem(self.constant(r)?),
+                Binary::LShift(l, r) => self
+                    .constant(l)?
+                    .checked_shl(u32::try_from(self.constant(r)?).ok()?),
+                Binary::RShift(l, r) => self
+                    .constant(l)?
+                    .checked_shr(u32::try_from(self.constant(r)?).ok()?),
+                Binary::Eq(l, r) => Some((self.constant(l)? == self.constant(r)?) as i64),
+                Binary::Neq(l, r) => Some((self.constant(l)? != self.constant(r)?) as i64),
+                Binary::Lt(l, r) => Some((self.constant(l)? < self.constant(r)?) as i64),
+                Binary::Gt(l, r) => Some((self.constant(l)? > self.constant(r)?) as i64),
+                Binary::Lte(l, r) => Some((self.constant(l)? <= self.constant(r)?) as i64),
+                Binary::Gte(l, r) => Some((self.constant(l)? >= self.constant(r)?) as i64),
+                Binary::BitAnd(l, r) => Some(self.constant(l)? & self.constant(r)?),
+                Binary::BitOr(l, r) => Some(self.constant(l)? | self.constant(r)?),
+                Binary::BitXor(l, r) => Some(self.constant(l)? ^ self.constant(r)?),
+                Binary::Call(..)
+                | Binary::Member(..)
+                | Binary::PtrMember(..)
+                | Binary::Index(..)
+                | Binary::Comma(..) => None,
+            },
+            Expr::AssignExpr(_, _) => None,
+            Expr::TypeExpr(_, TypeExpr::Cast(typ, e)) => match typ {
+                Type::Bool => Some((self.constant(e)? != 0) as i64),
+                Type::Int
+                | Type::UInt
+                | Type::Short
+                | Type::UShort
+                | Type::Long
+                | Type::ULong
+                | Type::LongLong
+                | Type::ULongLong
+                | Type::Char
+                | Type::UChar
+                | Type::Enum(_) => self.constant(e),
+                _ => None,
+            },
+            Expr::TypeExpr(_, TypeExpr::Sizeof(typ)) => typ.size(self.tags())?.try_into().ok(),
+            Expr::TypeExpr(_, TypeExpr::Alignof(typ)) => typ.align(self.tags())?.try_into().ok(),
+            Expr::TypeExpr(_, TypeExpr::CompoundLiteral(..)) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::attr::Attr;
+    use crate::syntax::{
+        ast::{
+            expr::{binary_expr::Binary, expr::Expr, literal_expr::Literal},
+            stmt::{declare_stmt::Declare, stmt::Stmt},
+        },
+        parser::parser::Parser,
+        typing::types::{Prototype, Type},
+    };
+
+    fn ptr(typ: Type) -> Type {
+        Type::Pointer(Box::new(typ))
+    }
+
+    fn array(typ: Type, size: usize) -> Type {
+        Type::Array(Box::new(typ), Some(size))
+    }
+
+    fn function(params: Vec<Type>, returns: Type) -> Type {
+        Type::Function(
+            params.into_iter().map(Box::new).collect(),
+            Box::new(returns),
+            Prototype::Fixed,
+        )
+    }
+
+    /// The names and types of the variables and functions `src` declares.
+    fn declare(src: &str) -> Vec<(String, Type)> {
+        let unit = Parser::new(src).parse_unit().unwrap();
+        unit.stmts
+            .into_iter()
+            .filter_map(|stmt| match stmt {
+                Stmt::Declare(Declare::DeclareIdentifier(_, name, typ, _)) => Some((name, typ)),
+                Stmt::Declare(Declare::DeclareTypedef(..)) => None,
+                stmt => panic!("unexpected {:?}", stmt),
+            })
+            .collect()
+    }
+
+    fn declare_one(src: &str) -> Type {
+        declare(src).remove(0).1
+    }
+
+    fn type_name(src: &str) -> Type {
+        let mut parser = Parser::new(src);
+        let typ = parser.parse_type_name().unwrap();
+        assert!(parser.at_eof(), "{} was not parsed to the end", src);
+        typ
+    }
+
+    #[test]
+    pub fn test_pointers_and_arrays() {
+        assert_eq!(declare_one("char **argv;"), ptr(ptr(Type::Char)));
+        assert_eq!(declare_one("int a[3][4];"), array(array(Type::Int, 4), 3));
+        assert_eq!(declare_one("int *a[3];"), array(ptr(Type::Int), 3));
+        assert_eq!(declare_one("int (*a)[3];"), ptr(array(Type::Int, 3)));
+        assert_eq!(
+            declare_one("extern int a[];"),
+            Type::Array(Box::new(Type::Int), None)
+        );
+        assert_eq!(declare_one("int a[2 * (3 + 1)];"), array(Type::Int, 8));
+        assert_eq!(declare_one("char buf[sizeof(int)];"), array(Type::Char, 4));
+        assert_eq!(
+            declare_one("char p[sizeof(char *[3]) + _Alignof(long double)];"),
+            array(Type::Char, 40)
+        );
+        assert_eq!(declare_one("char * const * p;"), ptr(ptr(Type::Char)));
+        assert_eq!(declare_one("int ((a));"), Type::Int);
+        // each level is parsed once, so deep nesting stays linear
+        let deep = format!("int {}*a{}[2];", "(".repeat(200), ")".repeat(200));
+        assert_eq!(declare_one(&deep), ptr(array(Type::Int, 2)));
+    }
+
+    #[test]
+    pub fn test_functions() {
+        assert_eq!(
+            declare_one("int (*fp)(int);"),
+            ptr(function(vec![Type::Int], Type::Int))
+        );
+        assert_eq!(
+            declare_one("void (*signal(int sig, void (*handler)(int)))(int);"),
+            function(
+                vec![Type::Int, ptr(function(vec![Type::Int], Type::Void))],
+                ptr(function(vec![Type::Int], Type::Void))
+            )
+        );
+        assert_eq!(
+            declare_one("int (*table[4])(void);"),
+            array(ptr(function(vec![], Type::Int)), 4)
+        );
+        assert_eq!(
+            declare_one("char *(*(*x)[2])(long);"),
+            ptr(array(ptr(function(vec![Type::Long], ptr(Type::Char))), 2))
+        );
+        // array and function parameters are pointers
+        assert_eq!(
+            declare_one("int f(int a[3][4], int g(void));"),
+            function(
+                vec![ptr(array(Type::Int, 4)), ptr(function(vec![], Type::Int))],
+                Type::Int
+            )
+        );
+        assert_eq!(
+            declare_one("int printf(const char *, ...);"),
+            Type::Function(
+                vec![Box::new(ptr(Type::Char))],
+                Box::new(Type::Int),
+                Prototype::Variadic
+            )
+        );
+        // `()` says nothing about the parameters, unlike `(void)`
+        assert_eq!(
+            declare_one("int f();"),
+            Type::Function(vec![], Box::new(Type::Int), Prototype::Unspecified)
+        );
+    }
+
+    #[test]
+    pub fn test_multiple_declarators() {
+        assert_eq!(
+            declare("int a = 1, *b, c[2], (*d)(char);"),
+            vec![
+                (String::from("a"), Type::Int),
+                (String::from("b"), ptr(Type::Int)),
+                (String::from("c"), array(Type::Int, 2)),
+                (
+                    String::from("d"),
+                    ptr(function(vec![Type::Char], Type::Int))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_function_definition() {
+        let unit = Parser::new("int (*f(int a))(char) { return 0; }")
+            .parse_unit()
+            .unwrap();
+        match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareFunction {
+                name,
+                parameters,
+                return_type,
+                ..
+            }) => {
+                assert_eq!(name, "f");
+                assert_eq!(parameters, &vec![(String::from("a"), Type::Int)]);
+                assert_eq!(return_type, &ptr(function(vec![Type::Char], Type::Int)));
+            }
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
+
+    #[test]
+    pub fn test_abstract_declarators() {
+        assert_eq!(type_name("unsigned char"), Type::UChar);
+        assert_eq!(type_name("int *"), ptr(Type::Int));
+        assert_eq!(type_name("char *[3]"), array(ptr(Type::Char), 3));
+        assert_eq!(type_name("char (*)[3]"), ptr(array(Type::Char, 3)));
+        assert_eq!(
+            type_name("int (*)(int, char *)"),
+            ptr(function(vec![Type::Int, ptr(Type::Char)], Type::Int))
+        );
+        assert_eq!(type_name("int (void)"), function(vec![], Type::Int));
+        assert_eq!(
+            declare_one("int f(int *, char (*)[2]);"),
+            function(vec![ptr(Type::Int), ptr(array(Type::Char, 2))], Type::Int)
+        );
+    }
+
+    fn id(name: &str) -> Box<Expr> {
+        Box::new(Expr::LiteralExpr(
+            Attr::new(),
+            Literal::ID(String::from(name)),
+        ))
+    }
+
+    fn var(name: &str, typ: Type) -> Stmt {
+        Stmt::Declare(Declare::DeclareIdentifier(
+            Attr::new(),
+            String::from(name),
+            typ,
+            None,
+        ))
+    }
+
+    fn typedef(name: &str, typ: Type) -> Stmt {
+        Stmt::Declare(Declare::DeclareTypedef(
+            Attr::new(),
+            String::from(name),
+            typ,
+        ))
+    }
+
+    /// `T * x;`, parsed as a multiplication.
+    fn mul() -> Stmt {
+        Stmt::Expr(
+            Attr::new(),
+            Box::new(Expr::BinaryExpr(Attr::new(), Binary::Mul(id("T"), id("x")))),
+        )
+    }
+
+    /// The body of the last function `src` defines.
+    fn body(src: &str) -> Vec<Stmt> {
+        let unit = Parser::new(src).parse_unit().unwrap();
+        match unit.stmts.into_iter().last() {
+            Some(Stmt::Declare(Declare::DeclareFunction { body, .. })) => body,
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
+
+    #[test]
+    pub fn test_typedef() {
+        let unit = Parser::new("typedef int T, *P; T x; P p;")
+            .parse_unit()
+            .unwrap();
+        assert_eq!(
+            unit.stmts,
+            vec![
+                typedef("T", Type::Int),
+                typedef("P", ptr(Type::Int)),
+                var("x", Type::Int),
+                var("p", ptr(Type::Int)),
+            ]
+        );
+        assert_eq!(
+            declare_one("typedef char *S; S (*f)(S);"),
+            ptr(function(vec![ptr(Type::Char)], ptr(Type::Char)))
+        );
+        assert_eq!(
+            declare("typedef long T; unsigned T;")[0],
+            (String::from("T"), Type::UInt)
+        );
+        assert_eq!(
+            body("typedef int T; void f() { T * x; }"),
+            vec![var("x", ptr(Type::Int))]
+        );
+        assert_eq!(
+            body("int T, x; void f() { typedef char T; T * x; } void g() { T * x; }"),
+            vec![mul()]
+        );
+    }
+
+    #[test]
+    pub fn test_typedef_shadowing() {
+        // by a local variable, up to the end of its block
+        assert_eq!(
+            body("typedef int T; void f() { int T; T * x; }"),
+            vec![var("T", Type::Int), mul()]
+        );
+        assert_eq!(
+            body("typedef int T; void f() { { double T; } T * x; }"),
+            vec![
+                Stmt::Block(Attr::new(), vec![var("T", Type::Double)]),
+                var("x", ptr(Type::Int))
+            ]
+        );
+        // by a parameter
+        assert_eq!(body("typedef int T; void f(int T) { T * x; }"), vec![mul()]);
+        // by a declaration in a for
+        assert_eq!(
+            body("typedef int T; void f() { for (int T = 0; ;) T * x; T * x; }")[1],
+            var("x", ptr(Type::Int))
+        );
+        // a typedef in an inner block shadows a variable
+        assert_eq!(
+            body("int T; void f() { typedef char T; T * x; }"),
+            vec![typedef("T", Type::Char), var("x", ptr(Type::Char))]
+        );
+        // labels are in their own namespace
+        assert_eq!(
+            body("typedef int T; void f() { T: ; }"),
+            vec![Stmt::Label(
+                Attr::new(),
+                String::from("T"),
+                Box::new(Stmt::Empty(Attr::new()))
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_typedef_type_names() {
+        let mut parser = Parser::new("typedef int T; T (*)(T)");
+        parser.parse_external_declaration().unwrap();
+        assert_eq!(
+            parser.parse_type_name(),
+            Ok(ptr(function(vec![Type::Int], Type::Int)))
+        );
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let error = |src: &str| Parser::new(src).parse_unit().unwrap_err().remove(0).message;
+        assert_eq!(error("int a[n];"), "array size must be an integer constant");
+        assert_eq!(error("int a[-1];"), "array size -1 is negative");
+        assert_eq!(error("int (*a;"), "expected ')', found ';'");
+        assert_eq!(error("int *;"), "expected identifier, found ';'");
+        assert_eq!(
+            error("int f(void) = 1;"),
+            "function 'f' is initialized like a variable"
+        );
+        assert_eq!(error("typedef int T = 1;"), "typedef 'T' is initialized");
+        assert_eq!(
+            error("typedef int T; T int x;"),
+            "cannot combine 'int' with 'T'"
+        );
+        assert_eq!(
+            error("void f(typedef int x);"),
+            "'typedef' is not allowed here"
+        );
+        let message = Parser::new("int *p").parse_type_name().unwrap_err().message;
+        assert_eq!(message, "unexpected name 'p' in a type name");
+    }
+}
@@ -0,0 +1,619 @@
+use crate::syntax::{
+    ast::expr::{
+        assign_expr::Assign,
+        binary_expr::Binary,
+        expr::{infix_precedence, Expr},
+        group_expr::GroupExpr,
+        literal_expr::Literal,
+        trinary_expr::Trinary,
+        type_expr::TypeExpr,
+        unary_expr::Unary,
+    },
+    diagnostic::diagnostic::Diagnostic,
+    tokenizer::{attr::Attr, token::Token},
+    typing::types::Type,
+};
+
+use super::parser::{ParseResult, Parser};
+
+type BinaryOp = fn(Box<Expr>, Box<Expr>) -> Binary;
+
+/// The precedence and node of a binary operator; a higher precedence
+/// binds tighter and every level is left-associative. The precedences
+/// are the ones the printer parenthesises by.
+fn binary_op(token: &Token) -> Option<(u8, BinaryOp)> {
+    let op: BinaryOp = match token {
+        Token::Or(_) => Binary::Or,
+        Token::And(_) => Binary::And,
+        Token::BitOr(_) => Binary::BitOr,
+        Token::BitXor(_) => Binary::BitXor,
+        Token::BitAnd(_) => Binary::BitAnd,
+        Token::Eq(_) => Binary::Eq,
+        Token::Neq(_) => Binary::Neq,
+        Token::Lt(_) => Binary::Lt,
+        Token::Gt(_) => Binary::Gt,
+        Token::Lte(_) => Binary::Lte,
+        Token::Gte(_) => Binary::Gte,
+        Token::LShift(_) => Binary::LShift,
+        Token::RShift(_) => Binary::RShift,
+        Token::Plus(_) => Binary::Plus,
+        Token::Minus(_) => Binary::Minus,
+        Token::Mul(_) => Binary::Mul,
+        Token::Div(_) => Binary::Div,
+        Token::Mod(_) => Binary::Mod,
+        _ => return None,
+    };
+    Some((infix_precedence(&token.to_string())?, op))
+}
+
+type AssignOp = fn(Box<Expr>, Box<Expr>) -> Assign;
+
+fn assign_op(token: &Token) -> Option<AssignOp> {
+    let op: AssignOp = match token {
+        Token::Assign(_) => Assign::Assign,
+        Token::PlusAssign(_) => Assign::PlusAssign,
+        Token::MinusAssign(_) => Assign::MinusAssign,
+        Token::MulAssign(_) => Assign::MulAssign,
+        Token::DivAssign(_) => Assign::DivAssign,
+        Token::ModAssign(_) => Assign::ModAssign,
+        Token::BitAndAssign(_) => Assign::BitAndAssign,
+        Token::BitOrAssign(_) => Assign::BitOrAssign,
+        Token::BitXorAssign(_) => Assign::BitXorAssign,
+        Token::LShiftAssign(_) => Assign::LShiftAssign,
+        Token::RShiftAssign(_) => Assign::RShiftAssign,
+        _ => return None,
+    };
+    Some(op)
+}
+
+/// `expr` as the target of the assignment or increment at `attr`.
+fn assignable(attr: Attr, expr: Expr) -> ParseResult<Box<Expr>> {
+    if !expr.is_lvalue() {
+        return Err(Diagnostic::error(
+            attr,
+            String::from("expression is not assignable"),
+        ));
+    }
+    Ok(Box::new(expr))
+}
+
+impl Parser {
+    /// An expression, such as the condition of an `if` or a return
+    /// value, where a comma is an operator.
+    pub fn parse_expr(&mut self) -> ParseResult<Expr> {
+        let start = self.attr();
+        let mut expr = self.parse_assign()?;
+        while self.eat(Token::Comma) {
+            let rhs = self.parse_assign()?;
+            let comma = Binary::Comma(Box::new(expr), Box::new(rhs));
+            expr = Expr::BinaryExpr(self.attr_from(&start), comma);
+        }
+        Ok(expr)
+    }
+
+    /// An expression without a comma operator, as the arguments of a
+    /// call and the values of initializers are.
+    // a = b, a += b, which are right-associative
+    pub(super) fn parse_assign(&mut self) -> ParseResult<Expr> {
+        let start = self.attr();
+        let target = self.parse_conditional()?;
+        let op = match assign_op(self.peek()) {
+            Some(op) => op,
+            None => return Ok(target),
+        };
+        let target = assignable(self.attr(), target)?;
+        self.advance();
+        let value = self.parse_assign()?;
+        Ok(Expr::AssignExpr(
+            self.attr_from(&start),
+            op(target, Box::new(value)),
+        ))
+    }
+
+    /// A constant expression, such as an array size, is at this level.
+    // a ? b : c, which is right-associative
+    pub(super) fn parse_conditional(&mut self) -> ParseResult<Expr> {
+        let start = self.attr();
+        let cond = self.parse_binary(0)?;
+        if !self.eat(Token::QuestionMark) {
+            return Ok(cond);
+        }
+        let then = self.parse_expr()?;
+        self.expect(Token::Colon)?;
+        let otherwise = self.parse_conditional()?;
+        Ok(Expr::TrinaryExpr(
+            self.attr_from(&start),
+            Trinary::TrinaryExpr(Box::new(cond), Box::new(then), Box::new(otherwise)),
+        ))
+    }
+
+    /// Binary operators of at least precedence `min`, by precedence
+    /// climbing.
+    fn parse_binary(&mut self, min: u8) -> ParseResult<Expr> {
+        let start = self.attr();
+        let mut lhs = self.parse_unary()?;
+        while let Some((prec, op)) = binary_op(self.peek()) {
+            if prec < min {
+                break;
+            }
+            self.advance();
+            let rhs = self.parse_binary(prec + 1)?;
+            lhs = Expr::BinaryExpr(self.attr_from(&start), op(Box::new(lhs), Box::new(rhs)));
+        }
+        Ok(lhs)
+    }
+
+    // +a, -a, !a, ~a, *a, &a, ++a, --a, (int)a, sizeof a, sizeof(int),
+    // _Alignof(int)
+    fn parse_unary(&mut self) -> ParseResult<Expr> {
+        let attr = self.attr();
+        let unary: fn(Box<Expr>) -> Unary = match self.peek() {
+            Token::Plus(_) => Unary::Plus,
+            Token::Minus(_) => Unary::Minus,
+            Token::Not(_) => Unary::Bang,
+            Token::BitNot(_) => Unary::BitNot,
+            Token::Mul(_) => Unary::Deref,
+            Token::BitAnd(_) => Unary::AddressOf,
+            Token::LParen(_) if self.is_type_name_start(1) => {
+                let typ = self.parse_paren_type_name()?;
+                if self.is(Token::LBrace) {
+                    let literal = self.parse_compound_literal(&attr, typ)?;
+                    return self.parse_postfix_operators(literal);
+                }
+                let operand = self.parse_unary()?;
+                let cast = TypeExpr::Cast(typ, Box::new(operand));
+                return Ok(Expr::TypeExpr(self.attr_from(&attr), cast));
+            }
+            Token::Sizeof(_) => {
+                self.advance();
+                let operand = if !(self.is(Token::LParen) && self.is_type_name_start(1)) {
+                    self.parse_unary()?
+                } else {
+                    let start = self.attr();
+                    let typ = self.parse_paren_type_name()?;
+                    if !self.is(Token::LBrace) {
+                        let sizeof = TypeExpr::Sizeof(typ);
+                        return Ok(Expr::TypeExpr(self.attr_from(&attr), sizeof));
+                    }
+                    // sizeof (int[]){1, 2}
+                    let literal = self.parse_compound_literal(&start, typ)?;
+                    self.parse_postfix_operators(literal)?
+                };
+                let sizeof = Unary::Sizeof(Box::new(operand));
+                return Ok(Expr::UnaryExpr(self.attr_from(&attr), sizeof));
+            }
+            Token::Alignof(_) => {
+                self.advance();
+                let typ = self.parse_paren_type_name()?;
+                let alignof = TypeExpr::Alignof(typ);
+                return Ok(Expr::TypeExpr(self.attr_from(&attr), alignof));
+            }
+            Token::Incr(_) | Token::Decr(_) => {
+                let incr = self.is(Token::Incr);
+                self.advance();
+                let operand = assignable(attr.clone(), self.parse_unary()?)?;
+                let unary = if incr {
+                    Unary::PreInc(operand)
+                } else {
+                    Unary::PreDec(operand)
+                };
+                return Ok(Expr::UnaryExpr(self.attr_from(&attr), unary));
+            }
+            _ => return self.parse_postfix(),
+        };
+        self.advance();
+        let operand = self.parse_unary()?;
+        Ok(Expr::UnaryExpr(
+            self.attr_from(&attr),
+            unary(Box::new(operand)),
+        ))
+    }
+
+    /// `(int *)`, as in casts, `sizeof` and compound literals.
+    fn parse_paren_type_name(&mut self) -> ParseResult<Type> {
+        self.expect(Token::LParen)?;
+        let typ = self.parse_type_name()?;
+        self.expect(Token::RParen)?;
+        Ok(typ)
+    }
+
+    // (struct P){1, 2}, after the type name, which is at `start`
+    fn parse_compound_literal(&mut self, start: &Attr, typ: Type) -> ParseResult<Expr> {
+        let init = self.parse_initializer()?;
+        let typ = self.complete_type(typ, &init);
+        let literal = TypeExpr::CompoundLiteral(typ, init);
+        Ok(Expr::TypeExpr(self.attr_from(start), literal))
+    }
+
+    // f(a, b), a[b], a.b, a->b, a++, a--
+    fn parse_postfix(&mut self) -> ParseResult<Expr> {
+        let expr = self.parse_primary()?;
+        self.parse_postfix_operators(expr)
+    }
+
+    fn parse_postfix_operators(&mut self, mut expr: Expr) -> ParseResult<Expr> {
+        let start = expr.attr().clone();
+        loop {
+            let attr = self.attr();
+            expr = if self.eat(Token::LParen) {
+                let args = self.parse_args()?;
+                Expr::BinaryExpr(self.attr_from(&start), Binary::Call(Box::new(expr), args))
+            } else if self.eat(Token::LBraket) {
+                let index = self.parse_expr()?;
+                self.expect(Token::RBraket)?;
+                Expr::BinaryExpr(
+                    self.attr_from(&start),
+                    Binary::Index(Box::new(expr), Box::new(index)),
+                )
+            } else if self.eat(Token::Dot) {
+                let member = self.parse_member()?;
+                Expr::BinaryExpr(
+                    self.attr_from(&start),
+                    Binary::Member(Box::new(expr), member),
+                )
+            } else if self.eat(Token::Arrow) {
+                let member = self.parse_member()?;
+                Expr::BinaryExpr(
+                    self.attr_from(&start),
+                    Binary::PtrMember(Box::new(expr), member),
+                )
+            } else if self.eat(Token::Incr) {
+                Expr::UnaryExpr(
+                    self.attr_from(&start),
+                    Unary::PostInc(assignable(attr, expr)?),
+                )
+            } else if self.eat(Token::Decr) {
+                Expr::UnaryExpr(
+                    self.attr_from(&start),
+                    Unary::PostDec(assignable(attr, expr)?),
+                )
+            } else {
+                return Ok(expr);
+            };
+        }
+    }
+
+    /// The name after `.` or `->`, as an identifier.
+    fn parse_member(&mut self) -> ParseResult<Box<Expr>> {
+        let attr = self.attr();
+        let name = self.expect_id()?;
+        Ok(Box::new(Expr::LiteralExpr(attr, Literal::ID(name))))
+    }
+
+    /// The arguments of a call after `(`, up to and including `)`.
+    fn parse_args(&mut self) -> ParseResult<Vec<Expr>> {
+        let mut args = Vec::new();
+        if self.eat(Token::RParen) {
+            return Ok(args);
+        }
+        loop {
+            args.push(self.parse_assign()?);
+            if !self.eat(Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RParen)?;
+        Ok(args)
+    }
+
+    // 1, 1.5, 'a', "abc", true, a, (a)
+    fn parse_primary(&mut self) -> ParseResult<Expr> {
+        let literal = match self.peek() {
+            Token::Int8(_, v) => Literal::Int(*v as i64),
+            Token::Int16(_, v) => Literal::Int(*v as i64),
+            Token::Int32(_, v) => Literal::Int(*v as i64),
+            Token::Int64(_, v) => Literal::Int(*v),
+            Token::UInt32(_, v) => Literal::UInt(*v as u64),
+            Token::UInt64(_, v) => Literal::UInt(*v),
+            Token::Float(_, v) => Literal::Float32(*v),
+            Token::Float64(_, v) => Literal::Float(*v),
+            Token::LongDouble(_, v) => Literal::LongDouble(*v),
+            Token::Char(_, _, c) => Literal::Char(*c),
+            Token::String(_, _, s) => Literal::String(s.clone()),
+            Token::Bool(_, b) => Literal::Bool(*b),
+            Token::ID(_, name) => Literal::ID(name.clone()),
+            Token::LParen(_) => {
+                let start = self.attr();
+                self.advance();
+                let expr = self.parse_expr()?;
+                self.expect(Token::RParen)?;
+                let group = GroupExpr::GroupExpr(Box::new(expr));
+                return Ok(Expr::GroupExpr(self.attr_from(&start), group));
+            }
+            _ => return Err(self.error(format!("expected an expression, found {}", self.found()))),
+        };
+        let attr = self.attr();
+        self.advance();
+        Ok(Expr::LiteralExpr(attr, literal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::ast::expr::{
+        binary_expr::Binary, expr::Expr, group_expr::GroupExpr, literal_expr::Literal,
+        trinary_expr::Trinary, type_expr::TypeExpr, unary_expr::Unary,
+    };
+
+    use super::super::parser::Parser;
+
+    /// `src` parsed and printed with every node parenthesised, so
+    /// `1 + 2 * 3` gives `(1 + (2 * 3))`.
+    fn parse(src: &str) -> String {
+        let mut parser = Parser::new(src);
+        let expr = parser.parse_expr().unwrap();
+        assert!(parser.at_eof(), "{} was not parsed to the end", src);
+        group(&expr)
+    }
+
+    fn group(expr: &Expr) -> String {
+        let binary = |l: &Expr, op: &str, r: &Expr| format!("({} {} {})", group(l), op, group(r));
+        match expr {
+            Expr::LiteralExpr(_, Literal::ID(s)) => s.clone(),
+            Expr::LiteralExpr(_, _) => expr.to_string(),
+            Expr::UnaryExpr(_, Unary::Plus(e)) => format!("(+{})", group(e)),
+            Expr::UnaryExpr(_, Unary::Minus(e)) => format!("(-{})", group(e)),
+            Expr::UnaryExpr(_, Unary::Bang(e)) => format!("(!{})", group(e)),
+            Expr::UnaryExpr(_, Unary::BitNot(e)) => format!("(~{})", group(e)),
+            Expr::UnaryExpr(_, Unary::Deref(e)) => format!("(*{})", group(e)),
+            Expr::UnaryExpr(_, Unary::AddressOf(e)) => format!("(&{})", group(e)),
+            Expr::UnaryExpr(_, Unary::Sizeof(e)) => format!("(sizeof {})", group(e)),
+            Expr::UnaryExpr(_, Unary::PreInc(e)) => format!("(++{})", group(e)),
+            Expr::UnaryExpr(_, Unary::PreDec(e)) => format!("(--{})", group(e)),
+            Expr::UnaryExpr(_, Unary::PostInc(e)) => format!("({}++)", group(e)),
+            Expr::UnaryExpr(_, Unary::PostDec(e)) => format!("({}--)", group(e)),
+            Expr::BinaryExpr(_, b) => match b {
+                Binary::Plus(l, r) => binary(l, "+", r),
+                Binary::Minus(l, r) => binary(l, "-", r),
+                Binary::Mul(l, r) => binary(l, "*", r),
+                Binary::Div(l, r) => binary(l, "/", r),
+                Binary::Mod(l, r) => binary(l, "%", r),
+                Binary::Eq(l, r) => binary(l, "==", r),
+                Binary::Neq(l, r) => binary(l, "!=", r),
+                Binary::Gt(l, r) => binary(l, ">", r),
+                Binary::Lt(l, r) => binary(l, "<", r),
+                Binary::Gte(l, r) => binary(l, ">=", r),
+                Binary::Lte(l, r) => binary(l, "<=", r),
+                Binary::And(l, r) => binary(l, "&&", r),
+                Binary::Or(l, r) => binary(l, "||", r),
+                Binary::BitAnd(l, r) => binary(l, "&", r),
+                Binary::BitOr(l, r) => binary(l, "|", r),
+                Binary::BitXor(l, r) => binary(l, "^", r),
+                Binary::LShift(l, r) => binary(l, "<<", r),
+                Binary::RShift(l, r) => binary(l, ">>", r),
+                Binary::Call(f, args) => {
+                    let args: Vec<String> = args.iter().map(group).collect();
+                    format!("{}({})", group(f), args.join(", "))
+                }
+                Binary::Member(l, r) => format!("({}.{})", group(l), group(r)),
+                Binary::PtrMember(l, r) => format!("({}->{})", group(l), group(r)),
+                Binary::Index(l, r) => format!("({}[{}])", group(l), group(r)),
+                Binary::Comma(l, r) => binary(l, ",", r),
+            },
+            Expr::TrinaryExpr(_, Trinary::TrinaryExpr(c, t, e)) => {
+                format!("({} ? {} : {})", group(c), group(t), group(e))
+            }
+            Expr::GroupExpr(_, GroupExpr::GroupExpr(e)) => format!("[{}]", group(e)),
+            Expr::AssignExpr(_, assign) => {
+                let (target, value) = assign.operands();
+                binary(target, assign.op(), value)
+            }
+            Expr::TypeExpr(_, TypeExpr::Cast(typ, e)) => {
+                format!("(({}){})", typ, group(e))
+            }
+            Expr::TypeExpr(_, TypeExpr::Sizeof(typ)) => format!("sizeof({})", typ),
+            Expr::TypeExpr(_, TypeExpr::Alignof(typ)) => format!("_Alignof({})", typ),
+            Expr::TypeExpr(_, TypeExpr::CompoundLiteral(typ, init)) => {
+                format!("({}){}", typ, init)
+            }
+        }
+    }
+
+    /// `src` parsed and printed back.
+    fn reprint(src: &str) -> String {
+        Parser::new(src).parse_expr().unwrap().to_string()
+    }
+
+    #[test]
+    pub fn test_binary_operators() {
+        let ops = [
+            "*", "/", "%", "+", "-", "<<", ">>", "<", ">", "<=", ">=", "==", "!=", "&", "^", "|",
+            "&&", "||",
+        ];
+        for op in ops {
+            assert_eq!(parse(&format!("a {} b", op)), format!("(a {} b)", op));
+        }
+    }
+
+    #[test]
+    pub fn test_precedence() {
+        // each level against the one below it, in both orders
+        let levels = [
+            vec!["*", "/", "%"],
+            vec!["+", "-"],
+            vec!["<<", ">>"],
+            vec!["<", ">", "<=", ">="],
+            vec!["==", "!="],
+            vec!["&"],
+            vec!["^"],
+            vec!["|"],
+            vec!["&&"],
+            vec!["||"],
+        ];
+        for pair in levels.windows(2) {
+            for high in &pair[0] {
+                for low in &pair[1] {
+                    assert_eq!(
+                        parse(&format!("a {} b {} c", high, low)),
+                        format!("((a {} b) {} c)", high, low)
+                    );
+                    assert_eq!(
+                        parse(&format!("a {} b {} c", low, high)),
+                        format!("(a {} (b {} c))", low, high)
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            parse("a || b && c | d ^ e & f == g < h << i + j * k"),
+            "(a || (b && (c | (d ^ (e & (f == (g < (h << (i + (j * k))))))))))"
+        );
+    }
+
+    #[test]
+    pub fn test_associativity() {
+        for level in [
+            ["*", "/"],
+            ["+", "-"],
+            ["<<", ">>"],
+            ["<", ">="],
+            ["==", "!="],
+            ["&", "&"],
+            ["^", "^"],
+            ["|", "|"],
+            ["&&", "&&"],
+            ["||", "||"],
+        ] {
+            assert_eq!(
+                parse(&format!("a {} b {} c", level[0], level[1])),
+                format!("((a {} b) {} c)", level[0], level[1])
+            );
+        }
+        assert_eq!(parse("a ? b : c ? d : e"), "(a ? b : (c ? d : e))");
+    }
+
+    #[test]
+    pub fn test_unary() {
+        assert_eq!(parse("-a * b"), "((-a) * b)");
+        assert_eq!(parse("!a && ~b"), "((!a) && (~b))");
+        assert_eq!(parse("- -a"), "(-(-a))");
+        assert_eq!(parse("+a - -1"), "((+a) - (-1))");
+        assert_eq!(parse("-f(a)"), "(-f(a))");
+        assert_eq!(parse("!a.b"), "(!(a.b))");
+    }
+
+    #[test]
+    pub fn test_postfix() {
+        assert_eq!(parse("f(a, b + c)(d)"), "f(a, (b + c))(d)");
+        assert_eq!(parse("a.b->c.d"), "(((a.b)->c).d)");
+        assert_eq!(parse("a->f(1).b"), "((a->f)(1).b)");
+        assert_eq!(parse("a.b * c->d"), "((a.b) * (c->d))");
+    }
+
+    #[test]
+    pub fn test_assign() {
+        let ops = [
+            "=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>=",
+        ];
+        for op in ops {
+            assert_eq!(parse(&format!("a {} b", op)), format!("(a {} b)", op));
+        }
+        assert_eq!(parse("a[i] = b = 0"), "((a[i]) = (b = 0))");
+        assert_eq!(parse("p->x += 2 * y"), "((p->x) += (2 * y))");
+        assert_eq!(parse("a = b ? c : d"), "(a = (b ? c : d))");
+        assert_eq!(parse("a ? b : (c = d)"), "(a ? b : [(c = d)])");
+        assert_eq!(parse("*(p) = f(a = 1)"), "((*[p]) = f((a = 1)))");
+    }
+
+    #[test]
+    pub fn test_increments() {
+        assert_eq!(parse("i++ + ++j"), "((i++) + (++j))");
+        assert_eq!(parse("--*p"), "(--(*p))");
+        assert_eq!(parse("*p++"), "(*(p++))");
+        assert_eq!(parse("-a[i]--"), "(-((a[i])--))");
+        assert_eq!(parse("a.b[c + 1]->d"), "(((a.b)[(c + 1)])->d)");
+        assert_eq!(parse("++(x)"), "(++[x])");
+    }
+
+    #[test]
+    pub fn test_type_operands() {
+        assert_eq!(parse("(long)a + b"), "(((long)a) + b)");
+        assert_eq!(
+            parse("(char *)(unsigned)-a"),
+            "((char *)((unsigned int)(-a)))"
+        );
+        assert_eq!(parse("(int (*)[3])p[1]"), "((int (*)[3])(p[1]))");
+        assert_eq!(parse("(a)-b"), "([a] - b)");
+        assert_eq!(
+            parse("sizeof(int) * sizeof a++"),
+            "(sizeof(int) * (sizeof (a++)))"
+        );
+        assert_eq!(
+            parse("sizeof (a) + _Alignof(double)"),
+            "((sizeof [a]) + _Alignof(double))"
+        );
+        assert_eq!(parse("&a[1] - &*p"), "((&(a[1])) - (&(*p)))");
+        assert_eq!(
+            parse("(int[]){1, 2}[i] = (int){3}"),
+            "(((int [2]){ 1, 2 }[i]) = (int){ 3 })"
+        );
+        assert_eq!(parse("sizeof (int[]){1, 2}"), "(sizeof (int [2]){ 1, 2 })");
+
+        let unit = "typedef struct p { int x; } P; P q = (P){.x = (P *)0 != 0}; int n = sizeof(P);";
+        assert!(Parser::new(unit).parse_unit().is_ok());
+    }
+
+    #[test]
+    pub fn test_comma() {
+        assert_eq!(parse("a = 1, b += 2, c"), "(((a = 1) , (b += 2)) , c)");
+        assert_eq!(parse("f((a, b), c)"), "f([(a , b)], c)");
+        assert_eq!(parse("a ? b, c : d"), "(a ? (b , c) : d)");
+    }
+
+    #[test]
+    pub fn test_reprint() {
+        for src in [
+            "a + b * c",
+            "(a + b) * c",
+            "a - (b - c)",
+            "*p++ = -(long)x",
+            "(*p)++",
+            "- -a",
+            "&a[i].b->c",
+            "f((a, b), c = d)",
+            "a ? b : c ? d : e",
+            "(a ? b : c) ? d : e",
+            "sizeof(int *) + sizeof x + _Alignof(char [2])",
+            "(unsigned long)(a + b)",
+            "(int){ 1 }",
+            "(int [2]){ 1, [1] = 2 }[i]",
+        ] {
+            assert_eq!(reprint(src), src);
+        }
+    }
+
+    #[test]
+    pub fn test_conditional() {
+        assert_eq!(parse("a || b ? c + d : e"), "((a || b) ? (c + d) : e)");
+        assert_eq!(parse("a ? b ? c : d : e"), "(a ? (b ? c : d) : e)");
+        assert_eq!(parse("a ? b : c || d"), "(a ? b : (c || d))");
+    }
+
+    #[test]
+    pub fn test_group() {
+        assert_eq!(parse("(a + b) * c"), "([(a + b)] * c)");
+        assert_eq!(parse("a - (b - c)"), "(a - [(b - c)])");
+        assert_eq!(parse("(f)(x)"), "[f](x)");
+        assert_eq!(parse("1 + 2.5 * 'c'"), "(1 + (2.5 * 'c'))");
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let error = |src: &str| Parser::new(src).parse_expr().unwrap_err().message;
+        assert_eq!(error("a +"), "expected an expression, found end of file");
+        assert_eq!(error("a ? b c"), "expected ':', found 'c'");
+        assert_eq!(error("(a * b"), "expected ')', found end of file");
+        assert_eq!(error("f(a,)"), "expected an expression, found ')'");
+        assert_eq!(error("a->1"), "expected identifier, found '1'");
+        assert_eq!(error("a[1"), "expected ']', found end of file");
+        assert_eq!(error("a + b = c"), "expression is not assignable");
+        assert_eq!(error("a ? b : c = d"), "expression is not assignable");
+        assert_eq!(error("f()++"), "expression is not assignable");
+        assert_eq!(error("--1"), "expression is not assignable");
+        assert_eq!(error("(a = b) = c"), "expression is not assignable");
+        assert_eq!(error("(int)a = b"), "expression is not assignable");
+        assert_eq!(error("(int *"), "expected ')', found end of file");
+        assert_eq!(error("_Alignof a"), "expected '(', found 'a'");
+        assert_eq!(error("sizeof(int a)"), "unexpected name 'a' in a type name");
+    }
+}
@@ -0,0 +1,327 @@
+use crate::syntax::{
+    ast::{
+        expr::{expr::Expr, literal_expr::Literal},
+        stmt::initializer::{Designator, Initializer},
+    },
+    diagnostic::diagnostic::Diagnostic,
+    tokenizer::token::Token,
+    typing::{tag::Field, types::Type},
+};
+
+use super::parser::{ParseResult, Parser};
+
+fn is_char(typ: &Type) -> bool {
+    matches!(typ, Type::Char | Type::UChar)
+}
+
+/// Unnamed bit-fields are skipped by initializers.
+fn is_initialized(field: &Field) -> bool {
+    field.name.is_some() || field.bits.is_none()
+}
+
+/// The string a `char` array is initialized from, braced or not.
+fn string_literal(init: &Initializer) -> Option<&str> {
+    match init {
+        Initializer::Expr(expr) => match expr.as_ref() {
+            Expr::LiteralExpr(_, Literal::String(s)) => Some(s),
+            _ => None,
+        },
+        Initializer::List(_, items) => match items.as_slice() {
+            [(designators, init)] if designators.is_empty() => string_literal(init),
+            _ => None,
+        },
+    }
+}
+
+impl Parser {
+    // = 1
+    // = { 1, .x = 2, [3] = { 4 } }
+    pub(super) fn parse_initializer(&mut self) -> ParseResult<Initializer> {
+        let start = self.attr();
+        if !self.eat(Token::LBrace) {
+            return Ok(Initializer::Expr(Box::new(self.parse_assign()?)));
+        }
+        let mut items = Vec::new();
+        while !self.is(Token::RBrace) {
+            let designators = self.parse_designators()?;
+            if !designators.is_empty() {
+                self.expect(Token::Assign)?;
+            }
+            items.push((designators, self.parse_initializer()?));
+            // a trailing comma is allowed
+            if !self.eat(Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::RBrace)?;
+        Ok(Initializer::List(self.attr_from(&start), items))
+    }
+
+    // .x[3].y
+    fn parse_designators(&mut self) -> ParseResult<Vec<Designator>> {
+        let mut designators = Vec::new();
+        loop {
+            if self.eat(Token::Dot) {
+                designators.push(Designator::Field(self.expect_id()?));
+            } else if self.eat(Token::LBraket) {
+                let attr = self.attr();
+                let expr = self.parse_conditional()?;
+                let index = self.constant(&expr).ok_or_else(|| {
+                    Diagnostic::error(
+                        attr.clone(),
+                        String::from("array designator must be an integer constant"),
+                    )
+                })?;
+                if index < 0 {
+                    let message = format!("array designator {} is negative", index);
+                    return Err(Diagnostic::error(attr, message));
+                }
+                self.expect(Token::RBraket)?;
+                designators.push(Designator::Index(index));
+            } else {
+                return Ok(designators);
+            }
+        }
+    }
+
+    /// The type of a declaration with an initializer, whose array size
+    /// may come from the initializer: `int a[] = {1, 2}` is an `int[2]`
+    /// and `char s[] = "ab"` a `char[3]`.
+    pub(super) fn complete_type(&self, typ: Type, init: &Initializer) -> Type {
+        let elem = match typ {
+            Type::Array(elem, None) => elem,
+            typ => return typ,
+        };
+        if is_char(&elem) {
+            if let Some(s) = string_literal(init) {
+                return Type::Array(elem, Some(s.len() + 1));
+            }
+        }
+        let items = match init {
+            Initializer::List(_, items) => items,
+            Initializer::Expr(_) => return Type::Array(elem, None),
+        };
+        // scalars without braces fill an aggregate element in turn
+        let scalars = self.scalars(&elem);
+        let (mut index, mut filled, mut size) = (0, 0, 0);
+        for (designators, init) in items {
+            if let Some(Designator::Index(i)) = designators.first() {
+                index = *i as usize;
+                // `[1].x = 1` goes on from inside the element
+                filled = self.offset(&elem, &designators[1..]);
+            }
+            let braced = matches!(init, Initializer::List(_, _))
+                || (matches!(elem.as_ref(), Type::Array(e, _) if is_char(e))
+                    && string_literal(init).is_some());
+            if (braced && designators.len() <= 1) || scalars <= 1 {
+                if filled > 0 {
+                    index += 1;
+                    filled = 0;
+                }
+                index += 1;
+            } else {
+                filled += 1;
+                if filled == scalars {
+                    index += 1;
+                    filled = 0;
+                }
+            }
+            size = size.max(index + (filled > 0) as usize);
+        }
+        Type::Array(elem, Some(size))
+    }
+
+    /// How many scalars of a `typ` come before the member `designators`
+    /// pick.
+    fn offset(&self, typ: &Type, designators: &[Designator]) -> usize {
+        let (first, rest) = match designators.split_first() {
+            Some(split) => split,
+            None => return 0,
+        };
+        match (typ, first) {
+            (Type::Array(elem, _), Designator::Index(i)) => {
+                *i as usize * self.scalars(elem) + self.offset(elem, rest)
+            }
+            (Type::Struct(id) | Type::Union(id), Designator::Field(name)) => {
+                let fields = &self.tag(*id).fields;
+                let i = match fields.iter().position(|f| f.name.as_ref() == Some(name)) {
+                    Some(i) => i,
+                    None => return 0,
+                };
+                let before = match typ {
+                    Type::Struct(_) => fields[..i]
+                        .iter()
+                        .filter(|field| is_initialized(field))
+                        .map(|field| self.scalars(&field.typ))
+                        .sum(),
+                    _ => 0,
+                };
+                before + self.offset(&fields[i].typ, rest)
+            }
+            _ => 0,
+        }
+    }
+
+    /// How many scalars initialize a `typ` when its braces are left out.
+    fn scalars(&self, typ: &Type) -> usize {
+        match typ {
+            Type::Array(elem, size) => size.unwrap_or(0) * self.scalars(elem),
+            Type::Struct(id) => self
+                .tag(*id)
+                .fields
+                .iter()
+                .filter(|field| is_initialized(field))
+                .map(|field| self.scalars(&field.typ))
+                .sum(),
+            Type::Union(id) => match self.tag(*id).fields.first() {
+                Some(field) => self.scalars(&field.typ),
+                None => 0,
+            },
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::attr::Attr;
+    use crate::syntax::{
+        ast::{
+            expr::{expr::Expr, literal_expr::Literal},
+            stmt::{
+                declare_stmt::Declare,
+                initializer::{Designator, Initializer},
+                stmt::Stmt,
+            },
+        },
+        parser::parser::Parser,
+        typing::types::Type,
+    };
+
+    fn int(i: i64) -> Initializer {
+        Initializer::Expr(Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(i))))
+    }
+
+    /// The type and initializer of the last declaration in `src`.
+    fn declare(src: &str) -> (Type, Option<Initializer>) {
+        let unit = Parser::new(src).parse_unit().unwrap();
+        match unit.stmts.into_iter().last() {
+            Some(Stmt::Declare(Declare::DeclareIdentifier(_, _, typ, init))) => (typ, init),
+            stmt => panic!("expected a declaration, found {:?}", stmt),
+        }
+    }
+
+    fn array(elem: Type, size: usize) -> Type {
+        Type::Array(Box::new(elem), Some(size))
+    }
+
+    #[test]
+    pub fn test_initializer_lists() {
+        assert_eq!(declare("int a = 1;"), (Type::Int, Some(int(1))));
+        assert_eq!(
+            declare("int a[3] = {1, 2,};"),
+            (
+                array(Type::Int, 3),
+                Some(Initializer::List(
+                    Attr::new(),
+                    vec![(vec![], int(1)), (vec![], int(2))]
+                ))
+            )
+        );
+        assert_eq!(
+            declare("int a[2][2] = {{1}, {}};"),
+            (
+                array(array(Type::Int, 2), 2),
+                Some(Initializer::List(
+                    Attr::new(),
+                    vec![
+                        (
+                            vec![],
+                            Initializer::List(Attr::new(), vec![(vec![], int(1))])
+                        ),
+                        (vec![], Initializer::List(Attr::new(), vec![])),
+                    ]
+                ))
+            )
+        );
+    }
+
+    #[test]
+    pub fn test_designators() {
+        let (_, init) = declare("struct p { int x, y[4]; } a = { .y[1 + 1] = 5, .x = 1, 2 };");
+        assert_eq!(
+            init,
+            Some(Initializer::List(
+                Attr::new(),
+                vec![
+                    (
+                        vec![Designator::Field(String::from("y")), Designator::Index(2)],
+                        int(5)
+                    ),
+                    (vec![Designator::Field(String::from("x"))], int(1)),
+                    (vec![], int(2)),
+                ]
+            ))
+        );
+        let (_, init) = declare("enum { N = 3 }; int a[5] = { [N] = 1 };");
+        assert_eq!(
+            init,
+            Some(Initializer::List(
+                Attr::new(),
+                vec![(vec![Designator::Index(3)], int(1))]
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_array_size() {
+        assert_eq!(declare("int a[] = {1, 2, 3};").0, array(Type::Int, 3));
+        assert_eq!(declare("int a[] = {[4] = 1, 2};").0, array(Type::Int, 6));
+        assert_eq!(
+            declare("int a[] = {[4] = 1, [1] = 2};").0,
+            array(Type::Int, 5)
+        );
+        assert_eq!(declare("char s[] = \"abc\";").0, array(Type::Char, 4));
+        assert_eq!(declare("char s[] = {\"abc\"};").0, array(Type::Char, 4));
+        assert_eq!(
+            declare("char s[][3] = {\"ab\", \"c\"};").0,
+            array(array(Type::Char, 3), 2)
+        );
+        // the braces of the elements are left out
+        assert_eq!(
+            declare("int a[][2] = {1, 2, 3};").0,
+            array(array(Type::Int, 2), 2)
+        );
+        assert_eq!(
+            declare("struct p { int x, y; } a[] = {1, 2, {3}, 4};").0,
+            array(Type::Struct(crate::syntax::typing::tag::TagId(0)), 3)
+        );
+        assert_eq!(
+            declare("int a[][2] = {[1] = 1, 2};").0,
+            array(array(Type::Int, 2), 2)
+        );
+        assert_eq!(
+            declare("int a[][2] = {[1][1] = 1, 2};").0,
+            array(array(Type::Int, 2), 3)
+        );
+        assert_eq!(
+            declare("int a[] = 1;").0,
+            Type::Array(Box::new(Type::Int), None)
+        );
+    }
+
+    #[test]
+    pub fn test_initializer_errors() {
+        let error = |src: &str| Parser::new(src).parse_unit().unwrap_err().remove(0).message;
+        assert_eq!(
+            error("int x; int a[2] = { [x] = 1 };"),
+            "array designator must be an integer constant"
+        );
+        assert_eq!(
+            error("int a[2] = { [-1] = 1 };"),
+            "array designator -1 is negative"
+        );
+        assert_eq!(error("int a[2] = { .x 1 };"), "expected '=', found '1'");
+        assert_eq!(error("int a[2] = { 1 2 };"), "expected '}', found '2'");
+    }
+}
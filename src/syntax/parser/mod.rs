@@ -1 +1,6 @@
+pub mod declaration;
+pub mod expression;
+pub mod initializer;
 pub mod parser;
+pub mod statement;
+pub mod tag;
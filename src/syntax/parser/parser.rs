@@ -1,72 +1,418 @@
+use std::{collections::HashMap, mem};
+
 use crate::syntax::{
-    ast::{
-        expr::{expr::Expr, literal_expr::Literal},
-        stmt::stmt::Stmt,
+    ast::{ast_module::CompilationUnit, stmt::stmt::Stmt},
+    diagnostic::diagnostic::Diagnostic,
+    tokenizer::{attr::Attr, concat::Concat, token::Token, tokenizer::Tokenizer},
+    typing::{
+        tag::{Tag, TagId, TagTable},
+        types::Type,
     },
-    tokenizer::{token::Token, tokenizer::Tokenizer},
 };
 
-pub struct Parser<'a> {
-    tokens: std::iter::Peekable<Tokenizer<'a>>,
+pub type ParseResult<T> = Result<T, Diagnostic>;
+
+/// What an ordinary identifier in scope was declared as.
+#[derive(Debug, Clone)]
+pub(super) enum Ordinary {
+    Variable,
+    Typedef(Type),
+    EnumConstant(i64),
+}
+
+/// The identifiers a block, a parameter list or the file declares. Tags
+/// have a namespace of their own.
+#[derive(Default)]
+struct Scope {
+    names: HashMap<String, Ordinary>,
+    tags: HashMap<String, TagId>,
+}
+
+/// A recursive-descent parser over preprocessed tokens. The grammar is
+/// split by kind over `declaration`, `statement` and `expression`.
+pub struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // innermost last
+    scopes: Vec<Scope>,
+    tags: TagTable,
+    // the tags defined since the last declaration took them
+    definitions: Vec<Stmt>,
+    // the syntax errors recovered from so far
+    diagnostics: Vec<Diagnostic>,
 }
 
-impl<'a> Parser<'a> {
-    pub fn new(input: &'a str) -> Self {
-        let tokenizer = Tokenizer::new(input);
+impl Parser {
+    /// Parses `input` as it is, without preprocessing it.
+    pub fn new(input: &str) -> Self {
+        let tokens = Tokenizer::new(input)
+            .take_while(|t| *t != Token::EOF)
+            .collect();
+        Parser::from_tokens(tokens)
+    }
+
+    /// Parses tokens that were already preprocessed, concatenating
+    /// adjacent string literals first.
+    pub fn from_tokens(tokens: Vec<Token>) -> Self {
+        let mut tokens: Vec<Token> = Concat::new(tokens.into_iter())
+            .filter(|t| *t != Token::EOF)
+            .collect();
+        tokens.push(Token::EOF);
         Parser {
-            tokens: tokenizer.peekable(),
+            tokens,
+            pos: 0,
+            scopes: vec![Scope::default()],
+            tags: TagTable::new(),
+            definitions: Vec::new(),
+            diagnostics: Vec::new(),
         }
     }
-}
 
-impl<'a> Iterator for Parser<'a> {
-    type Item = Stmt;
+    /// Parses a whole translation unit, reporting every syntax error in
+    /// it.
+    pub fn parse_unit(&mut self) -> Result<CompilationUnit, Vec<Diagnostic>> {
+        let unit = self.parse_recovering();
+        if self.has_errors() {
+            return Err(mem::take(&mut self.diagnostics));
+        }
+        Ok(unit)
+    }
 
-    fn next(&mut self) -> Option<Stmt> {
-        while let Some(t) = self.tokens.next() {
-            match t {
-                Token::Int64(_, i) => {
-                    return Some(Stmt::Expr(Box::new(Expr::LiteralExpr(Literal::Int(i)))));
-                }
-                Token::String(_, s) => {
-                    return Some(Stmt::Expr(Box::new(Expr::LiteralExpr(Literal::String(s)))));
+    /// Parses a whole translation unit, going on after syntax errors.
+    /// What could not be parsed is a `Stmt::Error(_)` in the unit, and the
+    /// errors are in `diagnostics`.
+    pub fn parse_recovering(&mut self) -> CompilationUnit {
+        let mut stmts: Vec<Stmt> = Vec::new();
+        while !self.at_eof() {
+            let start = self.position();
+            match self.parse_external_declaration() {
+                Ok(declaration) => stmts.extend(declaration),
+                Err(error) => stmts.extend(self.recover(start, error)),
+            }
+        }
+        CompilationUnit {
+            stmts,
+            tags: mem::take(&mut self.tags),
+        }
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.is_error())
+    }
+
+    /// Records `error` in the declaration or statement that began at
+    /// `start`, and skips to where parsing can go on: past the `;` that
+    /// ends it or the `{ ... }` it ends with, or up to a `}` that closes
+    /// an enclosing block or the start of a declaration.
+    ///
+    /// Returns the tags the declaration defined before the error,
+    /// followed by a `Stmt::Error` spanning the skipped tokens.
+    pub(super) fn recover(&mut self, start: usize, error: Diagnostic) -> Vec<Stmt> {
+        self.diagnostics.push(error);
+        // skip at least one token, so the same error can't come again
+        if self.pos == start {
+            self.advance();
+        }
+        // the braces opened since `start` still have to be closed, as in
+        // `int a[] = {1 2};`
+        let mut depth = self.tokens[start..self.pos]
+            .iter()
+            .fold(0, |depth: i32, token| match token {
+                Token::LBrace(_) => depth + 1,
+                Token::RBrace(_) => depth - 1,
+                _ => depth,
+            })
+            .max(0);
+        let mut opened_before = depth > 0;
+        loop {
+            match self.peek() {
+                Token::EOF => break,
+                Token::Semicolon(_) if depth == 0 => {
+                    self.advance();
+                    break;
                 }
-                Token::Char(_, c) => {
-                    return Some(Stmt::Expr(Box::new(Expr::LiteralExpr(Literal::Char(c)))));
+                Token::RBrace(_) if depth == 0 => break,
+                Token::RBrace(_) => {
+                    depth -= 1;
+                    if depth == 0 && !opened_before {
+                        self.advance();
+                        break;
+                    }
+                    opened_before &= depth > 0;
                 }
-                _ => panic!("Not supported yet"),
+                Token::LBrace(_) => depth += 1,
+                _ if depth == 0 && self.is_declaration_start() => break,
+                _ => {}
             }
+            self.advance();
+        }
+        let start = self.tokens[start].attr().cloned().unwrap_or_default();
+        let mut stmts = self.take_definitions();
+        stmts.push(Stmt::Error(self.attr_from(&start)));
+        stmts
+    }
+
+    pub(super) fn push_scope(&mut self) {
+        self.scopes.push(Scope::default());
+    }
+
+    pub(super) fn pop_scope(&mut self) {
+        self.scopes.pop();
+    }
+
+    /// Declares `name` in the innermost scope, hiding any outer
+    /// declaration.
+    pub(super) fn declare_name(&mut self, name: &str, ordinary: Ordinary) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.names.insert(name.to_owned(), ordinary);
+        }
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Ordinary> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.names.get(name))
+    }
+
+    /// The type `name` stands for, if the declaration of `name` in scope
+    /// is a typedef.
+    pub(super) fn typedef(&self, name: &str) -> Option<&Type> {
+        match self.lookup(name) {
+            Some(Ordinary::Typedef(typ)) => Some(typ),
+            _ => None,
+        }
+    }
+
+    /// The value of `name`, if the declaration in scope is an enum
+    /// constant.
+    pub(super) fn enum_constant(&self, name: &str) -> Option<i64> {
+        match self.lookup(name) {
+            Some(Ordinary::EnumConstant(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The tag `name` in scope, or only in the innermost scope for
+    /// `innermost`.
+    pub(super) fn lookup_tag(&self, name: &str, innermost: bool) -> Option<TagId> {
+        let scopes = if innermost {
+            &self.scopes[self.scopes.len() - 1..]
+        } else {
+            &self.scopes[..]
+        };
+        scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.tags.get(name))
+            .copied()
+    }
+
+    /// Adds `tag` to the table, declaring its name in the innermost scope.
+    pub(super) fn declare_tag(&mut self, tag: Tag) -> TagId {
+        let name = tag.name.clone();
+        let id = self.tags.add(tag);
+        if let (Some(name), Some(scope)) = (name, self.scopes.last_mut()) {
+            scope.tags.insert(name, id);
         }
-        None
+        id
+    }
+
+    pub(super) fn tag(&self, id: TagId) -> &Tag {
+        self.tags.tag(id)
+    }
+
+    pub(super) fn tags(&self) -> &TagTable {
+        &self.tags
+    }
+
+    pub(super) fn tag_mut(&mut self, id: TagId) -> &mut Tag {
+        self.tags.tag_mut(id)
+    }
+
+    /// Records the definition of a tag, for the declaration being parsed
+    /// to emit before its declarators.
+    pub(super) fn add_definition(&mut self, stmt: Stmt) {
+        self.definitions.push(stmt);
+    }
+
+    pub(super) fn take_definitions(&mut self) -> Vec<Stmt> {
+        mem::take(&mut self.definitions)
+    }
+
+    /// Runs `parse`, forgetting the tag definitions it records.
+    pub(super) fn dropping_definitions<T>(&mut self, parse: fn(&mut Parser) -> T) -> T {
+        let len = self.definitions.len();
+        let result = parse(self);
+        self.definitions.truncate(len);
+        result
+    }
+
+    /// Runs `parse`, failing at the first tag definition it records, for
+    /// the places that have no declaration to emit it in.
+    pub(super) fn forbidding_definitions<T>(
+        &mut self,
+        parse: fn(&mut Parser) -> ParseResult<T>,
+        place: &str,
+    ) -> ParseResult<T> {
+        let len = self.definitions.len();
+        let result = parse(self);
+        match self.definitions.split_off(len).first() {
+            Some(definition) if result.is_ok() => Err(Diagnostic::error(
+                definition.attr().clone(),
+                format!("defining a tag in {} is not supported", place),
+            )),
+            _ => result,
+        }
+    }
+
+    pub(super) fn peek(&self) -> &Token {
+        self.peek_nth(0)
+    }
+
+    pub(super) fn peek_nth(&self, n: usize) -> &Token {
+        let last = self.tokens.len() - 1;
+        &self.tokens[(self.pos + n).min(last)]
+    }
+
+    pub(super) fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub(super) fn at_eof(&self) -> bool {
+        *self.peek() == Token::EOF
+    }
+
+    /// Consumes the next token; at the end it keeps returning `EOF`.
+    pub(super) fn advance(&mut self) -> Token {
+        let token = self.peek().clone();
+        if token != Token::EOF {
+            self.pos += 1;
+        }
+        token
+    }
+
+    /// Whether the next token is of the kind `kind` constructs, which is
+    /// a token variant such as `Token::Semicolon`.
+    pub(super) fn is(&self, kind: fn(Attr) -> Token) -> bool {
+        is_kind(self.peek(), kind)
+    }
+
+    pub(super) fn is_nth(&self, n: usize, kind: fn(Attr) -> Token) -> bool {
+        is_kind(self.peek_nth(n), kind)
+    }
+
+    /// Consumes the next token if it is of the kind `kind`.
+    pub(super) fn eat(&mut self, kind: fn(Attr) -> Token) -> bool {
+        let matched = self.is(kind);
+        if matched {
+            self.advance();
+        }
+        matched
+    }
+
+    pub(super) fn expect(&mut self, kind: fn(Attr) -> Token) -> ParseResult<Token> {
+        if self.is(kind) {
+            return Ok(self.advance());
+        }
+        let expected = kind(Attr::new());
+        Err(self.error(format!("expected '{}', found {}", expected, self.found())))
+    }
+
+    pub(super) fn expect_id(&mut self) -> ParseResult<String> {
+        match self.peek() {
+            Token::ID(_, name) => {
+                let name = name.clone();
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.error(format!("expected identifier, found {}", self.found()))),
+        }
+    }
+
+    /// The next token, described for an error message.
+    pub(super) fn found(&self) -> String {
+        match self.peek() {
+            Token::EOF => String::from("end of file"),
+            token => format!("'{}'", token),
+        }
+    }
+
+    /// An error at the next token, or at the last one at the end.
+    pub(super) fn error(&self, message: String) -> Diagnostic {
+        Diagnostic::error(self.attr(), message)
+    }
+
+    /// The attr of the next token, or of the last one at the end.
+    pub(super) fn attr(&self) -> Attr {
+        let token = match self.peek() {
+            Token::EOF if self.pos > 0 => &self.tokens[self.pos - 1],
+            token => token,
+        };
+        token.attr().cloned().unwrap_or_default()
+    }
+
+    /// The attr of a node that starts at `start` and ends with the last
+    /// token consumed.
+    pub(super) fn attr_from(&self, start: &Attr) -> Attr {
+        let mut attr = start.clone();
+        if let Some(end) = self.pos.checked_sub(1).and_then(|i| self.tokens[i].attr()) {
+            attr.extend_to(end);
+        }
+        attr
     }
 }
 
+fn is_kind(token: &Token, kind: fn(Attr) -> Token) -> bool {
+    mem::discriminant(token) == mem::discriminant(&kind(Attr::new()))
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::syntax::ast::{
-        expr::{expr::Expr, literal_expr::Literal},
-        stmt::stmt::Stmt,
+    use crate::syntax::tokenizer::attr::Attr;
+    use std::fs;
+
+    use crate::syntax::{
+        ast::{
+            expr::{assign_expr::Assign, binary_expr::Binary, expr::Expr, literal_expr::Literal},
+            stmt::{declare_stmt::Declare, initializer::Initializer, stmt::Stmt},
+        },
+        preprocessor::preprocessor::Preprocessor,
+        typing::types::{Prototype, Type},
     };
 
     use super::Parser;
 
+    fn int(i: i64) -> Box<Expr> {
+        Box::new(Expr::LiteralExpr(Attr::new(), Literal::Int(i)))
+    }
+
+    fn id(name: &str) -> Box<Expr> {
+        Box::new(Expr::LiteralExpr(
+            Attr::new(),
+            Literal::ID(String::from(name)),
+        ))
+    }
+
     #[test]
     pub fn test_parser_int_expr() {
         let mut parser = Parser::new("1");
-        assert_eq!(
-            parser.next(),
-            Some(Stmt::Expr(Box::new(Expr::LiteralExpr(Literal::Int(1)))))
-        );
+        assert_eq!(parser.parse_expr(), Ok(*int(1)));
     }
 
     #[test]
     pub fn test_parser_string_expr() {
         let mut parser = Parser::new("\"abc\"");
         assert_eq!(
-            parser.next(),
-            Some(Stmt::Expr(Box::new(Expr::LiteralExpr(Literal::String(
-                String::from("abc")
-            )))))
+            parser.parse_expr(),
+            Ok(Expr::LiteralExpr(
+                Attr::new(),
+                Literal::String(String::from("abc"))
+            ))
         );
     }
 
@@ -74,8 +420,412 @@ mod tests {
     pub fn test_parser_char_expr() {
         let mut parser = Parser::new("'a'");
         assert_eq!(
-            parser.next(),
-            Some(Stmt::Expr(Box::new(Expr::LiteralExpr(Literal::Char('a')))))
+            parser.parse_expr(),
+            Ok(Expr::LiteralExpr(Attr::new(), Literal::Char('a')))
         )
     }
+
+    #[test]
+    pub fn test_parse_example() {
+        let src = fs::read_to_string("examples/c1.c").unwrap();
+        let unit = Parser::new(&src).parse_unit().unwrap();
+        assert_eq!(
+            unit.stmts,
+            vec![
+                Stmt::Declare(Declare::DeclareFunction {
+                    attr: Attr::new(),
+                    name: String::from("scheme_entry"),
+                    parameters: vec![],
+                    prototype: Prototype::Unspecified,
+                    return_type: Type::Int,
+                    body: vec![Stmt::Return(Attr::new(), Some(int(42)))],
+                }),
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("x"),
+                    Type::Int,
+                    Some(Initializer::Expr(int(10)))
+                )),
+                Stmt::Declare(Declare::DeclareFunction {
+                    attr: Attr::new(),
+                    name: String::from("main"),
+                    parameters: vec![
+                        (String::from("argc"), Type::Int),
+                        (
+                            String::from("argv"),
+                            Type::Pointer(Box::new(Type::Pointer(Box::new(Type::Char))))
+                        ),
+                    ],
+                    prototype: Prototype::Fixed,
+                    return_type: Type::Int,
+                    body: vec![
+                        Stmt::Declare(Declare::DeclareIdentifier(
+                            Attr::new(),
+                            String::from("c"),
+                            Type::Int,
+                            Some(Initializer::Expr(Box::new(Expr::BinaryExpr(
+                                Attr::new(),
+                                Binary::Call(id("scheme_entry"), vec![]),
+                            ))))
+                        )),
+                        Stmt::Return(Attr::new(), Some(int(0))),
+                    ],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_statements() {
+        let src = "void f(void) { int a, b[3]; a = g(1, \"s\"); { return; } h(); }";
+        let unit = Parser::new(src).parse_unit().unwrap();
+        let body = match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareFunction {
+                parameters,
+                return_type,
+                body,
+                ..
+            }) => {
+                assert_eq!(parameters, &vec![]);
+                assert_eq!(return_type, &Type::Void);
+                body
+            }
+            stmt => panic!("unexpected {:?}", stmt),
+        };
+        assert_eq!(
+            body,
+            &vec![
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("a"),
+                    Type::Int,
+                    None
+                )),
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("b"),
+                    Type::Array(Box::new(Type::Int), Some(3)),
+                    None
+                )),
+                Stmt::Expr(
+                    Attr::new(),
+                    Box::new(Expr::AssignExpr(
+                        Attr::new(),
+                        Assign::Assign(
+                            id("a"),
+                            Box::new(Expr::BinaryExpr(
+                                Attr::new(),
+                                Binary::Call(
+                                    id("g"),
+                                    vec![
+                                        *int(1),
+                                        Expr::LiteralExpr(
+                                            Attr::new(),
+                                            Literal::String(String::from("s"))
+                                        )
+                                    ]
+                                )
+                            ))
+                        )
+                    ))
+                ),
+                Stmt::Block(Attr::new(), vec![Stmt::Return(Attr::new(), None)]),
+                Stmt::Expr(
+                    Attr::new(),
+                    Box::new(Expr::BinaryExpr(Attr::new(), Binary::Call(id("h"), vec![])))
+                ),
+            ]
+        );
+    }
+
+    /// The statements of `body`, parsed as a function body and printed
+    /// one per line.
+    fn body(body: &str) -> String {
+        let src = format!("void f() {{ {} }}", body);
+        let unit = Parser::new(&src).parse_unit().unwrap();
+        match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => body
+                .iter()
+                .map(|stmt| stmt.to_string())
+                .collect::<Vec<String>>()
+                .join("\n"),
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
+
+    #[test]
+    pub fn test_control_flow() {
+        assert_eq!(
+            body("if (a) if (b) f(); else g();"),
+            "if (a) if (b) f(); else g();"
+        );
+        assert_eq!(
+            body("if (a < b) { f(); } else ;"),
+            "if (a < b) {\n    f();\n} else ;"
+        );
+        assert_eq!(body("while (a) a = a - 1;"), "while (a) a = a - 1;");
+        assert_eq!(
+            body("do { f(); } while (a);"),
+            "do {\n    f();\n} while (a);"
+        );
+        assert_eq!(body("for (;;) break;"), "for (;;) break;");
+        assert_eq!(
+            body("for (i = 0; i < n; i = i + 1) continue;"),
+            "for (i = 0; i < n; i = i + 1) continue;"
+        );
+        assert_eq!(body("for (f(); ; ) {}"), "for (f();;) {\n}");
+        assert_eq!(
+            body("for (i = 0; i < n; i++) a[i] = b = 0;"),
+            "for (i = 0; i < n; i++) a[i] = b = 0;"
+        );
+    }
+
+    #[test]
+    pub fn test_for_declaration() {
+        let src = "void f() { for (int i = 0; i < 3;) ; }";
+        let unit = Parser::new(src).parse_unit().unwrap();
+        match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => assert_eq!(
+                body,
+                &vec![Stmt::For(
+                    Attr::new(),
+                    vec![Stmt::Declare(Declare::DeclareIdentifier(
+                        Attr::new(),
+                        String::from("i"),
+                        Type::Int,
+                        Some(Initializer::Expr(int(0)))
+                    ))],
+                    Some(Box::new(Expr::BinaryExpr(
+                        Attr::new(),
+                        Binary::Lt(id("i"), int(3))
+                    ))),
+                    None,
+                    Box::new(Stmt::Empty(Attr::new()))
+                )]
+            ),
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
+
+    #[test]
+    pub fn test_switch_and_jumps() {
+        assert_eq!(
+            body("switch (a) { case 1: case 2: f(); break; default: g(); }"),
+            "switch (a) {\n    case 1: case 2: f();\n    break;\n    default: g();\n}"
+        );
+        assert_eq!(
+            body("again: if (a) goto again; out: ;"),
+            "again: if (a) goto again;\nout: ;"
+        );
+        assert_eq!(body(";;"), ";\n;");
+    }
+
+    #[test]
+    pub fn test_declarations() {
+        let src = "unsigned long n; static const char *s = \"x\"; int f(int, char *p);";
+        let unit = Parser::new(src).parse_unit().unwrap();
+        assert_eq!(
+            unit.stmts,
+            vec![
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("n"),
+                    Type::ULong,
+                    None
+                )),
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("s"),
+                    Type::Pointer(Box::new(Type::Char)),
+                    Some(Initializer::Expr(Box::new(Expr::LiteralExpr(
+                        Attr::new(),
+                        Literal::String(String::from("x")),
+                    ))))
+                )),
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("f"),
+                    Type::Function(
+                        vec![
+                            Box::new(Type::Int),
+                            Box::new(Type::Pointer(Box::new(Type::Char)))
+                        ],
+                        Box::new(Type::Int),
+                        Prototype::Fixed
+                    ),
+                    None
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let error = |src: &str| Parser::new(src).parse_unit().unwrap_err().remove(0).message;
+        assert_eq!(error("int x"), "expected ';', found end of file");
+        assert_eq!(error("int f() { return 1 }"), "expected ';', found '}'");
+        assert_eq!(error("int 1;"), "expected identifier, found '1'");
+        assert_eq!(error("x;"), "expected a declaration, found 'x'");
+        assert_eq!(error("long short a;"), "cannot combine 'short' with 'long'");
+        assert_eq!(
+            error("int f() { return ); }"),
+            "expected an expression, found ')'"
+        );
+        assert_eq!(error("void f() { if a f(); }"), "expected '(', found 'a'");
+        assert_eq!(
+            error("void f() { do f(); (a); }"),
+            "expected 'while', found '('"
+        );
+        assert_eq!(
+            error("void f() { goto 1; }"),
+            "expected identifier, found '1'"
+        );
+        assert_eq!(error("void f() { break }"), "expected ';', found '}'");
+    }
+
+    #[test]
+    pub fn test_recovery() {
+        let src = "int a = ;\nint b;\nvoid f() {\n  x = 1 y = 2;\n  return 1\n}\nint c[] = {1 2};\n} int d;";
+        let mut parser = Parser::new(src);
+        let unit = parser.parse_recovering();
+        let errors: Vec<(&str, u32, u32)> = parser
+            .diagnostics()
+            .iter()
+            .map(|d| (d.message.as_str(), d.attr.start().row, d.attr.start().col))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("expected an expression, found ';'", 1, 9),
+                ("expected ';', found 'y'", 4, 9),
+                ("expected ';', found '}'", 6, 1),
+                ("expected '}', found '2'", 7, 14),
+                ("expected a declaration, found '}'", 8, 1),
+            ]
+        );
+        assert_eq!(
+            unit.stmts,
+            vec![
+                Stmt::Error(Attr::new()),
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("b"),
+                    Type::Int,
+                    None
+                )),
+                Stmt::Declare(Declare::DeclareFunction {
+                    attr: Attr::new(),
+                    name: String::from("f"),
+                    parameters: vec![],
+                    prototype: Prototype::Unspecified,
+                    return_type: Type::Void,
+                    body: vec![Stmt::Error(Attr::new()), Stmt::Error(Attr::new())],
+                }),
+                Stmt::Error(Attr::new()),
+                Stmt::Error(Attr::new()),
+                Stmt::Declare(Declare::DeclareIdentifier(
+                    Attr::new(),
+                    String::from("d"),
+                    Type::Int,
+                    None
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_recovery_sync() {
+        let messages = |src: &str| -> Vec<String> {
+            match Parser::new(src).parse_unit() {
+                Ok(_) => vec![],
+                Err(errors) => errors.into_iter().map(|d| d.message).collect(),
+            }
+        };
+        // a declaration start ends the skipped tokens
+        assert_eq!(
+            messages("int a = 1 int b = ; int c;"),
+            vec![
+                "expected ';', found 'int'",
+                "expected an expression, found ';'"
+            ]
+        );
+        // a block after the error is skipped as a whole
+        assert_eq!(
+            messages("void f() { if (x y) { a b; } c; d e; }"),
+            vec!["expected ')', found 'y'", "expected ';', found 'e'"]
+        );
+        assert_eq!(
+            messages("int f(int a b) { return a; } int g;"),
+            vec!["expected ')', found 'b'"]
+        );
+        assert_eq!(
+            messages("struct s { int a b; } x; int y;"),
+            vec!["expected ';', found 'b'"]
+        );
+    }
+
+    #[test]
+    pub fn test_recovery_scopes() {
+        // the typedef goes out of scope with the block it failed in, so
+        // `T * y` in `g` is a multiplication
+        let mut parser = Parser::new(
+            "void f() { typedef int T; for (T i = ; ; ) { T x = ; } } void g() { T * y; }",
+        );
+        let unit = parser.parse_recovering();
+        assert_eq!(parser.diagnostics().len(), 2);
+        match &unit.stmts[1] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => {
+                assert!(matches!(body[..], [Stmt::Expr(_, _)]));
+            }
+            stmt => panic!("expected a function, found {:?}", stmt),
+        }
+    }
+
+    #[test]
+    pub fn test_preprocessed() {
+        let mut pp = Preprocessor::new();
+        let src = "#define RET(x) return x;\nint main() { RET(\"a\" \"b\") }";
+        let tokens = pp.preprocess_str("a.c", src);
+        let unit = Parser::from_tokens(tokens).parse_unit().unwrap();
+        match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => assert_eq!(
+                body,
+                &vec![Stmt::Return(
+                    Attr::new(),
+                    Some(Box::new(Expr::LiteralExpr(
+                        Attr::new(),
+                        Literal::String(String::from("ab"))
+                    )))
+                )]
+            ),
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
+
+    fn span(attr: &Attr) -> String {
+        format!("{}-{}", attr.start(), attr.end())
+    }
+
+    #[test]
+    pub fn test_spans() {
+        let src = "int a = 1 + 2, *b;\nvoid f(void) {\n  while (a) a--;\n}\nstruct S { int x; };";
+        let unit = Parser::new(src).parse_unit().unwrap();
+        let spans: Vec<String> = unit.stmts.iter().map(|stmt| span(stmt.attr())).collect();
+        assert_eq!(spans, vec!["1:5-1:13", "1:16-1:17", "2:1-4:1", "5:1-5:19"]);
+        match &unit.stmts[0] {
+            Stmt::Declare(Declare::DeclareIdentifier(_, _, _, Some(Initializer::Expr(e)))) => {
+                assert_eq!(span(e.attr()), "1:9-1:13")
+            }
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+        let body = match &unit.stmts[2] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => body,
+            stmt => panic!("unexpected {:?}", stmt),
+        };
+        assert_eq!(span(body[0].attr()), "3:3-3:16");
+        match &body[0] {
+            Stmt::While(_, _, stmt) => assert_eq!(span(stmt.attr()), "3:13-3:16"),
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
 }
@@ -0,0 +1,233 @@
+use crate::syntax::{
+    ast::{expr::expr::Expr, stmt::stmt::Stmt},
+    tokenizer::{attr::Attr, token::Token},
+};
+
+use super::parser::{ParseResult, Parser};
+
+impl Parser {
+    /// `{ ... }`, as the statements and declarations inside it.
+    pub(super) fn parse_block(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.push_scope();
+        let stmts = self.parse_block_items();
+        self.pop_scope();
+        stmts
+    }
+
+    fn parse_block_items(&mut self) -> ParseResult<Vec<Stmt>> {
+        self.expect(Token::LBrace)?;
+        let mut stmts = Vec::new();
+        while !self.is(Token::RBrace) && !self.at_eof() {
+            let start = self.position();
+            let item = if self.is_declaration_start() {
+                self.parse_declaration()
+            } else {
+                self.parse_stmt().map(|stmt| vec![stmt])
+            };
+            match item {
+                Ok(item) => stmts.extend(item),
+                Err(error) => stmts.extend(self.recover(start, error)),
+            }
+        }
+        self.expect(Token::RBrace)?;
+        Ok(stmts)
+    }
+
+    pub(super) fn parse_stmt(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        match self.peek() {
+            Token::LBrace(_) => {
+                let stmts = self.parse_block()?;
+                Ok(Stmt::Block(self.attr_from(&start), stmts))
+            }
+            Token::Return(_) => self.parse_return(),
+            Token::If(_) => self.parse_if(),
+            Token::While(_) => self.parse_while(),
+            Token::Do(_) => self.parse_do_while(),
+            Token::For(_) => self.parse_for(),
+            Token::Switch(_) => self.parse_switch(),
+            Token::Case(_) => self.parse_case(),
+            Token::Default(_) => self.parse_default(),
+            Token::Break(_) => self.parse_jump(Stmt::Break),
+            Token::Continue(_) => self.parse_jump(Stmt::Continue),
+            Token::Goto(_) => self.parse_goto(),
+            Token::ID(_, _) if self.is_nth(1, Token::Colon) => self.parse_label(),
+            Token::Semicolon(_) => {
+                self.advance();
+                Ok(Stmt::Empty(start))
+            }
+            _ => self.parse_expr_stmt(),
+        }
+    }
+
+    // return a;
+    // return;
+    fn parse_return(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::Return)?;
+        if self.eat(Token::Semicolon) {
+            return Ok(Stmt::Return(self.attr_from(&start), None));
+        }
+        let expr = self.parse_expr()?;
+        self.expect(Token::Semicolon)?;
+        Ok(Stmt::Return(self.attr_from(&start), Some(Box::new(expr))))
+    }
+
+    // if (a) b; else c;
+    fn parse_if(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::If)?;
+        let cond = self.parse_paren_expr()?;
+        let then = self.parse_stmt()?;
+        // a dangling else belongs to the nearest if
+        let otherwise = if self.eat(Token::Else) {
+            Some(Box::new(self.parse_stmt()?))
+        } else {
+            None
+        };
+        Ok(Stmt::If(
+            self.attr_from(&start),
+            cond,
+            Box::new(then),
+            otherwise,
+        ))
+    }
+
+    // while (a) b;
+    fn parse_while(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::While)?;
+        let cond = self.parse_paren_expr()?;
+        let body = self.parse_stmt()?;
+        Ok(Stmt::While(self.attr_from(&start), cond, Box::new(body)))
+    }
+
+    // do a; while (b);
+    fn parse_do_while(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::Do)?;
+        let body = self.parse_stmt()?;
+        self.expect(Token::While)?;
+        let cond = self.parse_paren_expr()?;
+        self.expect(Token::Semicolon)?;
+        Ok(Stmt::DoWhile(self.attr_from(&start), Box::new(body), cond))
+    }
+
+    // for (init; cond; step) a;
+    fn parse_for(&mut self) -> ParseResult<Stmt> {
+        // a declaration in the init is in scope up to the end of the body
+        self.push_scope();
+        let stmt = self.parse_for_scoped();
+        self.pop_scope();
+        stmt
+    }
+
+    fn parse_for_scoped(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::For)?;
+        self.expect(Token::LParen)?;
+        let init = if self.eat(Token::Semicolon) {
+            vec![]
+        } else if self.is_declaration_start() {
+            self.parse_declaration()?
+        } else {
+            vec![self.parse_expr_stmt()?]
+        };
+        let cond = if self.is(Token::Semicolon) {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+        self.expect(Token::Semicolon)?;
+        let step = if self.is(Token::RParen) {
+            None
+        } else {
+            Some(Box::new(self.parse_expr()?))
+        };
+        self.expect(Token::RParen)?;
+        let body = self.parse_stmt()?;
+        Ok(Stmt::For(
+            self.attr_from(&start),
+            init,
+            cond,
+            step,
+            Box::new(body),
+        ))
+    }
+
+    // switch (a) { ... }
+    fn parse_switch(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::Switch)?;
+        let expr = self.parse_paren_expr()?;
+        let body = self.parse_stmt()?;
+        Ok(Stmt::Switch(self.attr_from(&start), expr, Box::new(body)))
+    }
+
+    // case 1: a;
+    fn parse_case(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::Case)?;
+        let expr = self.parse_conditional()?;
+        self.expect(Token::Colon)?;
+        let stmt = self.parse_stmt()?;
+        Ok(Stmt::Case(
+            self.attr_from(&start),
+            Box::new(expr),
+            Box::new(stmt),
+        ))
+    }
+
+    // default: a;
+    fn parse_default(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::Default)?;
+        self.expect(Token::Colon)?;
+        let stmt = self.parse_stmt()?;
+        Ok(Stmt::Default(self.attr_from(&start), Box::new(stmt)))
+    }
+
+    // break;
+    // continue;
+    fn parse_jump(&mut self, stmt: fn(Attr) -> Stmt) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.advance();
+        self.expect(Token::Semicolon)?;
+        Ok(stmt(self.attr_from(&start)))
+    }
+
+    // goto a;
+    fn parse_goto(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        self.expect(Token::Goto)?;
+        let label = self.expect_id()?;
+        self.expect(Token::Semicolon)?;
+        Ok(Stmt::Goto(self.attr_from(&start), label))
+    }
+
+    // a: b;
+    fn parse_label(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        let label = self.expect_id()?;
+        self.expect(Token::Colon)?;
+        let stmt = self.parse_stmt()?;
+        Ok(Stmt::Label(self.attr_from(&start), label, Box::new(stmt)))
+    }
+
+    // a = b;
+    // f(a);
+    fn parse_expr_stmt(&mut self) -> ParseResult<Stmt> {
+        let start = self.attr();
+        let expr = self.parse_expr()?;
+        self.expect(Token::Semicolon)?;
+        Ok(Stmt::Expr(self.attr_from(&start), Box::new(expr)))
+    }
+
+    // (a)
+    fn parse_paren_expr(&mut self) -> ParseResult<Box<Expr>> {
+        self.expect(Token::LParen)?;
+        let expr = self.parse_expr()?;
+        self.expect(Token::RParen)?;
+        Ok(Box::new(expr))
+    }
+}
@@ -0,0 +1,498 @@
+use crate::syntax::{
+    ast::stmt::{declare_stmt::Declare, stmt::Stmt},
+    diagnostic::diagnostic::Diagnostic,
+    tokenizer::{attr::Attr, token::Token},
+    typing::{
+        tag::{Field, Tag, TagId, TagKind},
+        types::Type,
+    },
+};
+
+use super::parser::{Ordinary, ParseResult, Parser};
+
+fn is_integer(typ: &Type) -> bool {
+    matches!(
+        typ,
+        Type::Bool
+            | Type::Char
+            | Type::UChar
+            | Type::Short
+            | Type::UShort
+            | Type::Int
+            | Type::UInt
+            | Type::Long
+            | Type::ULong
+            | Type::LongLong
+            | Type::ULongLong
+            | Type::Enum(_)
+    )
+}
+
+impl Parser {
+    /// `struct S { ... }`, `union U`, `enum E { A, B = 2 }` or an
+    /// anonymous definition, as the tag it names.
+    ///
+    /// A tag without a body refers to the tag of that name in scope, or
+    /// declares it if there is none. `struct S;` on its own always
+    /// declares a new tag in the innermost scope, and so does a body.
+    pub(super) fn parse_tag_specifier(&mut self) -> ParseResult<TagId> {
+        let start = self.attr();
+        let kind = match self.advance() {
+            Token::Struct(_) => TagKind::Struct,
+            Token::Union(_) => TagKind::Union,
+            _ => TagKind::Enum,
+        };
+        let attr = self.attr();
+        let name = match self.peek() {
+            Token::ID(_, name) => {
+                let name = name.clone();
+                self.advance();
+                Some(name)
+            }
+            _ => None,
+        };
+        if !self.is(Token::LBrace) {
+            let name = match name {
+                Some(name) => name,
+                None => {
+                    return Err(self.error(format!(
+                        "expected identifier or '{{', found {}",
+                        self.found()
+                    )))
+                }
+            };
+            let forward = self.is(Token::Semicolon);
+            return match self.lookup_tag(&name, forward) {
+                Some(id) => self.check_kind(id, kind, &attr),
+                None => Ok(self.declare_tag(Tag::new(kind, Some(name)))),
+            };
+        }
+
+        let id = match name.as_ref().and_then(|name| self.lookup_tag(name, true)) {
+            Some(id) => {
+                self.check_kind(id, kind, &attr)?;
+                if self.tag(id).complete {
+                    let message = format!("redefinition of '{}'", self.tag(id).spelling());
+                    return Err(Diagnostic::error(attr, message));
+                }
+                id
+            }
+            None => self.declare_tag(Tag::new(kind, name)),
+        };
+        self.expect(Token::LBrace)?;
+        let definition = match kind {
+            TagKind::Enum => {
+                let enumerators = self.parse_enumerators()?;
+                self.tag_mut(id).enumerators = enumerators;
+                Declare::DeclareEnum
+            }
+            TagKind::Struct | TagKind::Union => {
+                let fields = self.parse_fields()?;
+                self.tag_mut(id).fields = fields;
+                match kind {
+                    TagKind::Struct => Declare::DeclareStruct,
+                    _ => Declare::DeclareUnion,
+                }
+            }
+        };
+        self.tag_mut(id).complete = true;
+        let definition = definition(self.attr_from(&start), id);
+        self.add_definition(Stmt::Declare(definition));
+        Ok(id)
+    }
+
+    fn check_kind(&self, id: TagId, kind: TagKind, attr: &Attr) -> ParseResult<TagId> {
+        let tag = self.tag(id);
+        if tag.kind != kind {
+            let message = format!(
+                "use of '{}' with tag type that does not match previous declaration",
+                tag.name.as_deref().unwrap_or_default()
+            );
+            return Err(Diagnostic::error(attr.clone(), message));
+        }
+        Ok(id)
+    }
+
+    /// The members of a struct or union after `{`, up to and including
+    /// `}`.
+    fn parse_fields(&mut self) -> ParseResult<Vec<Field>> {
+        let mut fields: Vec<Field> = Vec::new();
+        while !self.eat(Token::RBrace) {
+            if self.at_eof() {
+                self.expect(Token::RBrace)?;
+            }
+            let attr = self.attr();
+            let base = self.parse_unstored_specifiers()?;
+            // an anonymous struct or union, whose members are members of
+            // the enclosing one
+            if self.eat(Token::Semicolon) {
+                match base {
+                    Type::Struct(_) | Type::Union(_) => fields.push(Field::new(None, base, None)),
+                    _ => {
+                        let message = String::from("declaration does not declare anything");
+                        return Err(Diagnostic::error(attr, message));
+                    }
+                }
+                continue;
+            }
+            loop {
+                let attr = self.attr();
+                let declarator = self.parse_declarator(&base)?;
+                let bits = if self.eat(Token::Colon) {
+                    Some(self.parse_bit_width(&declarator.name, &declarator.typ)?)
+                } else {
+                    None
+                };
+                if declarator.name.is_none() && bits.is_none() {
+                    return Err(self.error(format!("expected identifier, found {}", self.found())));
+                }
+                let spelling = declarator.name.as_deref().unwrap_or("<anonymous>");
+                if let Type::Struct(id) | Type::Union(id) = declarator.typ {
+                    if !self.tag(id).complete {
+                        let message = format!(
+                            "field '{}' has incomplete type '{}'",
+                            spelling,
+                            self.tag(id).spelling()
+                        );
+                        return Err(Diagnostic::error(attr, message));
+                    }
+                }
+                if let Some(name) = &declarator.name {
+                    if fields.iter().any(|f| f.name.as_ref() == Some(name)) {
+                        return Err(Diagnostic::error(
+                            attr,
+                            format!("duplicate member '{}'", name),
+                        ));
+                    }
+                }
+                fields.push(Field::new(declarator.name, declarator.typ, bits));
+                if !self.eat(Token::Comma) {
+                    break;
+                }
+            }
+            self.expect(Token::Semicolon)?;
+        }
+        Ok(fields)
+    }
+
+    // a : 3
+    fn parse_bit_width(&mut self, name: &Option<String>, typ: &Type) -> ParseResult<u32> {
+        let spelling = name.as_deref().unwrap_or("<anonymous>");
+        let attr = self.attr();
+        if !is_integer(typ) {
+            let message = format!("bit-field '{}' has non-integer type", spelling);
+            return Err(Diagnostic::error(attr, message));
+        }
+        let expr = self.parse_conditional()?;
+        let width = self.constant(&expr).ok_or_else(|| {
+            let message = format!("bit-field '{}' width is not an integer constant", spelling);
+            Diagnostic::error(attr.clone(), message)
+        })?;
+        u32::try_from(width)
+            .ok()
+            .filter(|width| *width <= 64)
+            .filter(|width| *width > 0 || name.is_none())
+            .ok_or_else(|| {
+                let message = format!("bit-field '{}' has invalid width {}", spelling, width);
+                Diagnostic::error(attr, message)
+            })
+    }
+
+    /// The constants of an enum after `{`, up to and including `}`. They
+    /// are declared as they are parsed, so later values can use them.
+    fn parse_enumerators(&mut self) -> ParseResult<Vec<(String, i64)>> {
+        let mut enumerators = Vec::new();
+        let mut value: i64 = 0;
+        loop {
+            let name = self.expect_id()?;
+            if self.eat(Token::Assign) {
+                let attr = self.attr();
+                let expr = self.parse_conditional()?;
+                value = self.constant(&expr).ok_or_else(|| {
+                    let message =
+                        format!("enumerator value for '{}' is not an integer constant", name);
+                    Diagnostic::error(attr, message)
+                })?;
+            }
+            self.declare_name(&name, Ordinary::EnumConstant(value));
+            enumerators.push((name, value));
+            value = value.wrapping_add(1);
+            if !self.eat(Token::Comma) || self.is(Token::RBrace) {
+                break;
+            }
+        }
+        self.expect(Token::RBrace)?;
+        Ok(enumerators)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::attr::Attr;
+    use crate::syntax::{
+        ast::{
+            ast_module::CompilationUnit,
+            stmt::{declare_stmt::Declare, stmt::Stmt},
+        },
+        parser::parser::Parser,
+        typing::{
+            tag::{Field, TagId, TagKind},
+            types::Type,
+        },
+    };
+
+    fn parse(src: &str) -> CompilationUnit {
+        Parser::new(src).parse_unit().unwrap()
+    }
+
+    fn field(name: &str, typ: Type) -> Field {
+        Field::new(Some(String::from(name)), typ, None)
+    }
+
+    fn bit_field(name: Option<&str>, typ: Type, bits: u32) -> Field {
+        Field::new(name.map(String::from), typ, Some(bits))
+    }
+
+    fn var(name: &str, typ: Type) -> Stmt {
+        Stmt::Declare(Declare::DeclareIdentifier(
+            Attr::new(),
+            String::from(name),
+            typ,
+            None,
+        ))
+    }
+
+    fn ptr(typ: Type) -> Type {
+        Type::Pointer(Box::new(typ))
+    }
+
+    #[test]
+    pub fn test_struct() {
+        let unit = parse("struct point { int x, y; char *name; } p, *q; struct point r;");
+        let id = TagId(0);
+        assert_eq!(
+            unit.stmts,
+            vec![
+                Stmt::Declare(Declare::DeclareStruct(Attr::new(), id)),
+                var("p", Type::Struct(id)),
+                var("q", ptr(Type::Struct(id))),
+                var("r", Type::Struct(id)),
+            ]
+        );
+        let tag = unit.tags.tag(id);
+        assert_eq!(tag.kind, TagKind::Struct);
+        assert_eq!(tag.name.as_deref(), Some("point"));
+        assert!(tag.complete);
+        assert_eq!(
+            tag.fields,
+            vec![
+                field("x", Type::Int),
+                field("y", Type::Int),
+                field("name", ptr(Type::Char)),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_union_and_anonymous() {
+        let unit = parse(
+            "union { int i; float f; } u; struct s { struct { int a; }; union { char c; }; } v;",
+        );
+        assert_eq!(unit.tags.len(), 4);
+        let u = unit.tags.tag(TagId(0));
+        assert_eq!((u.kind, u.name.clone()), (TagKind::Union, None));
+        assert_eq!(
+            u.fields,
+            vec![field("i", Type::Int), field("f", Type::Float)]
+        );
+        assert_eq!(
+            unit.tags.tag(TagId(1)).fields,
+            vec![
+                Field::new(None, Type::Struct(TagId(2)), None),
+                Field::new(None, Type::Union(TagId(3)), None),
+            ]
+        );
+        assert_eq!(
+            unit.stmts[0],
+            Stmt::Declare(Declare::DeclareUnion(Attr::new(), TagId(0)))
+        );
+        assert_eq!(unit.stmts[1], var("u", Type::Union(TagId(0))));
+    }
+
+    #[test]
+    pub fn test_nested_definitions() {
+        // the inner definition completes first, and is in the file scope
+        let unit = parse("struct a { struct b { int x; } b; int y; }; struct b c;");
+        assert_eq!(
+            unit.stmts,
+            vec![
+                Stmt::Declare(Declare::DeclareStruct(Attr::new(), TagId(1))),
+                Stmt::Declare(Declare::DeclareStruct(Attr::new(), TagId(0))),
+                var("c", Type::Struct(TagId(1))),
+            ]
+        );
+        assert_eq!(
+            unit.tags.tag(TagId(0)).fields,
+            vec![field("b", Type::Struct(TagId(1))), field("y", Type::Int)]
+        );
+    }
+
+    #[test]
+    pub fn test_forward_declarations() {
+        let unit = parse("struct node; struct node { int v; struct node *next; }; struct list *l;");
+        let node = TagId(0);
+        assert_eq!(
+            unit.tags.tag(node).fields,
+            vec![
+                field("v", Type::Int),
+                field("next", ptr(Type::Struct(node)))
+            ]
+        );
+        assert_eq!(unit.tags.len(), 2);
+        assert!(!unit.tags.tag(TagId(1)).complete);
+        assert_eq!(unit.stmts[1], var("l", ptr(Type::Struct(TagId(1)))));
+    }
+
+    #[test]
+    pub fn test_tag_scopes() {
+        let unit = parse(
+            "struct s { int a; }; void f() { struct s x; struct s; struct s *p; \
+             { struct s { char c; } y; } }",
+        );
+        let body = match &unit.stmts[1] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => body,
+            stmt => panic!("unexpected {:?}", stmt),
+        };
+        assert_eq!(
+            body,
+            &vec![
+                var("x", Type::Struct(TagId(0))),
+                // `struct s;` declares a new tag hiding the outer one
+                var("p", ptr(Type::Struct(TagId(1)))),
+                Stmt::Block(
+                    Attr::new(),
+                    vec![
+                        Stmt::Declare(Declare::DeclareStruct(Attr::new(), TagId(2))),
+                        var("y", Type::Struct(TagId(2))),
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_bit_fields() {
+        let unit = parse("struct flags { unsigned a : 1, b : 3; int : 0; enum e { E } c : 2; };");
+        let tag = unit.tags.tag(TagId(0));
+        assert_eq!(
+            tag.fields,
+            vec![
+                bit_field(Some("a"), Type::UInt, 1),
+                bit_field(Some("b"), Type::UInt, 3),
+                bit_field(None, Type::Int, 0),
+                bit_field(Some("c"), Type::Enum(TagId(1)), 2),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_sizes() {
+        let unit = parse(
+            "struct S { char c; int i; short s; }; union U { char c[5]; int i; };
+            struct S a[sizeof(struct S)]; char b[sizeof(union U) + _Alignof(struct S)];",
+        );
+        let array = |typ, size| Type::Array(Box::new(typ), Some(size));
+        assert_eq!(unit.stmts[2], var("a", array(Type::Struct(TagId(0)), 12)));
+        assert_eq!(unit.stmts[3], var("b", array(Type::Char, 12)));
+        let errors = Parser::new("struct T; char c[sizeof(struct T)];")
+            .parse_unit()
+            .unwrap_err();
+        assert_eq!(errors[0].message, "array size must be an integer constant");
+    }
+
+    #[test]
+    pub fn test_enum() {
+        let unit = parse("enum color { RED, GREEN = 5, BLUE, LAST = BLUE * 2, }; int a[LAST];");
+        let tag = unit.tags.tag(TagId(0));
+        assert_eq!(tag.kind, TagKind::Enum);
+        assert_eq!(
+            tag.enumerators,
+            vec![
+                (String::from("RED"), 0),
+                (String::from("GREEN"), 5),
+                (String::from("BLUE"), 6),
+                (String::from("LAST"), 12),
+            ]
+        );
+        assert_eq!(
+            unit.stmts[1],
+            var("a", Type::Array(Box::new(Type::Int), Some(12)))
+        );
+        // enum constants shadow typedef names like variables do
+        let unit = parse("typedef int T; void f() { enum { T = 2 }; T * x; }");
+        match &unit.stmts[1] {
+            Stmt::Declare(Declare::DeclareFunction { body, .. }) => {
+                assert!(matches!(body[1], Stmt::Expr(_, _)))
+            }
+            stmt => panic!("unexpected {:?}", stmt),
+        }
+    }
+
+    #[test]
+    pub fn test_errors() {
+        let error = |src: &str| Parser::new(src).parse_unit().unwrap_err().remove(0).message;
+        assert_eq!(
+            error("struct s { int a; }; struct s { int b; };"),
+            "redefinition of 'struct s'"
+        );
+        assert_eq!(
+            error("struct s; union s *p;"),
+            "use of 's' with tag type that does not match previous declaration"
+        );
+        assert_eq!(
+            error("struct s { struct s inner; };"),
+            "field 'inner' has incomplete type 'struct s'"
+        );
+        assert_eq!(
+            error("struct s { int a; char a; };"),
+            "duplicate member 'a'"
+        );
+        assert_eq!(
+            error("struct s { float f : 1; };"),
+            "bit-field 'f' has non-integer type"
+        );
+        assert_eq!(
+            error("struct s { int a : 0; };"),
+            "bit-field 'a' has invalid width 0"
+        );
+        assert_eq!(
+            error("int n; struct s { int a : n; };"),
+            "bit-field 'a' width is not an integer constant"
+        );
+        assert_eq!(
+            error("int n; enum e { A = n };"),
+            "enumerator value for 'A' is not an integer constant"
+        );
+        assert_eq!(
+            error("struct s { int; };"),
+            "declaration does not declare anything"
+        );
+        assert_eq!(error("struct;"), "expected identifier or '{', found ';'");
+        assert_eq!(
+            error("int struct s x;"),
+            "cannot combine 'struct' with 'int'"
+        );
+        assert_eq!(
+            error("struct s { int a; } int x;"),
+            "cannot combine 'int' with 'struct s'"
+        );
+        assert_eq!(
+            error("void f(struct P { int x; } *p);"),
+            "defining a tag in a parameter list is not supported"
+        );
+        assert_eq!(
+            error("int (*f(void))(enum { A } e);"),
+            "defining a tag in a parameter list is not supported"
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use crate::syntax::{
+    diagnostic::diagnostic::Diagnostic,
+    tokenizer::{
+        attr::Attr,
+        token::{Encoding, Token},
+    },
+};
+
+use super::pp_token::PPToken;
+
+/// A value of an `#if` expression, computed in `intmax_t` or `uintmax_t`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Value {
+    value: i64,
+    unsigned: bool,
+}
+
+impl Value {
+    fn signed(value: i64) -> Value {
+        Value {
+            value,
+            unsigned: false,
+        }
+    }
+
+    fn unsigned(value: u64) -> Value {
+        Value {
+            value: value as i64,
+            unsigned: true,
+        }
+    }
+
+    fn is_true(&self) -> bool {
+        self.value != 0
+    }
+}
+
+/// Evaluates the controlling expression of `#if` or `#elif`, whose macros
+/// are already expanded and whose `defined` operators are already replaced.
+/// `attr` locates the directive, for errors at the end of the line.
+pub fn evaluate(tokens: &[PPToken], attr: &Attr) -> Result<bool, Diagnostic> {
+    let mut condition = Condition {
+        tokens,
+        pos: 0,
+        attr,
+        skip: 0,
+    };
+    if tokens.is_empty() {
+        return Err(condition.error("#if with no expression"));
+    }
+    let value = condition.conditional()?;
+    match condition.peek() {
+        Some(token) => Err(Diagnostic::error(
+            token.attr(),
+            format!("missing binary operator before token \"{}\"", token.text),
+        )),
+        None => Ok(value.is_true()),
+    }
+}
+
+struct Condition<'a> {
+    tokens: &'a [PPToken],
+    pos: usize,
+    attr: &'a Attr,
+    // > 0 inside an operand that short-circuiting leaves unevaluated, where
+    // division by zero is not an error
+    skip: u32,
+}
+
+impl<'a> Condition<'a> {
+    fn peek(&self) -> Option<&'a PPToken> {
+        self.tokens.get(self.pos)
+    }
+
+    fn error(&self, message: &str) -> Diagnostic {
+        let attr = match self.peek() {
+            Some(token) => token.attr(),
+            None => self.attr.clone(),
+        };
+        Diagnostic::error(attr, message.to_owned())
+    }
+
+    fn eat(&mut self, text: &str) -> bool {
+        match self.peek() {
+            Some(token) if token.text == text && token.ident().is_none() => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn skipped<T>(&mut self, skip: bool, f: impl FnOnce(&mut Self) -> T) -> T {
+        if skip {
+            self.skip += 1;
+        }
+        let value = f(self);
+        if skip {
+            self.skip -= 1;
+        }
+        value
+    }
+
+    fn conditional(&mut self) -> Result<Value, Diagnostic> {
+        let cond = self.logical_or()?;
+        if !self.eat("?") {
+            return Ok(cond);
+        }
+        let then = self.skipped(!cond.is_true(), |c| c.conditional())?;
+        if !self.eat(":") {
+            return Err(self.error("expected ':' in preprocessor expression"));
+        }
+        let otherwise = self.skipped(cond.is_true(), |c| c.conditional())?;
+        let value = if cond.is_true() { then } else { otherwise };
+        Ok(Value {
+            unsigned: then.unsigned || otherwise.unsigned,
+            ..value
+        })
+    }
+
+    fn logical_or(&mut self) -> Result<Value, Diagnostic> {
+        let mut lhs = self.logical_and()?;
+        while self.eat("||") {
+            let rhs = self.skipped(lhs.is_true(), |c| c.logical_and())?;
+            lhs = Value::signed((lhs.is_true() || rhs.is_true()) as i64);
+        }
+        Ok(lhs)
+    }
+
+    fn logical_and(&mut self) -> Result<Value, Diagnostic> {
+        let mut lhs = self.binary(0)?;
+        while self.eat("&&") {
+            let rhs = self.skipped(!lhs.is_true(), |c| c.binary(0))?;
+            lhs = Value::signed((lhs.is_true() && rhs.is_true()) as i64);
+        }
+        Ok(lhs)
+    }
+
+    /// The binary operators from `|` down to `*`, by precedence climbing.
+    fn binary(&mut self, level: usize) -> Result<Value, Diagnostic> {
+        const LEVELS: [&[&str]; 8] = [
+            &["|"],
+            &["^"],
+            &["&"],
+            &["==", "!="],
+            &["<", ">", "<=", ">="],
+            &["<<", ">>"],
+            &["+", "-"],
+            &["*", "/", "%"],
+        ];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut lhs = self.binary(level + 1)?;
+        loop {
+            let op = match LEVELS[level].iter().find(|op| self.eat(op)) {
+                Some(op) => *op,
+                None => return Ok(lhs),
+            };
+            let rhs = self.binary(level + 1)?;
+            lhs = self.apply(op, lhs, rhs)?;
+        }
+    }
+
+    fn apply(&self, op: &str, lhs: Value, rhs: Value) -> Result<Value, Diagnostic> {
+        let unsigned = lhs.unsigned || rhs.unsigned;
+        let (a, b) = (lhs.value, rhs.value);
+        let (ua, ub) = (a as u64, b as u64);
+        let arith = |signed: i64, unsigned_: u64| {
+            if unsigned {
+                Value::unsigned(unsigned_)
+            } else {
+                Value::signed(signed)
+            }
+        };
+        let compare = |signed: bool, unsigned_: bool| {
+            Value::signed(if unsigned { unsigned_ } else { signed } as i64)
+        };
+        let value = match op {
+            "|" => arith(a | b, ua | ub),
+            "^" => arith(a ^ b, ua ^ ub),
+            "&" => arith(a & b, ua & ub),
+            "==" => Value::signed((a == b) as i64),
+            "!=" => Value::signed((a != b) as i64),
+            "<" => compare(a < b, ua < ub),
+            ">" => compare(a > b, ua > ub),
+            "<=" => compare(a <= b, ua <= ub),
+            ">=" => compare(a >= b, ua >= ub),
+            // the type of a shift is the type of its left operand
+            "<<" | ">>" => {
+                let shift = (b as u32) & 63;
+                let left = op == "<<";
+                match (lhs.unsigned, left) {
+                    (true, true) => Value::unsigned(ua << shift),
+                    (true, false) => Value::unsigned(ua >> shift),
+                    (false, true) => Value::signed(a << shift),
+                    (false, false) => Value::signed(a >> shift),
+                }
+            }
+            "+" => arith(a.wrapping_add(b), ua.wrapping_add(ub)),
+            "-" => arith(a.wrapping_sub(b), ua.wrapping_sub(ub)),
+            "*" => arith(a.wrapping_mul(b), ua.wrapping_mul(ub)),
+            _ if b == 0 => {
+                if self.skip > 0 {
+                    return Ok(arith(0, 0));
+                }
+                return Err(Diagnostic::error(
+                    self.tokens[self.pos - 1].attr(),
+                    String::from("division by zero in #if"),
+                ));
+            }
+            "/" => arith(a.wrapping_div(b), ua / ub),
+            _ => arith(a.wrapping_rem(b), ua % ub),
+        };
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<Value, Diagnostic> {
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat("-") {
+            let v = self.unary()?;
+            return Ok(Value {
+                value: v.value.wrapping_neg(),
+                ..v
+            });
+        }
+        if self.eat("~") {
+            let v = self.unary()?;
+            return Ok(Value {
+                value: !v.value,
+                ..v
+            });
+        }
+        if self.eat("!") {
+            let v = self.unary()?;
+            return Ok(Value::signed(!v.is_true() as i64));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Value, Diagnostic> {
+        if self.eat("(") {
+            let value = self.conditional()?;
+            if !self.eat(")") {
+                return Err(self.error("missing ')' in expression"));
+            }
+            return Ok(value);
+        }
+        let token = match self.peek() {
+            Some(token) => token,
+            None => return Err(self.error("#if with no expression")),
+        };
+        let value = match &token.token {
+            Token::Int8(_, v) => Value::signed(*v as i64),
+            Token::Int16(_, v) => Value::signed(*v as i64),
+            Token::Int32(_, v) => Value::signed(*v as i64),
+            Token::Int64(_, v) => Value::signed(*v),
+            Token::UInt32(_, v) => Value::unsigned(*v as u64),
+            Token::UInt64(_, v) => Value::unsigned(*v),
+            Token::Bool(_, b) => Value::signed(*b as i64),
+            // a plain char is signed, as on x86-64
+            Token::Char(_, Encoding::Plain, c) => Value::signed(*c as u32 as u8 as i8 as i64),
+            Token::Char(_, _, c) => Value::signed(*c as i64),
+            Token::Float(..) | Token::Float64(..) | Token::LongDouble(..) => {
+                return Err(self.error("floating constant in preprocessor expression"));
+            }
+            // identifiers left after macro expansion are 0
+            _ if token.ident().is_some() => Value::signed(0),
+            _ => {
+                return Err(Diagnostic::error(
+                    token.attr(),
+                    format!(
+                        "token \"{}\" is not valid in preprocessor expressions",
+                        token.text
+                    ),
+                ));
+            }
+        };
+        self.pos += 1;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::{
+        preprocessor::pp_token::PPToken,
+        tokenizer::{attr::Attr, token::Token, tokenizer::Tokenizer},
+    };
+
+    use super::evaluate;
+
+    fn eval(input: &str) -> Result<bool, String> {
+        let tokens: Vec<PPToken> = Tokenizer::new(input)
+            .lossless()
+            .filter(|t| t.token != Token::EOF)
+            .map(|t| PPToken::from_lossless(t, false, false))
+            .collect();
+        evaluate(&tokens, &Attr::new()).map_err(|d| d.message)
+    }
+
+    #[test]
+    pub fn test_arithmetic() {
+        assert_eq!(eval("1 + 2 * 3 == 7"), Ok(true));
+        assert_eq!(eval("(1 + 2) * 3 == 7"), Ok(false));
+        assert_eq!(eval("10 / 3 == 3 && 10 % 3 == 1"), Ok(true));
+        assert_eq!(eval("1 << 4 | 1"), Ok(true));
+        assert_eq!(eval("-1 < 0"), Ok(true));
+        assert_eq!(eval("~0 == -1"), Ok(true));
+        assert_eq!(eval("!0 && !!2"), Ok(true));
+        assert_eq!(eval("0x10 == 16 && 'a' == 97"), Ok(true));
+        assert_eq!(eval("0 ? 1 : 2 == 2"), Ok(true));
+        assert_eq!(eval("FOO"), Ok(false));
+    }
+
+    #[test]
+    pub fn test_unsigned() {
+        assert_eq!(eval("-1 < 0u"), Ok(false));
+        assert_eq!(eval("-1 > 0"), Ok(false));
+        assert_eq!(eval("0xffffffffffffffff == -1"), Ok(true));
+        assert_eq!(eval("(0 ? 1u : -1) > 0"), Ok(true));
+    }
+
+    #[test]
+    pub fn test_short_circuit() {
+        assert_eq!(eval("0 && 1 / 0"), Ok(false));
+        assert_eq!(eval("1 || 1 / 0"), Ok(true));
+        assert_eq!(eval("1 ? 2 : 1 / 0"), Ok(true));
+        assert_eq!(eval("1 / 0"), Err(String::from("division by zero in #if")));
+    }
+
+    #[test]
+    pub fn test_errors() {
+        assert_eq!(eval(""), Err(String::from("#if with no expression")));
+        assert_eq!(eval("(1"), Err(String::from("missing ')' in expression")));
+        assert_eq!(
+            eval("1 2"),
+            Err(String::from("missing binary operator before token \"2\""))
+        );
+        assert_eq!(
+            eval("1.0"),
+            Err(String::from("floating constant in preprocessor expression"))
+        );
+        assert_eq!(eval("1 +"), Err(String::from("#if with no expression")));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::syntax::tokenizer::attr::Attr;
+
+use super::pp_token::PPToken;
+
+/// A macro whose replacement depends on where it is expanded.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Builtin {
+    File,
+    Line,
+}
+
+/// A `#define`d macro. Function-like macros have parameters, with a
+/// trailing `...` stored as the parameter `__VA_ARGS__`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Macro {
+    pub name: String,
+    pub params: Option<Vec<String>>,
+    pub variadic: bool,
+    pub body: Vec<PPToken>,
+    pub attr: Attr,
+    pub builtin: Option<Builtin>,
+}
+
+impl Macro {
+    pub fn object(name: &str, body: Vec<PPToken>, attr: Attr) -> Macro {
+        Macro {
+            name: name.to_owned(),
+            params: None,
+            variadic: false,
+            body,
+            attr,
+            builtin: None,
+        }
+    }
+
+    pub fn builtin(name: &str, builtin: Builtin) -> Macro {
+        Macro {
+            builtin: Some(builtin),
+            ..Macro::object(name, Vec::new(), Attr::new())
+        }
+    }
+
+    pub fn function(
+        name: &str,
+        params: Vec<String>,
+        variadic: bool,
+        body: Vec<PPToken>,
+        attr: Attr,
+    ) -> Macro {
+        Macro {
+            params: Some(params),
+            variadic,
+            ..Macro::object(name, body, attr)
+        }
+    }
+
+    pub fn is_function_like(&self) -> bool {
+        self.params.is_some()
+    }
+
+    /// The index of the parameter named `name`.
+    pub fn param(&self, name: &str) -> Option<usize> {
+        self.params.as_ref()?.iter().position(|p| p == name)
+    }
+
+    /// Whether `other` redefines this macro identically, which C allows
+    /// silently: same parameters and the same body tokens and spacing.
+    pub fn same_definition(&self, other: &Macro) -> bool {
+        self.params == other.params
+            && self.variadic == other.variadic
+            && self.body.len() == other.body.len()
+            && self
+                .body
+                .iter()
+                .zip(&other.body)
+                .enumerate()
+                .all(|(i, (a, b))| a.text == b.text && (i == 0 || a.space == b.space))
+    }
+}
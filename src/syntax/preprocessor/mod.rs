@@ -0,0 +1,6 @@
+pub mod condition;
+pub mod macro_def;
+pub mod output;
+pub mod pp_token;
+pub mod predefined;
+pub mod preprocessor;
@@ -0,0 +1,145 @@
+use crate::syntax::{
+    source::source_map::SourceMap,
+    tokenizer::{attr::Attr, tokenizer::Tokenizer},
+};
+
+use super::pp_token::PPToken;
+
+// a gap of more blank lines than this is bridged by a line marker
+const MAX_BLANK_LINES: u32 = 8;
+
+/// Prints preprocessed tokens as text, each `(token, location)` on the
+/// line of its location. A `# row "file"` line marker precedes the text of
+/// every file and every jump in line numbers, so that a compiler reading
+/// the text reports the original positions.
+pub fn print(tokens: &[(PPToken, Attr)], source_map: &SourceMap) -> String {
+    let mut text = String::new();
+    let mut file: Option<&str> = None;
+    let mut row = 0;
+    let mut last: Option<&PPToken> = None;
+    for (token, location) in tokens {
+        let (name, pos) = source_map.presumed(location);
+        if file != Some(name) || pos.row < row || pos.row > row + MAX_BLANK_LINES {
+            if last.is_some() {
+                text.push('\n');
+            }
+            text.push_str(&line_marker(pos.row, name));
+            text.push_str(&" ".repeat(pos.col as usize - 1));
+            (file, row) = (Some(name), pos.row);
+        } else if pos.row > row {
+            for _ in row..pos.row {
+                text.push('\n');
+            }
+            text.push_str(&" ".repeat(pos.col as usize - 1));
+            row = pos.row;
+        } else if let Some(last) = last {
+            if token.space || would_paste(last, token) {
+                text.push(' ');
+            }
+        }
+        text.push_str(&token.text);
+        last = Some(token);
+    }
+    if last.is_some() {
+        text.push('\n');
+    }
+    text
+}
+
+/// A line marker numbering the next line `row` of `file`.
+pub fn line_marker(row: u32, file: &str) -> String {
+    format!("# {} \"{}\"\n", row, escape(file))
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Whether printing `next` right after `last` would lex as other tokens,
+/// as `+` and `+` do, or `x` and `1` from different macros.
+fn would_paste(last: &PPToken, next: &PPToken) -> bool {
+    let text = format!("{}{}", last.text, next.text);
+    let mut tokenizer = Tokenizer::new(&text);
+    match tokenizer.next().and_then(|t| t.attr().cloned()) {
+        Some(attr) => attr.span().hi != last.text.len(),
+        None => false,
+    }
+}
+
+/// A Makefile rule making `target` depend on `prerequisites`, as
+/// `cc -MD` writes it, with a continuation line per prerequisite.
+pub fn make_rule(target: &str, prerequisites: &[&str]) -> String {
+    let mut rule = format!("{}:", escape_make(target));
+    for prerequisite in prerequisites {
+        rule.push_str(" \\\n  ");
+        rule.push_str(&escape_make(prerequisite));
+    }
+    rule.push('\n');
+    rule
+}
+
+/// Escapes the chars make treats specially in file names.
+fn escape_make(name: &str) -> String {
+    let mut escaped = String::new();
+    for c in name.chars() {
+        match c {
+            ' ' | '#' => escaped.push('\\'),
+            '$' => escaped.push('$'),
+            _ => {}
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::preprocessor::preprocessor::Preprocessor;
+
+    use super::make_rule;
+
+    fn preprocess(src: &str) -> String {
+        let mut pp = Preprocessor::new();
+        let file = pp.source_map_mut().add_file("a.c", src.to_owned());
+        pp.preprocess_text(file)
+    }
+
+    #[test]
+    pub fn test_print() {
+        assert_eq!(
+            preprocess("#define N 10\nint a[N];\n  int b;"),
+            "# 2 \"a.c\"\nint a[10];\n  int b;\n"
+        );
+        assert_eq!(preprocess("a\n\n\nb"), "# 1 \"a.c\"\na\n\n\nb\n");
+        assert_eq!(
+            preprocess("a\n\n\n\n\n\n\n\n\n\n\nb"),
+            "# 1 \"a.c\"\na\n# 12 \"a.c\"\nb\n"
+        );
+        assert_eq!(
+            preprocess("a\n#line 40 \"b.c\"\nb"),
+            "# 1 \"a.c\"\na\n# 40 \"b.c\"\nb\n"
+        );
+        assert_eq!(preprocess(""), "");
+    }
+
+    #[test]
+    pub fn test_print_expansions() {
+        // an invocation spanning lines is printed on the line it starts on
+        assert_eq!(
+            preprocess("#define F(x, y) x y\nF(1,\n  2) z"),
+            "# 2 \"a.c\"\n1 2\n     z\n"
+        );
+        assert_eq!(
+            preprocess("#define P +\n#define I(x) x\n+P -I(-) I(x)I(1)"),
+            "# 3 \"a.c\"\n+ + - - x 1\n"
+        );
+    }
+
+    #[test]
+    pub fn test_make_rule() {
+        assert_eq!(
+            make_rule("a.o", &["a.c", "my dir/b.h", "$c.h"]),
+            "a.o: \\\n  a.c \\\n  my\\ dir/b.h \\\n  $$c.h\n"
+        );
+    }
+}
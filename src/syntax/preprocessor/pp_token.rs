@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use crate::syntax::{
+    source::expansion::ExpansionId,
+    tokenizer::{
+        attr::Attr,
+        lossless::LosslessToken,
+        token::{keyword, Encoding, Token},
+    },
+};
+
+/// A token as the preprocessor sees it: its spelling, whether whitespace
+/// precedes it and whether it starts a line. The hide set holds the macros
+/// whose expansion produced the token, which must not expand it again.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PPToken {
+    pub token: Token,
+    pub text: String,
+    pub space: bool,
+    pub bol: bool,
+    pub hideset: HashSet<String>,
+}
+
+impl PPToken {
+    pub fn new(token: Token, text: String) -> PPToken {
+        PPToken {
+            token,
+            text,
+            space: false,
+            bol: false,
+            hideset: HashSet::new(),
+        }
+    }
+
+    /// Converts a lossless token, given whether the token before it had
+    /// trailing trivia and ended a line.
+    pub fn from_lossless(token: LosslessToken, space: bool, bol: bool) -> PPToken {
+        PPToken {
+            space: space || !token.leading.is_empty(),
+            bol: bol || token.has_leading_newline(),
+            ..PPToken::new(token.token, token.text)
+        }
+    }
+
+    pub fn attr(&self) -> Attr {
+        self.token.attr().cloned().unwrap_or_default()
+    }
+
+    pub fn set_expansion(&mut self, expansion: ExpansionId) {
+        if let Some(attr) = self.token.attr_mut() {
+            attr.set_expansion(Some(expansion));
+        }
+    }
+
+    /// The name of an identifier. Keywords count as identifiers until
+    /// preprocessing is over, so they can be macro names too.
+    pub fn ident(&self) -> Option<&str> {
+        match &self.token {
+            Token::ID(_, name) => Some(name),
+            Token::Bool(..) => Some(&self.text),
+            _ if keyword(&self.text).is_some() => Some(&self.text),
+            _ => None,
+        }
+    }
+
+    pub fn is_ident(&self, name: &str) -> bool {
+        self.ident() == Some(name)
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.token == Token::EOF
+    }
+
+    pub fn is_hash(&self) -> bool {
+        matches!(self.token, Token::Hash(_))
+    }
+
+    pub fn is_hashhash(&self) -> bool {
+        matches!(self.token, Token::HashHash(_))
+    }
+
+    pub fn is_lparen(&self) -> bool {
+        matches!(self.token, Token::LParen(_))
+    }
+
+    pub fn is_rparen(&self) -> bool {
+        matches!(self.token, Token::RParen(_))
+    }
+
+    pub fn is_comma(&self) -> bool {
+        matches!(self.token, Token::Comma(_))
+    }
+}
+
+/// Spells `tokens` the way they appear in the source, with a single space
+/// wherever whitespace separated two of them.
+pub fn spell(tokens: &[PPToken]) -> String {
+    let mut text = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 && token.space {
+            text.push(' ');
+        }
+        text.push_str(&token.text);
+    }
+    text
+}
+
+/// The `#` operator: a string literal spelling `tokens`.
+pub fn stringize(tokens: &[PPToken], attr: Attr) -> PPToken {
+    string(spell(tokens), attr)
+}
+
+/// A plain string literal with the value `value`.
+pub fn string(value: String, attr: Attr) -> PPToken {
+    let mut text = String::from("\"");
+    for c in value.chars() {
+        if c == '"' || c == '\\' {
+            text.push('\\');
+        }
+        text.push(c);
+    }
+    text.push('"');
+    PPToken::new(Token::String(attr, Encoding::Plain, value), text)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::{
+        attr::Attr,
+        token::{Encoding, Token},
+        tokenizer::Tokenizer,
+    };
+
+    use super::{stringize, PPToken};
+
+    fn lex(input: &str) -> Vec<PPToken> {
+        let mut space = false;
+        let mut tokens = Vec::new();
+        for token in Tokenizer::new(input).lossless() {
+            let next_space = !token.trailing.is_empty();
+            tokens.push(PPToken::from_lossless(token, space, false));
+            space = next_space;
+        }
+        tokens.pop();
+        tokens
+    }
+
+    #[test]
+    pub fn test_ident() {
+        let tokens = lex("a int true 1 +");
+        let idents: Vec<_> = tokens.iter().map(|t| t.ident()).collect();
+        assert_eq!(idents, [Some("a"), Some("int"), Some("true"), None, None]);
+    }
+
+    #[test]
+    pub fn test_stringize() {
+        let tokens = lex("a  +/**/b \"x\\n\" '\"'");
+        let string = stringize(&tokens, Attr::new());
+        assert_eq!(string.text, r#""a + b \"x\\n\" '\"'""#);
+        assert_eq!(
+            string.token,
+            Token::String(
+                Attr::new(),
+                Encoding::Plain,
+                String::from(r#"a + b "x\n" '"'"#)
+            )
+        );
+    }
+}
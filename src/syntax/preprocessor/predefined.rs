@@ -0,0 +1,88 @@
+use std::{
+    env,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The macros every translation unit starts with, as `#define` lines.
+/// `__FILE__` and `__LINE__` are built into the preprocessor instead.
+pub fn predefined_source() -> String {
+    let (date, time) = date_time(build_time());
+    let mut src = String::new();
+    for (name, value) in [
+        ("__STDC__", "1"),
+        ("__STDC_VERSION__", "201112L"),
+        ("__STDC_HOSTED__", "1"),
+        ("__x86_64__", "1"),
+        ("__x86_64", "1"),
+        ("__LP64__", "1"),
+        ("__liyuu__", "1"),
+        ("__DATE__", &format!("\"{}\"", date)),
+        ("__TIME__", &format!("\"{}\"", time)),
+    ] {
+        src.push_str(&format!("#define {} {}\n", name, value));
+    }
+    src
+}
+
+/// Seconds since the epoch, taken from `SOURCE_DATE_EPOCH` when it is set
+/// so that builds are reproducible.
+fn build_time() -> u64 {
+    env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs())
+        })
+}
+
+/// Formats `secs` since the epoch, in UTC, the way `__DATE__` and
+/// `__TIME__` spell it: `Mmm dd yyyy` and `hh:mm:ss`.
+pub fn date_time(secs: u64) -> (String, String) {
+    let days = (secs / 86400) as i64;
+    let secs = secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    let date = format!("{} {:>2} {}", MONTHS[month as usize - 1], day, year);
+    let time = format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60);
+    (date, time)
+}
+
+/// The proleptic Gregorian date of a day number counted from 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::date_time;
+
+    #[test]
+    pub fn test_date_time() {
+        assert_eq!(
+            date_time(0),
+            (String::from("Jan  1 1970"), String::from("00:00:00"))
+        );
+        assert_eq!(
+            date_time(951_782_400 + 3_723),
+            (String::from("Feb 29 2000"), String::from("01:02:03"))
+        );
+        assert_eq!(
+            date_time(1_792_281_599),
+            (String::from("Oct 17 2026"), String::from("23:59:59"))
+        );
+    }
+}
@@ -0,0 +1,1399 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::syntax::{
+    ast::stmt::directive_stmt::Directive,
+    diagnostic::diagnostic::Diagnostic,
+    source::{expansion::Expansion, source_file::FileId, source_map::SourceMap},
+    tokenizer::{attr::Attr, error::LexError, token::Token, tokenizer::Tokenizer},
+};
+
+use super::{
+    condition,
+    macro_def::{Builtin, Macro},
+    output,
+    pp_token::{spell, string, stringize, PPToken},
+    predefined::predefined_source,
+};
+
+// as deep as GCC allows
+const MAX_INCLUDE_DEPTH: usize = 200;
+
+/// Runs translation phase 4 between the `Tokenizer` and the `Parser`:
+/// executes directives, includes headers and expands macros.
+pub struct Preprocessor {
+    source_map: SourceMap,
+    macros: HashMap<String, Macro>,
+    // searched for `#include "..."` after the directory of the includer
+    quote_dirs: Vec<PathBuf>,
+    // searched for both forms of `#include`, before the system dirs
+    include_dirs: Vec<PathBuf>,
+    system_dirs: Vec<PathBuf>,
+    frames: Vec<Frame>,
+    // files marked `#pragma once`
+    once: HashSet<PathBuf>,
+    directives: Vec<Directive>,
+    diagnostics: Vec<Diagnostic>,
+    // the last token read from a file rather than from an expansion, which
+    // `__LINE__` in a macro body reports
+    invocation: Attr,
+}
+
+/// The tokens still to read from one file, or from a macro argument or
+/// directive line being expanded on its own.
+struct Frame {
+    file: FileId,
+    // reversed, so the next token is the last one
+    tokens: Vec<PPToken>,
+    // the open conditionals of the file
+    conds: Vec<Cond>,
+    // lexer errors not reported yet, reversed; they are dropped if they
+    // fall in a skipped group
+    errors: Vec<LexError>,
+}
+
+impl Frame {
+    fn new(file: FileId, mut tokens: Vec<PPToken>) -> Frame {
+        tokens.reverse();
+        Frame {
+            file,
+            tokens,
+            conds: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum CondCtx {
+    Then,
+    Elif,
+    Else,
+}
+
+/// An open `#if`, `#ifdef` or `#ifndef`.
+struct Cond {
+    ctx: CondCtx,
+    // whether one of the groups so far was taken
+    included: bool,
+    attr: Attr,
+}
+
+impl Preprocessor {
+    pub fn new() -> Preprocessor {
+        Preprocessor::with_source_map(SourceMap::new())
+    }
+
+    /// A preprocessor with the predefined macros of the target.
+    pub fn with_source_map(source_map: SourceMap) -> Preprocessor {
+        let mut pp = Preprocessor::empty(source_map);
+        for (name, builtin) in [("__FILE__", Builtin::File), ("__LINE__", Builtin::Line)] {
+            pp.macros
+                .insert(name.to_owned(), Macro::builtin(name, builtin));
+        }
+        pp.preprocess_str("<built-in>", &predefined_source());
+        pp
+    }
+
+    /// A preprocessor without any predefined macros.
+    pub fn empty(source_map: SourceMap) -> Preprocessor {
+        Preprocessor {
+            source_map,
+            macros: HashMap::new(),
+            quote_dirs: Vec::new(),
+            include_dirs: Vec::new(),
+            system_dirs: Vec::new(),
+            frames: Vec::new(),
+            once: HashSet::new(),
+            directives: Vec::new(),
+            diagnostics: Vec::new(),
+            invocation: Attr::new(),
+        }
+    }
+
+    /// Defines a macro the way `-D` does: `name` defines it as `1`,
+    /// `name=value` as `value`, and `name(params)=value` defines a
+    /// function-like macro.
+    pub fn define(&mut self, definition: &str) {
+        let line = match definition.split_once('=') {
+            Some((name, value)) => format!("#define {} {}\n", name, value),
+            None => format!("#define {} 1\n", definition),
+        };
+        self.preprocess_str("<command line>", &line);
+    }
+
+    /// Undefines a macro the way `-U` does.
+    pub fn undefine(&mut self, name: &str) {
+        self.preprocess_str("<command line>", &format!("#undef {}\n", name));
+    }
+
+    pub fn add_quote_dir<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.quote_dirs.push(dir.into());
+    }
+
+    pub fn add_include_dir<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.include_dirs.push(dir.into());
+    }
+
+    pub fn add_system_dir<P: Into<PathBuf>>(&mut self, dir: P) {
+        self.system_dirs.push(dir.into());
+    }
+
+    pub fn source_map(&self) -> &SourceMap {
+        &self.source_map
+    }
+
+    pub fn source_map_mut(&mut self) -> &mut SourceMap {
+        &mut self.source_map
+    }
+
+    pub fn into_source_map(self) -> SourceMap {
+        self.source_map
+    }
+
+    pub fn macro_def(&self, name: &str) -> Option<&Macro> {
+        self.macros.get(name)
+    }
+
+    pub fn is_defined(&self, name: &str) -> bool {
+        self.macros.contains_key(name)
+    }
+
+    /// The `#include`s and `#pragma once`s executed so far, in order.
+    pub fn directives(&self) -> &[Directive] {
+        &self.directives
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        &self.diagnostics
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(|d| d.is_error())
+    }
+
+    pub fn preprocess_file(&mut self, path: &Path) -> io::Result<Vec<Token>> {
+        let file = self.source_map.load_file(path)?;
+        Ok(self.preprocess(file))
+    }
+
+    pub fn preprocess_str(&mut self, name: &str, src: &str) -> Vec<Token> {
+        let file = self.source_map.add_file(name, src.to_owned());
+        self.preprocess(file)
+    }
+
+    /// Preprocesses the source map file `file` into the tokens the parser
+    /// reads, ending with `Token::EOF`.
+    pub fn preprocess(&mut self, file: FileId) -> Vec<Token> {
+        let mut tokens: Vec<Token> = self.run(file).into_iter().map(|(t, _)| t.token).collect();
+        tokens.push(Token::EOF);
+        tokens
+    }
+
+    /// Preprocesses `file` into text, as `cc -E` does.
+    pub fn preprocess_text(&mut self, file: FileId) -> String {
+        let tokens = self.run(file);
+        output::print(&tokens, &self.source_map)
+    }
+
+    /// The paths of the headers included so far, each once, in the order
+    /// they were first included.
+    pub fn includes(&self) -> Vec<&str> {
+        let mut includes: Vec<&str> = Vec::new();
+        for directive in &self.directives {
+            if let Directive::Include(path) = directive {
+                if !includes.contains(&path.as_str()) {
+                    includes.push(path);
+                }
+            }
+        }
+        includes
+    }
+
+    /// Preprocesses `file`, pairing every resulting token with the place
+    /// in the file that produced it, which for a token from a macro
+    /// expansion is the macro invocation.
+    fn run(&mut self, file: FileId) -> Vec<(PPToken, Attr)> {
+        self.push_file(file);
+        let mut output = Vec::new();
+        while let Some(token) = self.next_token() {
+            if token.hideset.is_empty() {
+                self.invocation = token.attr();
+            }
+            if token.bol && token.is_hash() {
+                self.directive(token);
+            } else if !self.expand(&token) {
+                output.push((token, self.invocation.clone()));
+            }
+        }
+        output
+    }
+
+    fn error(&mut self, attr: Attr, message: String) {
+        self.diagnostics.push(Diagnostic::error(attr, message));
+    }
+
+    fn warning(&mut self, attr: Attr, message: String) {
+        self.diagnostics.push(Diagnostic::warning(attr, message));
+    }
+
+    /// Lexes `file` and makes it the file read next.
+    fn push_file(&mut self, file: FileId) {
+        let src = self.source_map.file(file).src();
+        let mut lossless = Tokenizer::with_file(src, file).lossless();
+        let mut tokens = Vec::new();
+        let (mut space, mut bol) = (false, true);
+        for token in lossless.by_ref() {
+            if token.token == Token::EOF {
+                break;
+            }
+            let (next_space, next_bol) = (!token.trailing.is_empty(), token.ends_line());
+            tokens.push(PPToken::from_lossless(token, space, bol));
+            (space, bol) = (next_space, next_bol);
+        }
+        let mut frame = Frame::new(file, tokens);
+        frame.errors = lossless
+            .tokenizer()
+            .errors()
+            .iter()
+            .rev()
+            .cloned()
+            .collect();
+        self.frames.push(frame);
+    }
+
+    /// The next token of the current file, moving on to the includer at
+    /// the end of an included file.
+    fn next_token(&mut self) -> Option<PPToken> {
+        loop {
+            let frame = self.frames.last_mut()?;
+            if let Some(token) = frame.tokens.pop() {
+                if let Some(attr) = token.token.attr().filter(|a| a.file() == frame.file) {
+                    let upto = attr.span().hi;
+                    self.report_errors(upto);
+                }
+                return Some(token);
+            }
+            self.report_errors(usize::MAX);
+            let frame = self.frames.pop().unwrap();
+            for cond in frame.conds {
+                self.error(
+                    cond.attr,
+                    String::from("unterminated conditional directive"),
+                );
+            }
+        }
+    }
+
+    /// Reports the lexer errors of the current file before byte `upto`.
+    fn report_errors(&mut self, upto: usize) {
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+        while frame.errors.last().is_some_and(|e| e.attr.span().lo < upto) {
+            let error = frame.errors.pop().unwrap();
+            self.diagnostics.push(Diagnostic::from(error));
+        }
+    }
+
+    fn peek_token(&self) -> Option<&PPToken> {
+        self.frames.last()?.tokens.last()
+    }
+
+    /// Pushes `tokens` back to be read next.
+    fn unget(&mut self, tokens: Vec<PPToken>) {
+        if let Some(frame) = self.frames.last_mut() {
+            frame.tokens.extend(tokens.into_iter().rev());
+        }
+    }
+
+    /// The rest of the current line.
+    fn read_line(&mut self) -> Vec<PPToken> {
+        let mut line = Vec::new();
+        while self.peek_token().is_some_and(|t| !t.bol) {
+            line.push(self.frames.last_mut().unwrap().tokens.pop().unwrap());
+        }
+        line
+    }
+
+    fn current_file(&self) -> FileId {
+        self.frames.last().map(|f| f.file).unwrap_or_default()
+    }
+
+    fn directive(&mut self, hash: PPToken) {
+        let mut line = self.read_line();
+        if line.is_empty() {
+            // the null directive
+            return;
+        }
+        let name = line.remove(0);
+        if let Token::Int32(..) = name.token {
+            // a GCC line marker, `# 12 "file"`
+            line.insert(0, name);
+            return self.line(&hash, line);
+        }
+        match name.ident().unwrap_or(&name.text) {
+            "include" => self.include(&hash, line),
+            "define" => self.define_macro(&hash, line),
+            "undef" => self.undef(&hash, line),
+            "if" => {
+                let taken = self.eval_if(&hash, line);
+                self.push_cond(hash, taken);
+            }
+            "ifdef" | "ifndef" => {
+                let defined = match line.first().and_then(|t| t.ident()) {
+                    Some(name) => self.is_defined(name),
+                    None => {
+                        let message = format!("no macro name given in #{} directive", name.text);
+                        self.error(hash.attr(), message);
+                        false
+                    }
+                };
+                self.push_cond(hash, defined == name.is_ident("ifdef"));
+            }
+            "elif" => self.elif(hash, line),
+            "else" => self.else_(hash, line),
+            "endif" => self.endif(hash, line),
+            "line" => self.line(&hash, line),
+            "pragma" => self.pragma(line),
+            "error" => self.error(hash.attr(), format!("#error {}", spell(&line))),
+            "warning" => self.warning(hash.attr(), format!("#warning {}", spell(&line))),
+            text => {
+                let message = format!("invalid preprocessing directive #{}", text);
+                self.error(name.attr(), message)
+            }
+        }
+    }
+
+    fn extra_tokens(&mut self, line: &[PPToken], directive: &str) {
+        if let Some(token) = line.first() {
+            let message = format!("extra tokens at end of #{} directive", directive);
+            self.warning(token.attr(), message);
+        }
+    }
+
+    fn include(&mut self, hash: &PPToken, line: Vec<PPToken>) {
+        let (name, quoted) = match self.header_name(line) {
+            Some(header) => header,
+            None => {
+                let message = String::from("#include expects \"FILENAME\" or <FILENAME>");
+                return self.error(hash.attr(), message);
+            }
+        };
+        let path = match self.find_include(&name, quoted) {
+            Some(path) => path,
+            None => return self.error(hash.attr(), format!("'{}' file not found", name)),
+        };
+        if self.once.contains(&canonical(&path)) {
+            return;
+        }
+        if self.frames.len() >= MAX_INCLUDE_DEPTH {
+            return self.error(hash.attr(), String::from("#include nested too deeply"));
+        }
+        let path_name = path.to_string_lossy();
+        let file = match self.source_map.find_file(&path_name) {
+            Some(file) => file,
+            None => match self.source_map.load_file(&path) {
+                Ok(file) => file,
+                Err(e) => return self.error(hash.attr(), format!("{}: {}", path_name, e)),
+            },
+        };
+        self.directives
+            .push(Directive::Include(path_name.into_owned()));
+        self.push_file(file);
+    }
+
+    /// The header of `#include "name"` or `#include <name>`, expanding
+    /// macros first if the line has neither form. The flag tells whether
+    /// the name was quoted.
+    fn header_name(&mut self, line: Vec<PPToken>) -> Option<(String, bool)> {
+        let first = line.first()?;
+        if let Token::String(..) = first.token {
+            if first.text.starts_with('"') {
+                let name = first.text[1..first.text.len() - 1].to_owned();
+                self.extra_tokens(&line[1..], "include");
+                return Some((name, true));
+            }
+        }
+        if let Token::Lt(_) = first.token {
+            let end = line.iter().position(|t| matches!(t.token, Token::Gt(_)))?;
+            let mut name = spell(&line[1..end]);
+            if line[1].space {
+                name.insert(0, ' ');
+            }
+            self.extra_tokens(&line[end + 1..], "include");
+            return Some((name, false));
+        }
+        let expanded = self.expand_all(line);
+        match expanded.first() {
+            Some(t) if matches!(t.token, Token::String(..) | Token::Lt(_)) => {
+                self.header_name(expanded)
+            }
+            _ => None,
+        }
+    }
+
+    fn find_include(&self, name: &str, quoted: bool) -> Option<PathBuf> {
+        let path = Path::new(name);
+        if path.is_absolute() {
+            return path.is_file().then(|| path.to_path_buf());
+        }
+        let mut dirs = Vec::new();
+        if quoted {
+            let includer = self.source_map.file(self.current_file()).name();
+            dirs.push(Path::new(includer).parent().unwrap_or(Path::new("")));
+            dirs.extend(self.quote_dirs.iter().map(PathBuf::as_path));
+        }
+        dirs.extend(self.include_dirs.iter().map(PathBuf::as_path));
+        dirs.extend(self.system_dirs.iter().map(PathBuf::as_path));
+        dirs.into_iter()
+            .map(|dir| dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    fn define_macro(&mut self, hash: &PPToken, line: Vec<PPToken>) {
+        let mut tokens = line.into_iter().peekable();
+        let name = match self.macro_name(hash, tokens.next(), "define") {
+            Some(name) => name,
+            None => return,
+        };
+        let attr = name.attr();
+        let name = name.ident().unwrap();
+
+        let mut params = None;
+        let mut variadic = false;
+        if tokens.peek().is_some_and(|t| t.is_lparen() && !t.space) {
+            tokens.next();
+            let mut names: Vec<String> = Vec::new();
+            loop {
+                let token = match tokens.next() {
+                    Some(token) => token,
+                    None => {
+                        let message = String::from("missing ')' in macro parameter list");
+                        return self.error(attr, message);
+                    }
+                };
+                if token.is_rparen() && names.is_empty() {
+                    break;
+                }
+                if let Token::Ellipsis(_) = token.token {
+                    variadic = true;
+                    names.push(String::from("__VA_ARGS__"));
+                } else if let Some(param) = token.ident() {
+                    if names.iter().any(|n| n == param) {
+                        let message = format!("duplicate macro parameter \"{}\"", param);
+                        return self.error(token.attr(), message);
+                    }
+                    names.push(param.to_owned());
+                } else {
+                    let message = format!("expected parameter name, found \"{}\"", token.text);
+                    return self.error(token.attr(), message);
+                }
+                match tokens.next() {
+                    Some(t) if t.is_rparen() => break,
+                    Some(t) if t.is_comma() && !variadic => {}
+                    _ => {
+                        let message = String::from("expected ',' or ')' in macro parameter list");
+                        return self.error(attr, message);
+                    }
+                }
+            }
+            params = Some(names);
+        }
+
+        let mut body: Vec<PPToken> = tokens.collect();
+        if let Some(first) = body.first_mut() {
+            first.space = false;
+        }
+        if body.first().is_some_and(|t| t.is_hashhash())
+            || body.len() > 1 && body.last().is_some_and(|t| t.is_hashhash())
+        {
+            let message = String::from("'##' cannot appear at either end of a macro expansion");
+            return self.error(attr, message);
+        }
+        let def = match params {
+            Some(params) => {
+                let stray = body.iter().enumerate().find(|(i, t)| {
+                    t.is_hash()
+                        && body
+                            .get(i + 1)
+                            .and_then(|p| p.ident())
+                            .is_none_or(|p| !params.iter().any(|n| n == p))
+                });
+                if let Some((_, hash)) = stray {
+                    let message = String::from("'#' is not followed by a macro parameter");
+                    return self.error(hash.attr(), message);
+                }
+                Macro::function(name, params, variadic, body, attr)
+            }
+            None => Macro::object(name, body, attr),
+        };
+        if let Some(old) = self.macros.get(name) {
+            if !old.same_definition(&def) {
+                let message = format!("\"{}\" redefined", name);
+                self.warning(def.attr.clone(), message);
+            }
+        }
+        self.macros.insert(name.to_owned(), def);
+    }
+
+    /// Checks the name after `#define` or `#undef`.
+    fn macro_name(
+        &mut self,
+        hash: &PPToken,
+        token: Option<PPToken>,
+        directive: &str,
+    ) -> Option<PPToken> {
+        match token {
+            Some(token) if token.is_ident("defined") => {
+                let message = String::from("\"defined\" cannot be used as a macro name");
+                self.error(token.attr(), message);
+                None
+            }
+            Some(token) if token.ident().is_some() => Some(token),
+            Some(token) => {
+                self.error(
+                    token.attr(),
+                    String::from("macro names must be identifiers"),
+                );
+                None
+            }
+            None => {
+                let message = format!("no macro name given in #{} directive", directive);
+                self.error(hash.attr(), message);
+                None
+            }
+        }
+    }
+
+    fn undef(&mut self, hash: &PPToken, mut line: Vec<PPToken>) {
+        let token = (!line.is_empty()).then(|| line.remove(0));
+        if let Some(name) = self.macro_name(hash, token, "undef") {
+            self.macros.remove(name.ident().unwrap());
+            self.extra_tokens(&line, "undef");
+        }
+    }
+
+    /// Evaluates the condition of `#if` or `#elif`.
+    fn eval_if(&mut self, hash: &PPToken, line: Vec<PPToken>) -> bool {
+        let line = self.replace_defined(line);
+        let line = self.expand_all(line);
+        match condition::evaluate(&line, &hash.attr()) {
+            Ok(value) => value,
+            Err(diagnostic) => {
+                self.diagnostics.push(diagnostic);
+                false
+            }
+        }
+    }
+
+    /// Replaces `defined X` and `defined ( X )` by `1` or `0`.
+    fn replace_defined(&mut self, line: Vec<PPToken>) -> Vec<PPToken> {
+        let mut result = Vec::new();
+        let mut tokens = line.into_iter();
+        while let Some(token) = tokens.next() {
+            if !token.is_ident("defined") {
+                result.push(token);
+                continue;
+            }
+            let mut name = tokens.next();
+            let paren = name.as_ref().is_some_and(|t| t.is_lparen());
+            if paren {
+                name = tokens.next();
+            }
+            let defined = match name.as_ref().and_then(|t| t.ident()) {
+                Some(name) => self.is_defined(name),
+                None => {
+                    let message = String::from("operator \"defined\" requires an identifier");
+                    self.error(token.attr(), message);
+                    false
+                }
+            };
+            if paren && !tokens.next().is_some_and(|t| t.is_rparen()) {
+                self.error(token.attr(), String::from("missing ')' after \"defined\""));
+            }
+            let value = Token::Int32(token.attr(), defined as i32);
+            let mut number = PPToken::new(value, (defined as i32).to_string());
+            number.space = token.space;
+            result.push(number);
+        }
+        result
+    }
+
+    fn push_cond(&mut self, hash: PPToken, taken: bool) {
+        let cond = Cond {
+            ctx: CondCtx::Then,
+            included: taken,
+            attr: hash.attr(),
+        };
+        if let Some(frame) = self.frames.last_mut() {
+            frame.conds.push(cond);
+        }
+        if !taken {
+            self.skip_group();
+        }
+    }
+
+    fn elif(&mut self, hash: PPToken, line: Vec<PPToken>) {
+        let (ctx, included) = match self.frames.last().and_then(|f| f.conds.last()) {
+            Some(cond) => (cond.ctx, cond.included),
+            None => return self.error(hash.attr(), String::from("#elif without #if")),
+        };
+        if ctx == CondCtx::Else {
+            self.error(hash.attr(), String::from("#elif after #else"));
+        }
+        let taken = !included && self.eval_if(&hash, line);
+        let cond = self.frames.last_mut().unwrap().conds.last_mut().unwrap();
+        cond.ctx = CondCtx::Elif;
+        cond.included |= taken;
+        if !taken {
+            self.skip_group();
+        }
+    }
+
+    fn else_(&mut self, hash: PPToken, line: Vec<PPToken>) {
+        let cond = match self.frames.last_mut().and_then(|f| f.conds.last_mut()) {
+            Some(cond) => cond,
+            None => return self.error(hash.attr(), String::from("#else without #if")),
+        };
+        let (ctx, included) = (cond.ctx, cond.included);
+        cond.ctx = CondCtx::Else;
+        cond.included = true;
+        if ctx == CondCtx::Else {
+            self.error(hash.attr(), String::from("#else after #else"));
+        }
+        self.extra_tokens(&line, "else");
+        if included {
+            self.skip_group();
+        }
+    }
+
+    fn endif(&mut self, hash: PPToken, line: Vec<PPToken>) {
+        match self.frames.last_mut().and_then(|f| f.conds.pop()) {
+            Some(_) => self.extra_tokens(&line, "endif"),
+            None => self.error(hash.attr(), String::from("#endif without #if")),
+        }
+    }
+
+    /// Skips the rest of a group whose condition is false, leaving the
+    /// `#elif`, `#else` or `#endif` that ends it to be read next.
+    fn skip_group(&mut self) {
+        let frame = match self.frames.last_mut() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let mut depth = 0;
+        while let Some(token) = frame.tokens.pop() {
+            if !token.bol || !token.is_hash() {
+                continue;
+            }
+            let name = match frame.tokens.last() {
+                Some(name) if !name.bol => name.ident().unwrap_or_default(),
+                _ => continue,
+            };
+            match name {
+                "if" | "ifdef" | "ifndef" => depth += 1,
+                "elif" | "else" if depth == 0 => {
+                    frame.tokens.push(token);
+                    break;
+                }
+                "endif" if depth == 0 => {
+                    frame.tokens.push(token);
+                    break;
+                }
+                "endif" => depth -= 1,
+                _ => {}
+            }
+        }
+        // lexer errors in skipped text do not count
+        let upto = frame
+            .tokens
+            .last()
+            .map_or(usize::MAX, |t| t.attr().span().lo);
+        while frame.errors.last().is_some_and(|e| e.attr.span().lo < upto) {
+            frame.errors.pop();
+        }
+    }
+
+    fn line(&mut self, hash: &PPToken, line: Vec<PPToken>) {
+        // the directive numbers the line after it
+        let end = line.last().unwrap_or(hash).attr().span().hi;
+        let line = self.expand_all(line);
+        let row = match line.first().map(|t| &t.token) {
+            Some(Token::Int32(_, row)) if *row > 0 => *row as u32,
+            _ => {
+                let message = String::from("#line directive requires a positive integer argument");
+                return self.error(hash.attr(), message);
+            }
+        };
+        let name = match line.get(1).map(|t| &t.token) {
+            Some(Token::String(_, _, name)) => Some(name.clone()),
+            Some(_) => {
+                let message = String::from("invalid filename in #line directive");
+                return self.error(line[1].attr(), message);
+            }
+            None => None,
+        };
+        let file = self.source_map.file_mut(self.current_file());
+        if let Some(offset) = file.line_start(file.line_of(end) + 1) {
+            file.add_line_directive(offset, row, name);
+        }
+    }
+
+    fn pragma(&mut self, line: Vec<PPToken>) {
+        if line.first().is_some_and(|t| t.is_ident("once")) {
+            let file = self.source_map.file(self.current_file()).name();
+            self.once.insert(canonical(Path::new(file)));
+            self.directives.push(Directive::PragmaOnce);
+        }
+        // other pragmas are ignored
+    }
+
+    /// Fully expands `tokens` on their own, as for a macro argument or a
+    /// directive line.
+    fn expand_all(&mut self, tokens: Vec<PPToken>) -> Vec<PPToken> {
+        self.frames.push(Frame::new(self.current_file(), tokens));
+        let mut result = Vec::new();
+        while let Some(token) = self.frames.last_mut().unwrap().tokens.pop() {
+            if !self.expand(&token) {
+                result.push(token);
+            }
+        }
+        self.frames.pop();
+        result
+    }
+
+    /// Expands `token` if it invokes a macro, pushing the replacement back
+    /// to be rescanned. Returns whether it did.
+    fn expand(&mut self, token: &PPToken) -> bool {
+        let name = match token.ident() {
+            Some(name) if !token.hideset.contains(name) => name,
+            _ => return false,
+        };
+        let def = match self.macros.get(name) {
+            Some(def) => def.clone(),
+            None => return false,
+        };
+        if let Some(builtin) = def.builtin {
+            let replacement = self.builtin(builtin, token);
+            self.unget(vec![replacement]);
+            return true;
+        }
+        let mut hideset = token.hideset.clone();
+        let args = if def.is_function_like() {
+            if !self.peek_token().is_some_and(|t| t.is_lparen()) {
+                return false;
+            }
+            self.frames.last_mut().unwrap().tokens.pop();
+            let (args, rparen) = match self.read_args(&def, token) {
+                Some(args) => args,
+                // the invocation is dropped
+                None => return true,
+            };
+            hideset.retain(|n| rparen.hideset.contains(n));
+            Some(args)
+        } else {
+            None
+        };
+
+        // the body, its tokens marked as coming from this expansion
+        let expansion = Expansion::new(&def.name, def.attr.clone(), token.attr());
+        let id = self.source_map.add_expansion(expansion);
+        let mut body = def.body.clone();
+        for t in body.iter_mut() {
+            t.set_expansion(id);
+        }
+        // an object-like body has no parameters, so only `##` applies
+        let args = args.unwrap_or_default();
+        let body = self.substitute(&def, &body, &args);
+        hideset.insert(def.name.clone());
+
+        let mut body = body;
+        for (i, t) in body.iter_mut().enumerate() {
+            t.hideset.extend(hideset.iter().cloned());
+            t.bol = false;
+            if i == 0 {
+                t.space = token.space;
+            }
+        }
+        self.unget(body);
+        true
+    }
+
+    /// The replacement of `__FILE__` or `__LINE__` at `token`, which counts
+    /// as being where the outermost macro around it was invoked.
+    fn builtin(&self, builtin: Builtin, token: &PPToken) -> PPToken {
+        let attr = if token.hideset.is_empty() {
+            token.attr()
+        } else {
+            self.invocation.clone()
+        };
+        let (name, pos) = self.source_map.presumed(&attr);
+        let mut replacement = match builtin {
+            Builtin::File => string(name.to_owned(), attr),
+            Builtin::Line => {
+                let row = pos.row as i32;
+                PPToken::new(Token::Int32(attr, row), row.to_string())
+            }
+        };
+        replacement.space = token.space;
+        replacement.hideset = token.hideset.clone();
+        replacement
+    }
+
+    /// Reads the arguments of an invocation of `def` up to its `)`.
+    fn read_args(&mut self, def: &Macro, name: &PPToken) -> Option<(Vec<Vec<PPToken>>, PPToken)> {
+        let params = def.params.as_ref().map_or(0, Vec::len);
+        let mut args: Vec<Vec<PPToken>> = vec![Vec::new()];
+        let mut depth = 0;
+        let rparen = loop {
+            let token = match self.frames.last_mut().unwrap().tokens.pop() {
+                Some(token) => token,
+                None => {
+                    let message =
+                        format!("unterminated argument list invoking macro \"{}\"", def.name);
+                    self.error(name.attr(), message);
+                    return None;
+                }
+            };
+            if depth == 0 && token.is_rparen() {
+                break token;
+            }
+            if depth == 0 && token.is_comma() && !(def.variadic && args.len() == params) {
+                args.push(Vec::new());
+                continue;
+            }
+            if token.is_lparen() {
+                depth += 1;
+            } else if token.is_rparen() {
+                depth -= 1;
+            }
+            args.last_mut().unwrap().push(token);
+        };
+        if params == 0 && args.len() == 1 && args[0].is_empty() {
+            args.clear();
+        }
+        // the variadic arguments may be left out entirely
+        if def.variadic && args.len() + 1 == params {
+            args.push(Vec::new());
+        }
+        if args.len() != params {
+            let message = format!(
+                "macro \"{}\" requires {} arguments, but {} given",
+                def.name,
+                params,
+                args.len()
+            );
+            self.error(name.attr(), message);
+            return None;
+        }
+        Some((args, rparen))
+    }
+
+    /// Replaces the parameters in `body`, the body of `def`, by `args`,
+    /// applying the `#` and `##` operators. A `#` is only an operator
+    /// before a parameter, so it is left alone in object-like macros.
+    fn substitute(&mut self, def: &Macro, body: &[PPToken], args: &[Vec<PPToken>]) -> Vec<PPToken> {
+        let param = |t: &PPToken| t.ident().and_then(|name| def.param(name));
+        let va_args = def.params.as_ref().map_or(0, Vec::len).wrapping_sub(1);
+        let mut result: Vec<PPToken> = Vec::new();
+        // the left operand of a `##` was an empty argument
+        let mut placemarker = false;
+        let mut i = 0;
+        while i < body.len() {
+            let token = &body[i];
+            let next = body.get(i + 1);
+
+            if token.is_hash() {
+                if let Some(p) = next.and_then(param) {
+                    let mut string = stringize(&args[p], token.attr());
+                    string.space = token.space;
+                    result.push(string);
+                    placemarker = false;
+                    i += 2;
+                    continue;
+                }
+            }
+
+            if token.is_hashhash() {
+                let rhs = match next {
+                    Some(rhs) => rhs,
+                    None => break,
+                };
+                let operand = match param(rhs) {
+                    Some(p) if def.variadic && p == va_args => {
+                        // GNU `, ## __VA_ARGS__` drops the comma when
+                        // there are no variadic arguments
+                        if result.last().is_some_and(|t| t.is_comma()) {
+                            if args[p].is_empty() {
+                                result.pop();
+                            }
+                            result.extend(args[p].iter().cloned());
+                            i += 2;
+                            continue;
+                        }
+                        args[p].clone()
+                    }
+                    Some(p) => args[p].clone(),
+                    None => vec![rhs.clone()],
+                };
+                let mut operand = operand.into_iter();
+                if let Some(first) = operand.next() {
+                    match result.pop() {
+                        Some(lhs) if !placemarker => {
+                            let pasted = self.paste(lhs, &first);
+                            result.extend(pasted);
+                        }
+                        lhs => {
+                            result.extend(lhs);
+                            result.push(first);
+                        }
+                    }
+                    placemarker = false;
+                }
+                result.extend(operand);
+                i += 2;
+                continue;
+            }
+
+            if let Some(p) = param(token) {
+                let mut arg = if next.is_some_and(|t| t.is_hashhash()) {
+                    // an operand of `##` is not expanded
+                    placemarker = args[p].is_empty();
+                    args[p].clone()
+                } else {
+                    placemarker = false;
+                    self.expand_all(args[p].clone())
+                };
+                if let Some(first) = arg.first_mut() {
+                    first.space = token.space;
+                }
+                result.extend(arg);
+                i += 1;
+                continue;
+            }
+
+            placemarker = false;
+            result.push(token.clone());
+            i += 1;
+        }
+        result
+    }
+
+    /// The `##` operator: relexes the spellings of `lhs` and `rhs` joined
+    /// together, which must form a single token.
+    fn paste(&mut self, lhs: PPToken, rhs: &PPToken) -> Vec<PPToken> {
+        let text = format!("{}{}", lhs.text, rhs.text);
+        let mut tokenizer = Tokenizer::new(&text);
+        let token = tokenizer.next().unwrap_or(Token::EOF);
+        let rest = tokenizer.next();
+        let valid = token != Token::EOF
+            && rest == Some(Token::EOF)
+            && tokenizer.errors().is_empty()
+            && !matches!(token, Token::Illegal(_));
+        if !valid {
+            let message = format!(
+                "pasting \"{}\" and \"{}\" does not give a valid preprocessing token",
+                lhs.text, rhs.text
+            );
+            self.error(lhs.attr(), message);
+            return vec![lhs, rhs.clone()];
+        }
+        let mut token = token;
+        if let Some(attr) = token.attr_mut() {
+            *attr = lhs.attr();
+        }
+        let mut pasted = PPToken::new(token, text);
+        pasted.space = lhs.space;
+        pasted.hideset = lhs.hideset;
+        vec![pasted]
+    }
+}
+
+impl Default for Preprocessor {
+    fn default() -> Self {
+        Preprocessor::new()
+    }
+}
+
+/// The key under which `#pragma once` remembers a file.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use crate::syntax::{
+        ast::stmt::directive_stmt::Directive,
+        tokenizer::{
+            attr::Attr,
+            token::{Encoding, Token},
+            tokenizer::Tokenizer,
+        },
+    };
+
+    use super::Preprocessor;
+
+    /// Preprocesses `input` and compares the result with the tokens of
+    /// `expected`, ignoring attrs.
+    fn assert_pp(input: &str, expected: &str) {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("test.c", input);
+        assert_eq!(pp.diagnostics(), &[], "{}", input);
+        assert_eq!(strip(&tokens), strip(&tokenize(expected)), "{}", input);
+    }
+
+    fn tokenize(input: &str) -> Vec<Token> {
+        Tokenizer::new(input)
+            .take_while(|t| *t != Token::EOF)
+            .collect()
+    }
+
+    fn strip(tokens: &[Token]) -> Vec<Token> {
+        tokens
+            .iter()
+            .filter(|t| **t != Token::EOF)
+            .map(|t| {
+                let mut t = t.clone();
+                if let Some(attr) = t.attr_mut() {
+                    *attr = Attr::new();
+                }
+                t
+            })
+            .collect()
+    }
+
+    fn errors(input: &str) -> Vec<String> {
+        let mut pp = Preprocessor::new();
+        pp.preprocess_str("test.c", input);
+        pp.diagnostics().iter().map(|d| d.message.clone()).collect()
+    }
+
+    #[test]
+    pub fn test_object_like() {
+        assert_pp("#define N 10\nint a[N];", "int a[10];");
+        assert_pp("#define A B\n#define B 1\nA", "1");
+        assert_pp("#define E\nE x E", "x");
+        // a macro is not expanded inside its own expansion
+        assert_pp("#define X X + 1\nX", "X + 1");
+        assert_pp("#define A B\n#define B A\nA B", "A B");
+        assert_pp("#define int long\nint x;", "long x;");
+    }
+
+    #[test]
+    pub fn test_function_like() {
+        assert_pp("#define F(x) x * 2\nF(3)", "3 * 2");
+        assert_pp("#define F(x, y) (x + y)\nF((1, 2), [3])", "((1, 2) + [3])");
+        assert_pp("#define F() 1\nF() F", "1 F");
+        assert_pp("#define F(x) x\nF(\n1\n)", "1");
+        assert_pp(
+            "#define F(x) #x\n#define G(x) F(x)\n#define N 1\nG(N)",
+            "\"1\"",
+        );
+        assert_pp("#define F(x) x\nF(F(1))", "1");
+        assert_pp("#define f(a) a*g\n#define g(a) f(a)\nf(2)(9)", "2*9*g");
+        // an object-like macro with a parenthesized body
+        assert_pp("#define F (x)\nF(1)", "(x)(1)");
+    }
+
+    #[test]
+    pub fn test_stringize() {
+        assert_pp("#define S(x) #x\nS(a  +  b)", "\"a + b\"");
+        assert_pp("#define S(x) #x\nS(\"a\\n\")", r#""\"a\\n\"""#);
+        assert_pp("#define S(x) #x\nS()", "\"\"");
+    }
+
+    #[test]
+    pub fn test_paste() {
+        assert_pp(
+            "#define P(a, b) a ## b\nP(x, y) P(1, 2) P(+, =)",
+            "xy 12 +=",
+        );
+        assert_pp("#define P(a, b) a ## b\nP(, y) P(x, ) P(,)", "y x");
+        assert_pp("#define P(a) a ## 1\n#define x1 one\nP(x)", "one");
+        assert_pp(
+            "#define P(a, b, c) a ## b ## c\nP(1, 2, 3) P(, , z)",
+            "123 z",
+        );
+        assert_pp("#define N 1\n#define P(a) a ## N\nP(N)", "NN");
+        // object-like macros paste too
+        assert_pp("#define AB a ## b\nint AB;", "int ab;");
+        // the example of C11 6.10.3.3
+        assert_pp(
+            "#define hash_hash # ## #\n#define mkstr(a) # a\n\
+             #define in_between(a) mkstr(a)\n#define join(c, d) in_between(c hash_hash d)\n\
+             char p[] = join(x, y);",
+            "char p[] = \"x ## y\";",
+        );
+        assert_eq!(
+            errors("#define P(a, b) a ## b\nP(., ;)"),
+            ["pasting \".\" and \";\" does not give a valid preprocessing token"]
+        );
+    }
+
+    #[test]
+    pub fn test_variadic() {
+        assert_pp(
+            "#define F(fmt, ...) printf(fmt, __VA_ARGS__)\nF(\"%d %d\", 1, 2)",
+            "printf(\"%d %d\", 1, 2)",
+        );
+        assert_pp(
+            "#define F(...) [__VA_ARGS__]\nF() F(a, (b, c))",
+            "[] [a, (b, c)]",
+        );
+        assert_pp(
+            "#define F(x, ...) f(x, ## __VA_ARGS__)\nF(1) F(1, 2)",
+            "f(1) f(1, 2)",
+        );
+        assert_pp("#define S(...) #__VA_ARGS__\nS(a, b)", "\"a, b\"");
+    }
+
+    #[test]
+    pub fn test_undef_and_redefine() {
+        assert_pp("#define A 1\n#undef A\nA", "A");
+        assert_pp("#define A 1\n#define A 1\nA", "1");
+        assert_eq!(errors("#define A 1\n#define A 2"), ["\"A\" redefined"]);
+        assert_eq!(errors("#define 1"), ["macro names must be identifiers"]);
+        assert_eq!(
+            errors("#define defined"),
+            ["\"defined\" cannot be used as a macro name"]
+        );
+        assert_eq!(
+            errors("#define F(x, x) x"),
+            ["duplicate macro parameter \"x\""]
+        );
+        assert_eq!(
+            errors("#define F(x) #y"),
+            ["'#' is not followed by a macro parameter"]
+        );
+        assert_eq!(
+            errors("#define F ## x"),
+            ["'##' cannot appear at either end of a macro expansion"]
+        );
+    }
+
+    #[test]
+    pub fn test_invocation_errors() {
+        assert_eq!(
+            errors("#define F(x, y) x\nF(1)"),
+            ["macro \"F\" requires 2 arguments, but 1 given"]
+        );
+        assert_eq!(
+            errors("#define F(x) x\nF(1"),
+            ["unterminated argument list invoking macro \"F\""]
+        );
+    }
+
+    #[test]
+    pub fn test_conditionals() {
+        assert_pp("#if 1\na\n#else\nb\n#endif", "a");
+        assert_pp("#if 0\na\n#elif 1\nb\n#elif 1\nc\n#else\nd\n#endif", "b");
+        assert_pp("#if 0\na\n#elif 0\nb\n#else\nc\n#endif", "c");
+        assert_pp("#define A\n#ifdef A\na\n#endif\n#ifndef A\nb\n#endif", "a");
+        assert_pp("#if 0\n#if 1\na\n#else\nb\n#endif\n#else\nc\n#endif", "c");
+        assert_pp(
+            "#define N 3\n#if N * 2 == 6 && defined(N) && !defined M\nok\n#endif",
+            "ok",
+        );
+        assert_pp("#define F(x) (x + 1)\n#if F(1) == 2\nok\n#endif", "ok");
+        // directives and lexer errors in skipped groups are ignored
+        assert_pp("#if 0\n#error no\nit's\n#bogus\n#endif\nx", "x");
+    }
+
+    #[test]
+    pub fn test_conditional_errors() {
+        assert_eq!(errors("#endif"), ["#endif without #if"]);
+        assert_eq!(errors("#else"), ["#else without #if"]);
+        assert_eq!(errors("#elif 1"), ["#elif without #if"]);
+        assert_eq!(errors("#if 1\n"), ["unterminated conditional directive"]);
+        assert_eq!(errors("#if 1\n#else\n#else\n#endif"), ["#else after #else"]);
+        assert_eq!(
+            errors("#ifdef\n#endif"),
+            ["no macro name given in #ifdef directive"]
+        );
+        assert_eq!(errors("#if\n#endif"), ["#if with no expression"]);
+    }
+
+    #[test]
+    pub fn test_error_directive() {
+        assert_eq!(errors("#error stop  here"), ["#error stop here"]);
+        assert_eq!(errors("#if 0\n#error no\n#endif"), Vec::<String>::new());
+        assert_eq!(errors("#foo"), ["invalid preprocessing directive #foo"]);
+        // the null directive
+        assert_eq!(errors("#\nx"), Vec::<String>::new());
+    }
+
+    #[test]
+    pub fn test_line() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("a.c", "#line 10 \"b.c\"\nx\n#line 20\ny\n# 5 \"c.c\"\nz");
+        let locations: Vec<String> = tokens
+            .iter()
+            .filter_map(|t| t.attr())
+            .map(|a| pp.source_map().location(a))
+            .collect();
+        assert_eq!(locations, ["b.c:10:1", "b.c:20:1", "c.c:5:1"]);
+    }
+
+    #[test]
+    pub fn test_hash_not_at_line_start() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("a.c", "#define H #\nH define x\na # b");
+        assert!(pp.diagnostics().is_empty());
+        assert_eq!(
+            tokens
+                .iter()
+                .filter(|t| matches!(t, Token::Hash(_)))
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    pub fn test_file_and_line() {
+        assert_pp("__LINE__\n\n__LINE__", "1 3");
+        assert_pp("#define L __LINE__\n\nL", "3");
+        assert_pp("#define F(x) x\nF(\n__LINE__\n)", "3");
+        assert_pp(
+            "__FILE__\n#line 7 \"b.c\"\n__FILE__ __LINE__",
+            "\"test.c\" \"b.c\" 7",
+        );
+        assert_pp("#if __LINE__ == 1\nok\n#endif", "ok");
+    }
+
+    #[test]
+    pub fn test_predefined() {
+        assert_pp(
+            "__STDC__ __STDC_VERSION__ __STDC_HOSTED__ __x86_64__ __liyuu__",
+            "1 201112L 1 1 1",
+        );
+        assert_pp(
+            "#if defined(__DATE__) && defined __TIME__ && defined __FILE__\nok\n#endif",
+            "ok",
+        );
+        let mut pp = Preprocessor::empty(Default::default());
+        pp.preprocess_str("a.c", "__STDC__ __LINE__");
+        assert!(!pp.is_defined("__STDC__"));
+        assert!(!pp.is_defined("__LINE__"));
+    }
+
+    #[test]
+    pub fn test_command_line() {
+        let src = "#ifdef DEBUG\nlog(LEVEL);\n#endif\nF(2);";
+        let configure = |debug: bool| {
+            let mut pp = Preprocessor::new();
+            if debug {
+                pp.define("DEBUG");
+                pp.define("LEVEL=3");
+            }
+            pp.define("F(x)=x * x");
+            pp.define("EMPTY=");
+            pp.undefine("__liyuu__");
+            pp
+        };
+        let mut pp = configure(true);
+        let tokens = pp.preprocess_str("a.c", src);
+        assert_eq!(pp.diagnostics(), &[]);
+        assert_eq!(strip(&tokens), strip(&tokenize("log(3); 2 * 2;")));
+        assert_eq!(pp.macro_def("DEBUG").unwrap().body[0].text, "1");
+        assert!(pp.macro_def("EMPTY").unwrap().body.is_empty());
+        assert!(!pp.is_defined("__liyuu__"));
+
+        let mut pp = configure(false);
+        let tokens = pp.preprocess_str("a.c", src);
+        assert_eq!(strip(&tokens), strip(&tokenize("2 * 2;")));
+    }
+
+    #[test]
+    pub fn test_command_line_errors() {
+        let mut pp = Preprocessor::new();
+        pp.define("1=2");
+        let diagnostic = &pp.diagnostics()[0];
+        assert_eq!(diagnostic.message, "macro names must be identifiers");
+        assert_eq!(
+            pp.source_map().location(&diagnostic.attr),
+            "<command line>:1:9"
+        );
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("liyuu-pp-{}-{}", name, std::process::id()));
+        fs::create_dir_all(dir.join("inc")).unwrap();
+        dir
+    }
+
+    #[test]
+    pub fn test_include() {
+        let dir = temp_dir("include");
+        fs::write(dir.join("a.h"), "#pragma once\nint a;\n").unwrap();
+        fs::write(dir.join("inc").join("b.h"), "#include \"a.h\"\nint b;\n").unwrap();
+        fs::write(dir.join("inc").join("a.h"), "int wrong;\n").unwrap();
+        let main = dir.join("main.c");
+        fs::write(
+            &main,
+            "#include \"a.h\"\n#include <b.h>\n#define H \"a.h\"\n#include H\nint c;\n",
+        )
+        .unwrap();
+
+        let mut pp = Preprocessor::new();
+        pp.add_include_dir(dir.join("inc"));
+        let tokens = pp.preprocess_file(&main).unwrap();
+        assert_eq!(pp.diagnostics(), &[]);
+        let names: Vec<&str> = tokens
+            .iter()
+            .filter_map(|t| match t {
+                Token::ID(_, name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+        // `b.h` finds `inc/a.h` next to itself first
+        assert_eq!(names, ["a", "wrong", "b", "c"]);
+        let includes: Vec<&Directive> = pp.directives().iter().collect();
+        assert_eq!(includes.len(), 4);
+        assert_eq!(includes[1], &Directive::PragmaOnce);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    pub fn test_include_errors() {
+        let dir = temp_dir("include-errors");
+        fs::write(dir.join("self.h"), "#include \"self.h\"\n").unwrap();
+        let mut pp = Preprocessor::new();
+        pp.preprocess_file(&dir.join("self.h")).unwrap();
+        let messages: Vec<&str> = pp
+            .diagnostics()
+            .iter()
+            .map(|d| d.message.as_str())
+            .collect();
+        assert_eq!(messages, ["#include nested too deeply"]);
+        fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(errors("#include \"none.h\""), ["'none.h' file not found"]);
+        assert_eq!(
+            errors("#include none"),
+            ["#include expects \"FILENAME\" or <FILENAME>"]
+        );
+    }
+
+    #[test]
+    pub fn test_strings_concatenate_after_expansion() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_str("a.c", "#define S(x) #x\nS(a) \"b\"");
+        assert!(matches!(&tokens[0], Token::String(_, Encoding::Plain, s) if s == "a"));
+        assert_eq!(tokens.len(), 3);
+    }
+
+    #[test]
+    pub fn test_example() {
+        let mut pp = Preprocessor::new();
+        let tokens = pp.preprocess_file("examples/c1.c".as_ref()).unwrap();
+        assert!(!pp.has_errors());
+        assert!(tokens.len() > 1);
+    }
+}
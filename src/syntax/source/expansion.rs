@@ -0,0 +1,26 @@
+use crate::syntax::tokenizer::attr::Attr;
+
+/// Identifies an `Expansion` recorded in a `SourceMap`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct ExpansionId(pub u32);
+
+/// One expansion of a macro. The tokens of the macro body that it
+/// produced carry its id in their attrs; `invocation` is the attr of the
+/// macro name where it was invoked, which carries the id of the enclosing
+/// expansion when the invocation itself came from a macro.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Expansion {
+    pub name: String,
+    pub definition: Attr,
+    pub invocation: Attr,
+}
+
+impl Expansion {
+    pub fn new(name: &str, definition: Attr, invocation: Attr) -> Expansion {
+        Expansion {
+            name: name.to_owned(),
+            definition,
+            invocation,
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod expansion;
+pub mod source_file;
+pub mod source_map;
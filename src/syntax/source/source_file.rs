@@ -0,0 +1,181 @@
+use crate::syntax::tokenizer::pos::Pos;
+
+/// Identifies a file loaded into a `SourceMap`.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct FileId(pub u32);
+
+pub struct SourceFile {
+    id: FileId,
+    name: String,
+    src: String,
+    // byte offset of the first char of every line
+    line_starts: Vec<usize>,
+    // `#line` directives, in source order
+    line_directives: Vec<LineDirective>,
+}
+
+/// A `#line` directive: the line starting at byte `offset` is presumed to
+/// be line `row` of `name`, or of the file itself when there is no name.
+struct LineDirective {
+    offset: usize,
+    row: u32,
+    name: Option<String>,
+}
+
+impl SourceFile {
+    pub fn new(id: FileId, name: &str, src: String) -> SourceFile {
+        let mut line_starts = vec![0];
+        let bytes = src.as_bytes();
+        for (i, &b) in bytes.iter().enumerate() {
+            let is_break = b == b'\n' || b == b'\r' && bytes.get(i + 1) != Some(&b'\n');
+            if is_break {
+                line_starts.push(i + 1);
+            }
+        }
+        SourceFile {
+            id,
+            name: name.to_owned(),
+            src,
+            line_starts,
+            line_directives: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The 1-based line containing the byte `offset`.
+    pub fn line_of(&self, offset: usize) -> u32 {
+        match self.line_starts.binary_search(&offset) {
+            Ok(i) => i as u32 + 1,
+            Err(i) => i as u32,
+        }
+    }
+
+    /// The byte offset where the 1-based line `row` starts.
+    pub fn line_start(&self, row: u32) -> Option<usize> {
+        self.line_starts.get(row.checked_sub(1)? as usize).copied()
+    }
+
+    /// The text of the 1-based line `row`, without its line break.
+    pub fn line(&self, row: u32) -> Option<&str> {
+        let start = *self.line_starts.get(row.checked_sub(1)? as usize)?;
+        let end = self
+            .line_starts
+            .get(row as usize)
+            .copied()
+            .unwrap_or(self.src.len());
+        Some(self.src[start..end].trim_end_matches(['\n', '\r']))
+    }
+
+    /// The 1-based row and display column of the byte `offset`. Columns
+    /// count chars rather than bytes, and a tab advances to the next
+    /// multiple of `tab_width`.
+    pub fn lookup(&self, offset: usize, tab_width: u32) -> Pos {
+        let offset = offset.min(self.src.len());
+        let row = self.line_of(offset);
+        let start = self.line_starts[row as usize - 1];
+        let mut col = 0;
+        for c in self.src[start..offset].chars() {
+            if c == '\t' && tab_width > 0 {
+                col = (col / tab_width + 1) * tab_width;
+            } else {
+                col += 1;
+            }
+        }
+        Pos::new(row, col + 1)
+    }
+
+    /// Records a `#line` directive numbering the line that starts at byte
+    /// `offset` as `row`, optionally renaming the file from there on.
+    pub fn add_line_directive(&mut self, offset: usize, row: u32, name: Option<String>) {
+        let name = name.or_else(|| {
+            self.line_directives
+                .iter()
+                .rev()
+                .find(|d| d.offset <= offset)
+                .and_then(|d| d.name.clone())
+        });
+        self.line_directives
+            .push(LineDirective { offset, row, name });
+    }
+
+    /// The file name and position of the byte `offset` as adjusted by the
+    /// `#line` directives before it.
+    pub fn presumed(&self, offset: usize, tab_width: u32) -> (&str, Pos) {
+        let pos = self.lookup(offset, tab_width);
+        match self
+            .line_directives
+            .iter()
+            .rev()
+            .find(|d| d.offset <= offset)
+        {
+            Some(d) => {
+                let row = d.row + (pos.row - self.line_of(d.offset));
+                let name = d.name.as_deref().unwrap_or(&self.name);
+                (name, Pos::new(row, pos.col))
+            }
+            None => (&self.name, pos),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::pos::Pos;
+
+    use super::{FileId, SourceFile};
+
+    #[test]
+    pub fn test_lines() {
+        let file = SourceFile::new(FileId(0), "a.c", String::from("ab\ncd\r\n\ref"));
+        assert_eq!(file.line_count(), 4);
+        assert_eq!(file.line(1), Some("ab"));
+        assert_eq!(file.line(2), Some("cd"));
+        assert_eq!(file.line(3), Some(""));
+        assert_eq!(file.line(4), Some("ef"));
+        assert_eq!(file.line(5), None);
+        assert_eq!(file.line_of(0), 1);
+        assert_eq!(file.line_of(2), 1);
+        assert_eq!(file.line_of(3), 2);
+        assert_eq!(file.line_of(9), 4);
+    }
+
+    #[test]
+    pub fn test_lookup() {
+        let file = SourceFile::new(FileId(0), "a.c", String::from("a\tb\n\té = 1;\n"));
+        assert_eq!(file.lookup(0, 8), Pos::new(1, 1));
+        assert_eq!(file.lookup(2, 8), Pos::new(1, 9));
+        assert_eq!(file.lookup(2, 4), Pos::new(1, 5));
+        assert_eq!(file.lookup(4, 4), Pos::new(2, 1));
+        assert_eq!(file.lookup(5, 4), Pos::new(2, 5));
+        // `é` takes two bytes but one column
+        assert_eq!(file.lookup(8, 4), Pos::new(2, 7));
+        assert_eq!(file.lookup(100, 4), Pos::new(3, 1));
+    }
+
+    #[test]
+    pub fn test_presumed() {
+        let mut file = SourceFile::new(FileId(0), "a.c", String::from("a\n#line 10\nb\nc\n"));
+        assert_eq!(file.presumed(0, 8), ("a.c", Pos::new(1, 1)));
+        file.add_line_directive(11, 10, None);
+        assert_eq!(file.presumed(11, 8), ("a.c", Pos::new(10, 1)));
+        assert_eq!(file.presumed(13, 8), ("a.c", Pos::new(11, 1)));
+        file.add_line_directive(13, 20, Some(String::from("b.c")));
+        assert_eq!(file.presumed(13, 8), ("b.c", Pos::new(20, 1)));
+        assert_eq!(file.presumed(0, 8), ("a.c", Pos::new(1, 1)));
+    }
+}
@@ -0,0 +1,151 @@
+use std::{fs, io, path::Path};
+
+use crate::syntax::tokenizer::{attr::Attr, pos::Pos};
+
+use super::{
+    expansion::{Expansion, ExpansionId},
+    source_file::{FileId, SourceFile},
+};
+
+/// Owns every file of a compilation, so that an `Attr` can be resolved
+/// back to its file name, text and display position, and the macro
+/// expansions it came through.
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+    expansions: Vec<Expansion>,
+    tab_width: u32,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap {
+            files: Vec::new(),
+            expansions: Vec::new(),
+            tab_width: 8,
+        }
+    }
+
+    pub fn set_tab_width(&mut self, tab_width: u32) {
+        self.tab_width = tab_width;
+    }
+
+    pub fn add_file(&mut self, name: &str, src: String) -> FileId {
+        let id = FileId(self.files.len() as u32);
+        self.files.push(SourceFile::new(id, name, src));
+        id
+    }
+
+    pub fn load_file(&mut self, path: &Path) -> io::Result<FileId> {
+        let src = fs::read_to_string(path)?;
+        Ok(self.add_file(&path.to_string_lossy(), src))
+    }
+
+    pub fn file(&self, id: FileId) -> &SourceFile {
+        &self.files[id.0 as usize]
+    }
+
+    pub fn file_mut(&mut self, id: FileId) -> &mut SourceFile {
+        &mut self.files[id.0 as usize]
+    }
+
+    pub fn find_file(&self, name: &str) -> Option<FileId> {
+        self.files.iter().find(|f| f.name() == name).map(|f| f.id())
+    }
+
+    pub fn add_expansion(&mut self, expansion: Expansion) -> ExpansionId {
+        let id = ExpansionId(self.expansions.len() as u32);
+        self.expansions.push(expansion);
+        id
+    }
+
+    pub fn expansion(&self, id: ExpansionId) -> &Expansion {
+        &self.expansions[id.0 as usize]
+    }
+
+    /// The expansions `attr` came through, innermost first.
+    pub fn expansion_chain(&self, attr: &Attr) -> Vec<&Expansion> {
+        let mut chain = Vec::new();
+        let mut next = attr.expansion();
+        while let Some(id) = next {
+            let expansion = self.expansion(id);
+            chain.push(expansion);
+            next = expansion.invocation.expansion();
+        }
+        chain
+    }
+
+    /// The display position of the start of `attr`.
+    pub fn lookup(&self, attr: &Attr) -> Pos {
+        self.file(attr.file())
+            .lookup(attr.span().lo, self.tab_width)
+    }
+
+    /// The source text covered by `attr`.
+    pub fn text(&self, attr: &Attr) -> &str {
+        let span = attr.span();
+        &self.file(attr.file()).src()[span.lo..span.hi]
+    }
+
+    /// The file name and position of the start of `attr` as adjusted by
+    /// `#line` directives.
+    pub fn presumed(&self, attr: &Attr) -> (&str, Pos) {
+        self.file(attr.file())
+            .presumed(attr.span().lo, self.tab_width)
+    }
+
+    /// Formats the presumed start of `attr` as `file:row:col`.
+    pub fn location(&self, attr: &Attr) -> String {
+        let (name, pos) = self.presumed(attr);
+        format!("{}:{}:{}", name, pos.row, pos.col)
+    }
+}
+
+impl Default for SourceMap {
+    fn default() -> Self {
+        SourceMap::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::{token::Token, tokenizer::Tokenizer};
+
+    use super::SourceMap;
+
+    #[test]
+    pub fn test_source_map() {
+        let mut source_map = SourceMap::new();
+        source_map.set_tab_width(4);
+        let header = source_map.add_file("a.h", String::from("int a;\n"));
+        let main = source_map.add_file("main.c", String::from("\tb;\n"));
+        assert_eq!(source_map.find_file("main.c"), Some(main));
+        assert_eq!(source_map.file(header).name(), "a.h");
+
+        let src = source_map.file(main).src();
+        let mut tokenizer = Tokenizer::with_file(src, main);
+        match tokenizer.next() {
+            Some(Token::ID(attr, name)) => {
+                assert_eq!(name, "b");
+                assert_eq!(attr.file(), main);
+                assert_eq!(source_map.text(&attr), "b");
+                assert_eq!(source_map.location(&attr), "main.c:1:5");
+            }
+            token => panic!("unexpected {:?}", token),
+        }
+    }
+
+    #[test]
+    pub fn test_long_lines() {
+        let mut source_map = SourceMap::new();
+        let src = format!("{}x", " ".repeat(300));
+        let file = source_map.add_file("long.c", src);
+        let mut tokenizer = Tokenizer::with_file(source_map.file(file).src(), file);
+        match tokenizer.next() {
+            Some(Token::ID(attr, _)) => {
+                assert_eq!(attr.start().col, 301);
+                assert_eq!(source_map.location(&attr), "long.c:1:301");
+            }
+            token => panic!("unexpected {:?}", token),
+        }
+    }
+}
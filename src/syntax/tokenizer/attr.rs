@@ -1,31 +1,83 @@
-use super::{pos::Pos, range::Range};
+use crate::syntax::source::{expansion::ExpansionId, source_file::FileId};
 
+use super::{pos::Pos, range::Range, span::Span};
+
+/// Where a token or node comes from: its file, its byte span in that file
+/// and the row/col range of its first and last chars. Tokens from the body
+/// of a macro also record the expansion that produced them.
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Attr {
+    file: FileId,
+    span: Span,
     range: Range,
+    expansion: Option<ExpansionId>,
 }
 
 impl Attr {
     pub fn new() -> Attr {
         Attr {
+            file: FileId::default(),
+            span: Span::default(),
             range: Range::new(),
+            expansion: None,
         }
     }
 
-    pub fn point(row: u32, col: u8) -> Attr {
+    pub fn point(row: u32, col: u32) -> Attr {
         let mut range = Range::new();
         range.set_start(row, col);
         range.set_end(row, col);
-        Attr { range }
+        Attr {
+            range,
+            ..Attr::new()
+        }
     }
 
-    pub fn range(start_row: u32, start_col: u8, end_row: u32, end_col: u8) -> Attr {
+    pub fn range(start_row: u32, start_col: u32, end_row: u32, end_col: u32) -> Attr {
         let mut attr = Attr::new();
         attr.set_start(start_row, start_col);
         attr.set_end(end_row, end_col);
         attr
     }
 
+    pub fn with_span(mut self, lo: usize, hi: usize) -> Attr {
+        self.span = Span::new(lo, hi);
+        self
+    }
+
+    pub fn with_file(mut self, file: FileId) -> Attr {
+        self.file = file;
+        self
+    }
+
+    pub fn file(&self) -> FileId {
+        self.file
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn expansion(&self) -> Option<ExpansionId> {
+        self.expansion
+    }
+
+    pub fn start(&self) -> &Pos {
+        self.range.start()
+    }
+
+    pub fn end(&self) -> &Pos {
+        self.range.end()
+    }
+
+    pub fn set_file(&mut self, file: FileId) {
+        self.file = file;
+    }
+
+    pub fn set_expansion(&mut self, expansion: Option<ExpansionId>) {
+        self.expansion = expansion;
+    }
+
     pub fn set_start_pos(&mut self, pos: Pos) {
         self.set_start(pos.row, pos.col);
     }
@@ -34,17 +86,36 @@ impl Attr {
         self.set_end(pos.row, pos.col);
     }
 
-    fn set_start(&mut self, row: u32, col: u8) {
+    pub fn set_start_offset(&mut self, offset: usize) {
+        self.span.lo = offset;
+    }
+
+    pub fn set_end_offset(&mut self, offset: usize) {
+        self.span.hi = offset;
+    }
+
+    /// Extends this attr to the end of `other`.
+    pub fn extend_to(&mut self, other: &Attr) {
+        self.set_end_pos(other.end().clone());
+        self.set_end_offset(other.span.hi);
+    }
+
+    fn set_start(&mut self, row: u32, col: u32) {
         self.range.set_start(row, col);
     }
 
-    fn set_end(&mut self, row: u32, col: u8) {
+    fn set_end(&mut self, row: u32, col: u32) {
         self.range.set_end(row, col);
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::syntax::{
+        source::source_file::FileId,
+        tokenizer::{pos::Pos, span::Span},
+    };
+
     use super::Range;
 
     use super::Attr;
@@ -53,5 +124,15 @@ mod tests {
     pub fn test_default() {
         let attr = Attr::default();
         assert_eq!(attr.range, Range::default());
+        assert_eq!(attr.span, Span::default());
+        assert_eq!(attr.file, FileId::default());
+    }
+
+    #[test]
+    pub fn test_extend_to() {
+        let mut attr = Attr::range(1, 1, 1, 2).with_span(0, 2);
+        attr.extend_to(&Attr::range(2, 3, 2, 4).with_span(7, 9));
+        assert_eq!(attr, Attr::range(1, 1, 2, 4).with_span(0, 9));
+        assert_eq!(attr.start(), &Pos::new(1, 1));
     }
 }
@@ -0,0 +1,112 @@
+use std::iter::Peekable;
+
+use super::{
+    error::{LexError, LexErrorKind},
+    token::{Encoding, Token},
+};
+
+/// Concatenates adjacent string literals (translation phase 6), so
+/// `"ab" L"cd"` becomes the single literal `L"abcd"`.
+///
+/// A prefixed literal takes over unprefixed neighbours; literals with two
+/// different prefixes are reported and keep the first prefix.
+pub struct Concat<I: Iterator<Item = Token>> {
+    tokens: Peekable<I>,
+    errors: Vec<LexError>,
+}
+
+impl<I: Iterator<Item = Token>> Concat<I> {
+    pub fn new(tokens: I) -> Self {
+        Concat {
+            tokens: tokens.peekable(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
+    }
+}
+
+impl<I: Iterator<Item = Token>> Iterator for Concat<I> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        let (mut attr, mut encoding, mut s) = match self.tokens.next()? {
+            Token::String(attr, encoding, s) => (attr, encoding, s),
+            token => return Some(token),
+        };
+        while let Some(Token::String(..)) = self.tokens.peek() {
+            if let Some(Token::String(attr1, encoding1, s1)) = self.tokens.next() {
+                if encoding == Encoding::Plain {
+                    encoding = encoding1;
+                } else if encoding1 != Encoding::Plain && encoding1 != encoding {
+                    let error =
+                        LexError::new(attr1.clone(), LexErrorKind::MismatchedStringPrefixes);
+                    self.errors.push(error);
+                }
+                attr.extend_to(&attr1);
+                s.push_str(&s1);
+            }
+        }
+        Some(Token::String(attr, encoding, s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::{
+        attr::Attr,
+        error::{LexError, LexErrorKind},
+        token::{Encoding, Token},
+        tokenizer::Tokenizer,
+    };
+
+    use super::Concat;
+
+    #[test]
+    pub fn test_concat() {
+        let mut tokens = Concat::new(Tokenizer::new("\"ab\" \"c\"\n  L\"d\" ;\"e\""));
+        assert_eq!(
+            tokens.next(),
+            Some(Token::String(
+                Attr::range(1, 1, 2, 6).with_span(0, 15),
+                Encoding::Wide,
+                String::from("abcd")
+            ))
+        );
+        assert_eq!(
+            tokens.next(),
+            Some(Token::Semicolon(Attr::point(2, 8).with_span(16, 17)))
+        );
+        assert_eq!(
+            tokens.next(),
+            Some(Token::String(
+                Attr::range(2, 9, 2, 11).with_span(17, 20),
+                Encoding::Plain,
+                String::from("e")
+            ))
+        );
+        assert!(tokens.errors().is_empty());
+    }
+
+    #[test]
+    pub fn test_concat_mismatched_prefixes() {
+        let mut tokens = Concat::new(Tokenizer::new("u\"a\" U\"b\""));
+        assert_eq!(
+            tokens.next(),
+            Some(Token::String(
+                Attr::range(1, 1, 1, 9).with_span(0, 9),
+                Encoding::Utf16,
+                String::from("ab")
+            ))
+        );
+        assert_eq!(
+            tokens.errors(),
+            &[LexError::new(
+                Attr::range(1, 6, 1, 9).with_span(5, 9),
+                LexErrorKind::MismatchedStringPrefixes
+            )]
+        );
+    }
+}
@@ -1,34 +1,59 @@
 use super::pos::Pos;
 
+/// Tracks the position of the last consumed char, both as row/col and as
+/// a byte offset.
 pub struct Cursor {
-    pub index: i32,
+    offset: usize,
+    next_offset: usize,
     row: u32,
-    col: u8,
+    col: u32,
 }
 
 impl Cursor {
     pub fn new() -> Cursor {
         Cursor {
-            index: -1,
+            offset: 0,
+            next_offset: 0,
             row: 1,
             col: 0,
         }
     }
 
-    pub fn incr_col(&mut self) {
-        self.index += 1;
+    pub fn incr_col(&mut self, c: char) {
+        self.bump(c);
         self.col += 1;
     }
 
-    pub fn incr_row(&mut self) {
-        self.index += 1;
+    pub fn incr_row(&mut self, c: char) {
+        self.bump(c);
         self.row += 1;
         self.col = 0
     }
 
+    fn bump(&mut self, c: char) {
+        self.offset = self.next_offset;
+        self.next_offset += c.len_utf8();
+    }
+
     pub fn cur_pos(&mut self) -> Pos {
         Pos::new(self.row, self.col)
     }
+
+    /// The byte offset of the last consumed char.
+    pub fn cur_offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The byte offset just past the last consumed char.
+    pub fn next_offset(&self) -> usize {
+        self.next_offset
+    }
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Cursor::new()
+    }
 }
 
 #[cfg(test)]
@@ -41,11 +66,22 @@ mod tests {
     pub fn test_cursor() {
         let mut cursor = Cursor::new();
         assert_eq!(cursor.cur_pos(), Pos::new(1, 0));
-        cursor.incr_col();
+        cursor.incr_col('a');
         assert_eq!(cursor.cur_pos(), Pos::new(1, 1));
-        cursor.incr_row();
+        cursor.incr_row('\n');
         assert_eq!(cursor.cur_pos(), Pos::new(2, 0));
-        cursor.incr_col();
+        cursor.incr_col('a');
         assert_eq!(cursor.cur_pos(), Pos::new(2, 1));
     }
+
+    #[test]
+    pub fn test_offsets() {
+        let mut cursor = Cursor::new();
+        cursor.incr_col('a');
+        assert_eq!((cursor.cur_offset(), cursor.next_offset()), (0, 1));
+        cursor.incr_col('é');
+        assert_eq!((cursor.cur_offset(), cursor.next_offset()), (1, 3));
+        cursor.incr_row('\n');
+        assert_eq!((cursor.cur_offset(), cursor.next_offset()), (3, 4));
+    }
 }
@@ -0,0 +1,134 @@
+use std::fmt;
+
+use super::attr::Attr;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum LexErrorKind {
+    // 0x, 1e+
+    MissingDigits,
+    // 08
+    InvalidDigit(char),
+    // 1abc, 1.0u
+    InvalidSuffix(String),
+    // 0x1.0
+    MissingHexExponent,
+    // 18446744073709551616
+    IntegerTooLarge,
+    // 9223372036854775808 fits only an unsigned type
+    IntegerTooLargeForSigned,
+    // 1e999
+    FloatOutOfRange,
+    // '\q'
+    UnknownEscape(char),
+    // '\x'
+    MissingHexEscapeDigits,
+    // '\x100', '\777'
+    EscapeOutOfRange,
+    // '\u12'
+    IncompleteUniversalChar,
+    // '\u0041', '\ud800'
+    InvalidUniversalChar(u32),
+    // ''
+    EmptyCharConstant,
+    // 'ab'
+    MultiCharConstant,
+    // u"a" U"b"
+    MismatchedStringPrefixes,
+    // /* ...
+    UnterminatedComment,
+    // 'a
+    UnterminatedChar,
+    // "abc
+    UnterminatedString,
+    // @
+    UnexpectedChar(char),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct LexError {
+    pub attr: Attr,
+    pub kind: LexErrorKind,
+}
+
+impl LexError {
+    pub fn new(attr: Attr, kind: LexErrorKind) -> LexError {
+        LexError { attr, kind }
+    }
+}
+
+impl LexErrorKind {
+    /// Whether the literal is still usable, so the error only deserves a
+    /// warning.
+    pub fn is_warning(&self) -> bool {
+        matches!(
+            self,
+            LexErrorKind::IntegerTooLargeForSigned
+                | LexErrorKind::UnknownEscape(_)
+                | LexErrorKind::MultiCharConstant
+        )
+    }
+}
+
+impl fmt::Display for LexErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LexErrorKind::MissingDigits => write!(f, "numeric literal has no digits"),
+            LexErrorKind::InvalidDigit(c) => write!(f, "invalid digit '{}' in numeric literal", c),
+            LexErrorKind::InvalidSuffix(s) => {
+                write!(f, "invalid suffix '{}' on numeric literal", s)
+            }
+            LexErrorKind::MissingHexExponent => {
+                write!(f, "hexadecimal floating literal requires an exponent")
+            }
+            LexErrorKind::IntegerTooLarge => {
+                write!(f, "integer literal is too large for any integer type")
+            }
+            LexErrorKind::IntegerTooLargeForSigned => {
+                write!(
+                    f,
+                    "integer literal is too large for a signed type, treated as unsigned"
+                )
+            }
+            LexErrorKind::FloatOutOfRange => write!(f, "floating literal is out of range"),
+            LexErrorKind::UnknownEscape(c) => write!(f, "unknown escape sequence '\\{}'", c),
+            LexErrorKind::MissingHexEscapeDigits => {
+                write!(f, "\\x used with no following hex digits")
+            }
+            LexErrorKind::EscapeOutOfRange => write!(f, "escape sequence out of range"),
+            LexErrorKind::IncompleteUniversalChar => {
+                write!(f, "incomplete universal character name")
+            }
+            LexErrorKind::InvalidUniversalChar(v) => {
+                write!(f, "\\u{:04X} is not a valid universal character", v)
+            }
+            LexErrorKind::EmptyCharConstant => write!(f, "empty character constant"),
+            LexErrorKind::MultiCharConstant => write!(f, "multi-character character constant"),
+            LexErrorKind::MismatchedStringPrefixes => {
+                write!(f, "concatenated string literals have different prefixes")
+            }
+            LexErrorKind::UnterminatedComment => write!(f, "unterminated /* comment"),
+            LexErrorKind::UnterminatedChar => write!(f, "missing terminating ' character"),
+            LexErrorKind::UnterminatedString => write!(f, "missing terminating \" character"),
+            LexErrorKind::UnexpectedChar(c) => write!(f, "unexpected character '{}'", c),
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::attr::Attr;
+
+    use super::{LexError, LexErrorKind};
+
+    #[test]
+    pub fn test_display() {
+        let e = LexError::new(Attr::point(1, 1), LexErrorKind::InvalidDigit('8'));
+        assert_eq!(e.to_string(), "invalid digit '8' in numeric literal");
+    }
+}
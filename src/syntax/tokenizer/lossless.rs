@@ -0,0 +1,280 @@
+use std::fmt;
+
+use super::{token::Token, tokenizer::Tokenizer};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TriviaKind {
+    // spaces, tabs, vertical tabs and form feeds
+    Whitespace,
+    // \n, \r\n or \r
+    Newline,
+    // // ...
+    LineComment,
+    // /* ... */
+    BlockComment,
+    // a backslash-newline between tokens
+    Splice,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Trivia {
+    pub kind: TriviaKind,
+    pub text: String,
+}
+
+/// A token together with its exact spelling and the trivia around it.
+///
+/// Trailing trivia runs up to and including the first line break after the
+/// token; everything else before a token is its leading trivia. The final
+/// `Token::EOF` carries whatever trivia ends the file.
+#[derive(Debug, PartialEq)]
+pub struct LosslessToken {
+    pub leading: Vec<Trivia>,
+    pub token: Token,
+    pub text: String,
+    pub trailing: Vec<Trivia>,
+}
+
+impl LosslessToken {
+    pub fn has_leading_newline(&self) -> bool {
+        self.leading.iter().any(|t| t.kind == TriviaKind::Newline)
+    }
+
+    pub fn ends_line(&self) -> bool {
+        self.trailing.iter().any(|t| t.kind == TriviaKind::Newline)
+    }
+}
+
+impl fmt::Display for LosslessToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for trivia in &self.leading {
+            write!(f, "{}", trivia.text)?;
+        }
+        write!(f, "{}", self.text)?;
+        for trivia in &self.trailing {
+            write!(f, "{}", trivia.text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lexes in lossless mode: concatenating the `Display` of every token,
+/// up to and including `EOF`, reproduces the input byte for byte.
+pub struct Lossless<'a> {
+    input: &'a str,
+    tokenizer: Tokenizer<'a>,
+    // the next token, already split from the trivia before it
+    next: Option<(Vec<Trivia>, Token)>,
+    offset: usize,
+    done: bool,
+}
+
+impl<'a> Lossless<'a> {
+    pub fn new(input: &'a str, tokenizer: Tokenizer<'a>) -> Self {
+        Lossless {
+            input,
+            tokenizer,
+            next: None,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    pub fn tokenizer(&self) -> &Tokenizer<'a> {
+        &self.tokenizer
+    }
+
+    fn span_of(&self, token: &Token) -> (usize, usize) {
+        match token.attr() {
+            Some(attr) => (attr.span().lo, attr.span().hi),
+            None => (self.input.len(), self.input.len()),
+        }
+    }
+
+    /// Lexes the next token and the trivia between it and the last one.
+    fn lex(&mut self) -> (Vec<Trivia>, Token) {
+        let token = self.tokenizer.next().unwrap_or(Token::EOF);
+        let (lo, hi) = self.span_of(&token);
+        let trivia = scan_trivia(&self.input[self.offset..lo]);
+        self.offset = hi;
+        (trivia, token)
+    }
+}
+
+impl<'a> Iterator for Lossless<'a> {
+    type Item = LosslessToken;
+
+    fn next(&mut self) -> Option<LosslessToken> {
+        if self.done {
+            return None;
+        }
+        let (leading, token) = match self.next.take() {
+            Some(next) => next,
+            None => self.lex(),
+        };
+        let (lo, hi) = self.span_of(&token);
+        let text = self.input[lo..hi].to_owned();
+        if token == Token::EOF {
+            self.done = true;
+            return Some(LosslessToken {
+                leading,
+                token,
+                text,
+                trailing: Vec::new(),
+            });
+        }
+
+        let (mut trivia, next) = self.lex();
+        let split = trivia
+            .iter()
+            .position(|t| t.kind == TriviaKind::Newline)
+            .map_or(trivia.len(), |i| i + 1);
+        let leading_of_next = trivia.split_off(split);
+        self.next = Some((leading_of_next, next));
+        Some(LosslessToken {
+            leading,
+            token,
+            text,
+            trailing: trivia,
+        })
+    }
+}
+
+/// Splits the text between two tokens, which the tokenizer has already
+/// skipped as whitespace and comments, into trivia.
+fn scan_trivia(text: &str) -> Vec<Trivia> {
+    let mut trivia: Vec<Trivia> = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let (kind, len) = if let Some(len) = newline_len(rest) {
+            (TriviaKind::Newline, len)
+        } else if let Some(len) = rest.strip_prefix('\\').and_then(newline_len) {
+            (TriviaKind::Splice, len + 1)
+        } else if rest.starts_with("//") {
+            (TriviaKind::LineComment, line_comment_len(rest))
+        } else if let Some(body) = rest.strip_prefix("/*") {
+            let len = body.find("*/").map_or(rest.len(), |i| i + 4);
+            (TriviaKind::BlockComment, len)
+        } else {
+            let c = rest.chars().next().unwrap();
+            (TriviaKind::Whitespace, c.len_utf8())
+        };
+        match trivia.last_mut() {
+            Some(last) if last.kind == TriviaKind::Whitespace && kind == last.kind => {
+                last.text.push_str(&rest[..len]);
+            }
+            _ => trivia.push(Trivia {
+                kind,
+                text: rest[..len].to_owned(),
+            }),
+        }
+        rest = &rest[len..];
+    }
+    trivia
+}
+
+fn newline_len(text: &str) -> Option<usize> {
+    if text.starts_with("\r\n") {
+        Some(2)
+    } else if text.starts_with(['\n', '\r']) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+/// A line comment runs to the first line break that is not spliced away.
+fn line_comment_len(text: &str) -> usize {
+    let mut i = 0;
+    while i < text.len() {
+        let rest = &text[i..];
+        if let Some(len) = rest.strip_prefix('\\').and_then(newline_len) {
+            i += len + 1;
+        } else if newline_len(rest).is_some() {
+            return i;
+        } else {
+            i += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+    text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::syntax::tokenizer::{token::Token, tokenizer::Tokenizer};
+
+    use super::{Trivia, TriviaKind};
+
+    fn round_trip(input: &str) -> String {
+        Tokenizer::new(input)
+            .lossless()
+            .map(|token| token.to_string())
+            .collect()
+    }
+
+    fn trivia(kind: TriviaKind, text: &str) -> Trivia {
+        Trivia {
+            kind,
+            text: String::from(text),
+        }
+    }
+
+    #[test]
+    pub fn test_round_trip() {
+        let inputs = vec![
+            "",
+            "  \n",
+            "int a;",
+            "int a; // comment\r\n\t/* block\n comment */ return a \\\n+ 1;\n",
+            "\"str\\\ning\" 'x' @ /* unterminated",
+            "// comment \\\n  continued\nx",
+        ];
+        for input in inputs {
+            assert_eq!(round_trip(input), input);
+        }
+    }
+
+    #[test]
+    pub fn test_round_trip_examples() {
+        for entry in fs::read_dir("examples").unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+            assert_eq!(round_trip(&source), source, "{}", path.display());
+        }
+    }
+
+    #[test]
+    pub fn test_trivia() {
+        let tokens: Vec<_> = Tokenizer::new("/* a */ x; // b\n  y\n")
+            .lossless()
+            .collect();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(
+            tokens[0].leading,
+            vec![
+                trivia(TriviaKind::BlockComment, "/* a */"),
+                trivia(TriviaKind::Whitespace, " "),
+            ]
+        );
+        assert_eq!(tokens[0].text, "x");
+        assert!(tokens[0].trailing.is_empty());
+        assert_eq!(
+            tokens[1].trailing,
+            vec![
+                trivia(TriviaKind::Whitespace, " "),
+                trivia(TriviaKind::LineComment, "// b"),
+                trivia(TriviaKind::Newline, "\n"),
+            ]
+        );
+        assert!(tokens[1].ends_line());
+        assert_eq!(
+            tokens[2].leading,
+            vec![trivia(TriviaKind::Whitespace, "  ")]
+        );
+        assert_eq!(tokens[2].trailing, vec![trivia(TriviaKind::Newline, "\n")]);
+        assert_eq!(tokens[3].token, Token::EOF);
+        assert!(tokens[3].leading.is_empty());
+    }
+}
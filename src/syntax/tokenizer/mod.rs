@@ -1,6 +1,11 @@
 pub mod attr;
+pub mod concat;
 pub mod cursor;
+pub mod error;
+pub mod lossless;
+pub mod number;
 pub mod pos;
 pub mod range;
+pub mod span;
 pub mod token;
 pub mod tokenizer;
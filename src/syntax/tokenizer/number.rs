@@ -0,0 +1,328 @@
+use super::{attr::Attr, error::LexErrorKind, token::Token};
+
+/// Converts the text of a C preprocessing number into a literal token.
+///
+/// Integer literals get the first type of the C11 6.4.4.1 candidate list
+/// that can hold the value, with the LP64 sizes of x86-64: `int` is
+/// `Int32`, `long` and `long long` are `Int64`, and their unsigned
+/// counterparts are `UInt32`/`UInt64`. Floating literals are `Float` with
+/// an `f` suffix and `Float64` otherwise.
+///
+/// A malformed literal yields `Token::Illegal`; a literal that is out of
+/// range still yields a (saturated) token alongside the error.
+pub fn classify(text: &str, attr: Attr) -> (Token, Option<LexErrorKind>) {
+    let lower = text.to_ascii_lowercase();
+    let result = if lower.starts_with("0x") {
+        if lower.contains('.') || lower.contains('p') {
+            hex_float(&text[2..], attr.clone())
+        } else {
+            integer(&text[2..], 16, attr.clone())
+        }
+    } else if lower.starts_with("0b") {
+        integer(&text[2..], 2, attr.clone())
+    } else if lower.contains('.') || lower.contains('e') {
+        decimal_float(text, attr.clone())
+    } else if text.starts_with('0') {
+        integer(text, 8, attr.clone())
+    } else {
+        integer(text, 10, attr.clone())
+    };
+    match result {
+        Ok((token, warning)) => (token, warning),
+        Err(kind) => (Token::Illegal(attr), Some(kind)),
+    }
+}
+
+type Classified = Result<(Token, Option<LexErrorKind>), LexErrorKind>;
+
+fn integer(text: &str, radix: u32, attr: Attr) -> Classified {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || radix == 16 && c.is_ascii_hexdigit()))
+        .unwrap_or(text.len());
+    let (digits, suffix) = text.split_at(end);
+    if digits.is_empty() {
+        return Err(LexErrorKind::MissingDigits);
+    }
+    let (unsigned, long) = integer_suffix(suffix)?;
+
+    let mut value: u64 = 0;
+    let mut overflow = false;
+    for c in digits.chars() {
+        let digit = c.to_digit(radix).ok_or(LexErrorKind::InvalidDigit(c))?;
+        match value
+            .checked_mul(radix as u64)
+            .and_then(|v| v.checked_add(digit as u64))
+        {
+            Some(v) => value = v,
+            None => overflow = true,
+        }
+    }
+    if overflow {
+        return Ok((
+            Token::UInt64(attr, u64::MAX),
+            Some(LexErrorKind::IntegerTooLarge),
+        ));
+    }
+
+    // decimal literals without a `u` suffix never become unsigned
+    let decimal = radix == 10;
+    let token = if !long && !unsigned && value <= i32::MAX as u64 {
+        Token::Int32(attr, value as i32)
+    } else if !long && (unsigned || !decimal) && value <= u32::MAX as u64 {
+        Token::UInt32(attr, value as u32)
+    } else if !unsigned && value <= i64::MAX as u64 {
+        Token::Int64(attr, value as i64)
+    } else if unsigned || !decimal {
+        Token::UInt64(attr, value)
+    } else {
+        return Ok((
+            Token::UInt64(attr, value),
+            Some(LexErrorKind::IntegerTooLargeForSigned),
+        ));
+    };
+    Ok((token, None))
+}
+
+/// Returns whether the suffix makes the literal unsigned and whether it
+/// requests at least `long`.
+fn integer_suffix(suffix: &str) -> Result<(bool, bool), LexErrorKind> {
+    let (unsigned, rest) = match suffix.strip_prefix(['u', 'U']) {
+        Some(rest) => (true, rest),
+        None => (false, suffix),
+    };
+    let (long, rest) = match rest {
+        _ if rest.starts_with("ll") || rest.starts_with("LL") => (true, &rest[2..]),
+        _ if rest.starts_with('l') || rest.starts_with('L') => (true, &rest[1..]),
+        _ => (false, rest),
+    };
+    match (unsigned, rest) {
+        (_, "") => Ok((unsigned, long)),
+        (false, "u" | "U") if long => Ok((true, long)),
+        _ => Err(LexErrorKind::InvalidSuffix(suffix.to_owned())),
+    }
+}
+
+fn float_token(mantissa: &str, suffix: &str, value: Option<f64>, attr: Attr) -> Classified {
+    match suffix {
+        "f" | "F" => {
+            let v = match value {
+                Some(v) => v as f32,
+                None => mantissa.parse::<f32>().unwrap(),
+            };
+            if v.is_infinite() {
+                Ok((
+                    Token::Float(attr, f32::MAX),
+                    Some(LexErrorKind::FloatOutOfRange),
+                ))
+            } else {
+                Ok((Token::Float(attr, v), None))
+            }
+        }
+        "" | "l" | "L" => {
+            let v = match value {
+                Some(v) => v,
+                None => mantissa.parse::<f64>().unwrap(),
+            };
+            let token = match suffix {
+                "" => Token::Float64,
+                _ => Token::LongDouble,
+            };
+            if v.is_infinite() {
+                Ok((token(attr, f64::MAX), Some(LexErrorKind::FloatOutOfRange)))
+            } else {
+                Ok((token(attr, v), None))
+            }
+        }
+        _ => Err(LexErrorKind::InvalidSuffix(suffix.to_owned())),
+    }
+}
+
+fn decimal_float(text: &str, attr: Attr) -> Classified {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut digits = 0;
+    let mut seen_dot = false;
+    while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+        if bytes[i] == b'.' {
+            if seen_dot {
+                break;
+            }
+            seen_dot = true;
+        } else {
+            digits += 1;
+        }
+        i += 1;
+    }
+    if digits == 0 {
+        return Err(LexErrorKind::MissingDigits);
+    }
+    if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+        i += 1;
+        i += exponent_digits(&bytes[i..])?;
+    }
+    let (mantissa, suffix) = text.split_at(i);
+    float_token(mantissa, suffix, None, attr)
+}
+
+/// Returns the length of a signed exponent, which needs at least one digit.
+fn exponent_digits(bytes: &[u8]) -> Result<usize, LexErrorKind> {
+    let sign = matches!(bytes.first(), Some(b'+' | b'-')) as usize;
+    let digits = bytes[sign..]
+        .iter()
+        .take_while(|b| b.is_ascii_digit())
+        .count();
+    if digits == 0 {
+        return Err(LexErrorKind::MissingDigits);
+    }
+    Ok(sign + digits)
+}
+
+fn hex_float(text: &str, attr: Attr) -> Classified {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    let mut mantissa: u64 = 0;
+    let mut scale: i32 = 0;
+    let mut digits = 0;
+    let mut seen_dot = false;
+    while i < bytes.len() && (bytes[i].is_ascii_hexdigit() || bytes[i] == b'.') {
+        if bytes[i] == b'.' {
+            if seen_dot {
+                break;
+            }
+            seen_dot = true;
+        } else {
+            let digit = (bytes[i] as char).to_digit(16).unwrap() as u64;
+            digits += 1;
+            if mantissa >> 60 == 0 {
+                mantissa = mantissa * 16 + digit;
+                if seen_dot {
+                    scale -= 4;
+                }
+            } else if !seen_dot {
+                // drop digits beyond the precision of u64
+                scale += 4;
+            }
+        }
+        i += 1;
+    }
+    if digits == 0 {
+        return Err(LexErrorKind::MissingDigits);
+    }
+    if i == bytes.len() || (bytes[i] != b'p' && bytes[i] != b'P') {
+        return Err(LexErrorKind::MissingHexExponent);
+    }
+    i += 1;
+    let len = exponent_digits(&bytes[i..])?;
+    let exponent: i32 = match text[i..i + len].parse() {
+        Ok(exponent) => exponent,
+        Err(_) if bytes[i] == b'-' => i32::MIN,
+        Err(_) => i32::MAX,
+    };
+    let value = mantissa as f64 * 2f64.powi(exponent.saturating_add(scale));
+    float_token("", &text[i + len..], Some(value), attr)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::tokenizer::{attr::Attr, error::LexErrorKind, token::Token};
+
+    use super::classify;
+
+    fn token(text: &str) -> Token {
+        classify(text, Attr::new()).0
+    }
+
+    fn error(text: &str) -> Option<LexErrorKind> {
+        classify(text, Attr::new()).1
+    }
+
+    #[test]
+    pub fn test_integer_bases() {
+        assert_eq!(token("42"), Token::Int32(Attr::new(), 42));
+        assert_eq!(token("0x2a"), Token::Int32(Attr::new(), 42));
+        assert_eq!(token("0X2A"), Token::Int32(Attr::new(), 42));
+        assert_eq!(token("052"), Token::Int32(Attr::new(), 42));
+        assert_eq!(token("0b101010"), Token::Int32(Attr::new(), 42));
+        assert_eq!(token("0"), Token::Int32(Attr::new(), 0));
+    }
+
+    #[test]
+    pub fn test_integer_types() {
+        assert_eq!(token("2147483647"), Token::Int32(Attr::new(), i32::MAX));
+        assert_eq!(token("2147483648"), Token::Int64(Attr::new(), 2147483648));
+        assert_eq!(token("0x80000000"), Token::UInt32(Attr::new(), 0x80000000));
+        assert_eq!(
+            token("0xffffffffffffffff"),
+            Token::UInt64(Attr::new(), u64::MAX)
+        );
+        assert_eq!(token("1u"), Token::UInt32(Attr::new(), 1));
+        assert_eq!(token("1l"), Token::Int64(Attr::new(), 1));
+        assert_eq!(token("1LL"), Token::Int64(Attr::new(), 1));
+        assert_eq!(token("1ul"), Token::UInt64(Attr::new(), 1));
+        assert_eq!(token("1LU"), Token::UInt64(Attr::new(), 1));
+        assert_eq!(token("1ull"), Token::UInt64(Attr::new(), 1));
+        assert_eq!(token("1llu"), Token::UInt64(Attr::new(), 1));
+    }
+
+    #[test]
+    pub fn test_integer_errors() {
+        assert_eq!(error("08"), Some(LexErrorKind::InvalidDigit('8')));
+        assert_eq!(error("0b102"), Some(LexErrorKind::InvalidDigit('2')));
+        assert_eq!(error("0x"), Some(LexErrorKind::MissingDigits));
+        assert_eq!(
+            error("1lL"),
+            Some(LexErrorKind::InvalidSuffix(String::from("lL")))
+        );
+        assert_eq!(
+            error("12abc"),
+            Some(LexErrorKind::InvalidSuffix(String::from("abc")))
+        );
+        assert_eq!(token("12abc"), Token::Illegal(Attr::new()));
+        assert_eq!(
+            error("18446744073709551616"),
+            Some(LexErrorKind::IntegerTooLarge)
+        );
+        assert_eq!(
+            classify("9223372036854775808", Attr::new()),
+            (
+                Token::UInt64(Attr::new(), 9223372036854775808),
+                Some(LexErrorKind::IntegerTooLargeForSigned)
+            )
+        );
+        assert_eq!(error("9223372036854775808u"), None);
+    }
+
+    #[test]
+    pub fn test_floats() {
+        assert_eq!(token("1.5"), Token::Float64(Attr::new(), 1.5));
+        assert_eq!(token("1."), Token::Float64(Attr::new(), 1.0));
+        assert_eq!(token(".5"), Token::Float64(Attr::new(), 0.5));
+        assert_eq!(token("1e3"), Token::Float64(Attr::new(), 1000.0));
+        assert_eq!(token("2.5E-1"), Token::Float64(Attr::new(), 0.25));
+        assert_eq!(token("09.5"), Token::Float64(Attr::new(), 9.5));
+        assert_eq!(token("1.5f"), Token::Float(Attr::new(), 1.5));
+        assert_eq!(token("1.5L"), Token::LongDouble(Attr::new(), 1.5));
+        assert_eq!(token("2e1l"), Token::LongDouble(Attr::new(), 20.0));
+        assert_eq!(token("0x1.8p1"), Token::Float64(Attr::new(), 3.0));
+        assert_eq!(token("0x10p-2f"), Token::Float(Attr::new(), 4.0));
+        assert_eq!(token("0x.8P+0"), Token::Float64(Attr::new(), 0.5));
+    }
+
+    #[test]
+    pub fn test_float_errors() {
+        assert_eq!(error("1e"), Some(LexErrorKind::MissingDigits));
+        assert_eq!(error("1e+"), Some(LexErrorKind::MissingDigits));
+        assert_eq!(error("0x1.8"), Some(LexErrorKind::MissingHexExponent));
+        assert_eq!(
+            error("1.0u"),
+            Some(LexErrorKind::InvalidSuffix(String::from("u")))
+        );
+        assert_eq!(
+            error("1..5"),
+            Some(LexErrorKind::InvalidSuffix(String::from(".5")))
+        );
+        assert_eq!(error("1e999"), Some(LexErrorKind::FloatOutOfRange));
+        assert_eq!(error("1e39f"), Some(LexErrorKind::FloatOutOfRange));
+        assert_eq!(token("1e999"), Token::Float64(Attr::new(), f64::MAX));
+    }
+}
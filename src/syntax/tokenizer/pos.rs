@@ -1,15 +1,24 @@
+use std::fmt;
+
 #[derive(Debug, Default, PartialEq, Eq, Clone)]
 pub struct Pos {
     pub row: u32,
-    pub col: u8,
+    pub col: u32,
 }
 
 impl Pos {
-    pub fn new(row: u32, col: u8) -> Pos {
+    pub fn new(row: u32, col: u32) -> Pos {
         Pos { row, col }
     }
 }
 
+/// `row:col`, the way locations end in diagnostics.
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.row, self.col)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Pos;
@@ -26,4 +35,8 @@ mod tests {
         let p2 = Pos { row: 1, col: 1 };
         assert_eq!(p1, p2);
     }
+    #[test]
+    pub fn test_display() {
+        assert_eq!(Pos::new(3, 14).to_string(), "3:14");
+    }
 }
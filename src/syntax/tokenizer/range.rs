@@ -14,11 +14,19 @@ impl Range {
         }
     }
 
-    pub fn set_start(&mut self, row: u32, col: u8) {
+    pub fn start(&self) -> &Pos {
+        &self.start
+    }
+
+    pub fn end(&self) -> &Pos {
+        &self.end
+    }
+
+    pub fn set_start(&mut self, row: u32, col: u32) {
         self.start = Pos::new(row, col);
     }
 
-    pub fn set_end(&mut self, row: u32, col: u8) {
+    pub fn set_end(&mut self, row: u32, col: u32) {
         self.end = Pos::new(row, col);
     }
 }
@@ -0,0 +1,44 @@
+/// A half-open range of byte offsets into a source file.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    pub lo: usize,
+    pub hi: usize,
+}
+
+impl Span {
+    pub fn new(lo: usize, hi: usize) -> Span {
+        Span { lo, hi }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hi - self.lo
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lo == self.hi
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        self.lo <= offset && offset < self.hi
+    }
+
+    /// The smallest span covering both spans.
+    pub fn to(&self, other: Span) -> Span {
+        Span::new(self.lo.min(other.lo), self.hi.max(other.hi))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Span;
+
+    #[test]
+    pub fn test_span() {
+        let span = Span::new(2, 5);
+        assert_eq!(span.len(), 3);
+        assert!(span.contains(2));
+        assert!(!span.contains(5));
+        assert_eq!(span.to(Span::new(7, 9)), Span::new(2, 9));
+        assert!(Span::default().is_empty());
+    }
+}
@@ -1,63 +1,580 @@
+use std::fmt;
+
 use super::attr::Attr;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub enum Token {
-    Illegal,
+    // a malformed or unknown lexeme, reported in `Tokenizer::errors`
+    Illegal(Attr),
 
-    // 'a'
-    Char(Attr, char),
+    // 'a', L'a', u'a', U'a', u8'a'
+    Char(Attr, Encoding, char),
 
     // true, false
     Bool(Attr, bool),
 
-    // 3
+    // 3, 0x3, 03, 0b11, 3u, 3l, 3ull
     Int8(Attr, i8),
     Int16(Attr, i16),
     Int32(Attr, i32),
     Int64(Attr, i64),
+    UInt32(Attr, u32),
+    UInt64(Attr, u64),
 
-    // 3.1
+    // 3.1f, 3.1, 3.1l, 0x1.8p1; long double is lexed with double precision
     Float(Attr, f32),
     Float64(Attr, f64),
+    LongDouble(Attr, f64),
 
-    // "abc"
-    String(Attr, String),
+    // "abc", L"abc", u"abc", U"abc", u8"abc"
+    String(Attr, Encoding, String),
 
     // a
     ID(Attr, String),
 
+    // digraphs `<:`, `:>`, `<%` and `%>` lex to the same tokens
     LParen(Attr),       // (
     RParen(Attr),       // )
-    LBrace(Attr),       // {
-    RBrace(Attr),       // }
-    LBraket(Attr),      // [
-    RBraket(Attr),      // ]
+    LBrace(Attr),       // { <%
+    RBrace(Attr),       // } %>
+    LBraket(Attr),      // [ <:
+    RBraket(Attr),      // ] :>
     QuestionMark(Attr), // ?
+    Colon(Attr),        // :
+    Semicolon(Attr),    // ;
+    Comma(Attr),        // ,
+    Ellipsis(Attr),     // ...
 
-    Hash(Attr),  // #
-    Dot(Attr),   // .
-    Arrow(Attr), // ->
+    Hash(Attr),     // # %:
+    HashHash(Attr), // ## %:%:
+    Dot(Attr),      // .
+    Arrow(Attr),    // ->
 
     Incr(Attr),   // ++
+    Decr(Attr),   // --
     Plus(Attr),   // +
     Minus(Attr),  // -
     Mul(Attr),    // *
     Div(Attr),    // /
+    Mod(Attr),    // %
     Lt(Attr),     // <
     Gt(Attr),     // >
     Lte(Attr),    // <=
     LShift(Attr), // <<
     Gte(Attr),    // >=
     RShift(Attr), // >>
+    Eq(Attr),     // ==
+    Neq(Attr),    // !=
     Not(Attr),    // !
+    And(Attr),    // &&
+    Or(Attr),     // ||
+    BitAnd(Attr), // &
+    BitOr(Attr),  // |
+    BitXor(Attr), // ^
+    BitNot(Attr), // ~
+
+    Assign(Attr),       // =
+    PlusAssign(Attr),   // +=
+    MinusAssign(Attr),  // -=
+    MulAssign(Attr),    // *=
+    DivAssign(Attr),    // /=
+    ModAssign(Attr),    // %=
+    LShiftAssign(Attr), // <<=
+    RShiftAssign(Attr), // >>=
+    BitAndAssign(Attr), // &=
+    BitOrAssign(Attr),  // |=
+    BitXorAssign(Attr), // ^=
 
-    Typedef(Attr), // typedef
-    Struct(Attr),  // struct
-    Enum(Attr),    // enum
-    If(Attr),      // if
-    Else(Attr),    // else
-    While(Attr),   // while
-    Return(Attr),  // return
+    // `char`, `float` and `_Bool` are suffixed to keep them apart from the
+    // literals above
+    Auto(Attr),         // auto
+    Break(Attr),        // break
+    Case(Attr),         // case
+    CharKw(Attr),       // char
+    Const(Attr),        // const
+    Continue(Attr),     // continue
+    Default(Attr),      // default
+    Do(Attr),           // do
+    Double(Attr),       // double
+    Else(Attr),         // else
+    Enum(Attr),         // enum
+    Extern(Attr),       // extern
+    FloatKw(Attr),      // float
+    For(Attr),          // for
+    Goto(Attr),         // goto
+    If(Attr),           // if
+    Inline(Attr),       // inline
+    Int(Attr),          // int
+    Long(Attr),         // long
+    Register(Attr),     // register
+    Restrict(Attr),     // restrict
+    Return(Attr),       // return
+    Short(Attr),        // short
+    Signed(Attr),       // signed
+    Sizeof(Attr),       // sizeof
+    Static(Attr),       // static
+    Struct(Attr),       // struct
+    Switch(Attr),       // switch
+    Typedef(Attr),      // typedef
+    Union(Attr),        // union
+    Unsigned(Attr),     // unsigned
+    Void(Attr),         // void
+    Volatile(Attr),     // volatile
+    While(Attr),        // while
+    Alignas(Attr),      // _Alignas
+    Alignof(Attr),      // _Alignof
+    Atomic(Attr),       // _Atomic
+    BoolKw(Attr),       // _Bool
+    Complex(Attr),      // _Complex
+    Generic(Attr),      // _Generic
+    Imaginary(Attr),    // _Imaginary
+    Noreturn(Attr),     // _Noreturn
+    StaticAssert(Attr), // _Static_assert
+    ThreadLocal(Attr),  // _Thread_local
 
     EOF,
 }
+
+impl Token {
+    /// The attr of the lexeme, which every token but `EOF` has.
+    pub fn attr(&self) -> Option<&Attr> {
+        match self {
+            Token::Char(attr, ..)
+            | Token::Bool(attr, ..)
+            | Token::Int8(attr, ..)
+            | Token::Int16(attr, ..)
+            | Token::Int32(attr, ..)
+            | Token::Int64(attr, ..)
+            | Token::UInt32(attr, ..)
+            | Token::UInt64(attr, ..)
+            | Token::Float(attr, ..)
+            | Token::Float64(attr, ..)
+            | Token::LongDouble(attr, ..)
+            | Token::String(attr, ..)
+            | Token::ID(attr, ..)
+            | Token::Illegal(attr)
+            | Token::LParen(attr)
+            | Token::RParen(attr)
+            | Token::LBrace(attr)
+            | Token::RBrace(attr)
+            | Token::LBraket(attr)
+            | Token::RBraket(attr)
+            | Token::QuestionMark(attr)
+            | Token::Colon(attr)
+            | Token::Semicolon(attr)
+            | Token::Comma(attr)
+            | Token::Ellipsis(attr)
+            | Token::Hash(attr)
+            | Token::HashHash(attr)
+            | Token::Dot(attr)
+            | Token::Arrow(attr)
+            | Token::Incr(attr)
+            | Token::Decr(attr)
+            | Token::Plus(attr)
+            | Token::Minus(attr)
+            | Token::Mul(attr)
+            | Token::Div(attr)
+            | Token::Mod(attr)
+            | Token::Lt(attr)
+            | Token::Gt(attr)
+            | Token::Lte(attr)
+            | Token::LShift(attr)
+            | Token::Gte(attr)
+            | Token::RShift(attr)
+            | Token::Eq(attr)
+            | Token::Neq(attr)
+            | Token::Not(attr)
+            | Token::And(attr)
+            | Token::Or(attr)
+            | Token::BitAnd(attr)
+            | Token::BitOr(attr)
+            | Token::BitXor(attr)
+            | Token::BitNot(attr)
+            | Token::Assign(attr)
+            | Token::PlusAssign(attr)
+            | Token::MinusAssign(attr)
+            | Token::MulAssign(attr)
+            | Token::DivAssign(attr)
+            | Token::ModAssign(attr)
+            | Token::LShiftAssign(attr)
+            | Token::RShiftAssign(attr)
+            | Token::BitAndAssign(attr)
+            | Token::BitOrAssign(attr)
+            | Token::BitXorAssign(attr)
+            | Token::Auto(attr)
+            | Token::Break(attr)
+            | Token::Case(attr)
+            | Token::CharKw(attr)
+            | Token::Const(attr)
+            | Token::Continue(attr)
+            | Token::Default(attr)
+            | Token::Do(attr)
+            | Token::Double(attr)
+            | Token::Else(attr)
+            | Token::Enum(attr)
+            | Token::Extern(attr)
+            | Token::FloatKw(attr)
+            | Token::For(attr)
+            | Token::Goto(attr)
+            | Token::If(attr)
+            | Token::Inline(attr)
+            | Token::Int(attr)
+            | Token::Long(attr)
+            | Token::Register(attr)
+            | Token::Restrict(attr)
+            | Token::Return(attr)
+            | Token::Short(attr)
+            | Token::Signed(attr)
+            | Token::Sizeof(attr)
+            | Token::Static(attr)
+            | Token::Struct(attr)
+            | Token::Switch(attr)
+            | Token::Typedef(attr)
+            | Token::Union(attr)
+            | Token::Unsigned(attr)
+            | Token::Void(attr)
+            | Token::Volatile(attr)
+            | Token::While(attr)
+            | Token::Alignas(attr)
+            | Token::Alignof(attr)
+            | Token::Atomic(attr)
+            | Token::BoolKw(attr)
+            | Token::Complex(attr)
+            | Token::Generic(attr)
+            | Token::Imaginary(attr)
+            | Token::Noreturn(attr)
+            | Token::StaticAssert(attr)
+            | Token::ThreadLocal(attr) => Some(attr),
+            Token::EOF => None,
+        }
+    }
+
+    pub fn attr_mut(&mut self) -> Option<&mut Attr> {
+        match self {
+            Token::Char(attr, ..)
+            | Token::Bool(attr, ..)
+            | Token::Int8(attr, ..)
+            | Token::Int16(attr, ..)
+            | Token::Int32(attr, ..)
+            | Token::Int64(attr, ..)
+            | Token::UInt32(attr, ..)
+            | Token::UInt64(attr, ..)
+            | Token::Float(attr, ..)
+            | Token::Float64(attr, ..)
+            | Token::LongDouble(attr, ..)
+            | Token::String(attr, ..)
+            | Token::ID(attr, ..)
+            | Token::Illegal(attr)
+            | Token::LParen(attr)
+            | Token::RParen(attr)
+            | Token::LBrace(attr)
+            | Token::RBrace(attr)
+            | Token::LBraket(attr)
+            | Token::RBraket(attr)
+            | Token::QuestionMark(attr)
+            | Token::Colon(attr)
+            | Token::Semicolon(attr)
+            | Token::Comma(attr)
+            | Token::Ellipsis(attr)
+            | Token::Hash(attr)
+            | Token::HashHash(attr)
+            | Token::Dot(attr)
+            | Token::Arrow(attr)
+            | Token::Incr(attr)
+            | Token::Decr(attr)
+            | Token::Plus(attr)
+            | Token::Minus(attr)
+            | Token::Mul(attr)
+            | Token::Div(attr)
+            | Token::Mod(attr)
+            | Token::Lt(attr)
+            | Token::Gt(attr)
+            | Token::Lte(attr)
+            | Token::LShift(attr)
+            | Token::Gte(attr)
+            | Token::RShift(attr)
+            | Token::Eq(attr)
+            | Token::Neq(attr)
+            | Token::Not(attr)
+            | Token::And(attr)
+            | Token::Or(attr)
+            | Token::BitAnd(attr)
+            | Token::BitOr(attr)
+            | Token::BitXor(attr)
+            | Token::BitNot(attr)
+            | Token::Assign(attr)
+            | Token::PlusAssign(attr)
+            | Token::MinusAssign(attr)
+            | Token::MulAssign(attr)
+            | Token::DivAssign(attr)
+            | Token::ModAssign(attr)
+            | Token::LShiftAssign(attr)
+            | Token::RShiftAssign(attr)
+            | Token::BitAndAssign(attr)
+            | Token::BitOrAssign(attr)
+            | Token::BitXorAssign(attr)
+            | Token::Auto(attr)
+            | Token::Break(attr)
+            | Token::Case(attr)
+            | Token::CharKw(attr)
+            | Token::Const(attr)
+            | Token::Continue(attr)
+            | Token::Default(attr)
+            | Token::Do(attr)
+            | Token::Double(attr)
+            | Token::Else(attr)
+            | Token::Enum(attr)
+            | Token::Extern(attr)
+            | Token::FloatKw(attr)
+            | Token::For(attr)
+            | Token::Goto(attr)
+            | Token::If(attr)
+            | Token::Inline(attr)
+            | Token::Int(attr)
+            | Token::Long(attr)
+            | Token::Register(attr)
+            | Token::Restrict(attr)
+            | Token::Return(attr)
+            | Token::Short(attr)
+            | Token::Signed(attr)
+            | Token::Sizeof(attr)
+            | Token::Static(attr)
+            | Token::Struct(attr)
+            | Token::Switch(attr)
+            | Token::Typedef(attr)
+            | Token::Union(attr)
+            | Token::Unsigned(attr)
+            | Token::Void(attr)
+            | Token::Volatile(attr)
+            | Token::While(attr)
+            | Token::Alignas(attr)
+            | Token::Alignof(attr)
+            | Token::Atomic(attr)
+            | Token::BoolKw(attr)
+            | Token::Complex(attr)
+            | Token::Generic(attr)
+            | Token::Imaginary(attr)
+            | Token::Noreturn(attr)
+            | Token::StaticAssert(attr)
+            | Token::ThreadLocal(attr) => Some(attr),
+            Token::EOF => None,
+        }
+    }
+}
+
+/// Spells the token as it could appear in source, for messages.
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let punct = match self {
+            Token::Illegal(_) => "<illegal>",
+            Token::Char(_, encoding, c) => {
+                return write!(f, "{}'{}'", encoding.prefix(), c.escape_default())
+            }
+            Token::Bool(_, b) => return write!(f, "{}", b),
+            Token::Int8(_, v) => return write!(f, "{}", v),
+            Token::Int16(_, v) => return write!(f, "{}", v),
+            Token::Int32(_, v) => return write!(f, "{}", v),
+            Token::Int64(_, v) => return write!(f, "{}", v),
+            Token::UInt32(_, v) => return write!(f, "{}", v),
+            Token::UInt64(_, v) => return write!(f, "{}", v),
+            Token::Float(_, v) => return write!(f, "{:?}f", v),
+            Token::Float64(_, v) => return write!(f, "{:?}", v),
+            Token::LongDouble(_, v) => return write!(f, "{:?}L", v),
+            Token::String(_, encoding, s) => {
+                return write!(f, "{}\"{}\"", encoding.prefix(), s.escape_default())
+            }
+            Token::ID(_, name) => name,
+            Token::LParen(_) => "(",
+            Token::RParen(_) => ")",
+            Token::LBrace(_) => "{",
+            Token::RBrace(_) => "}",
+            Token::LBraket(_) => "[",
+            Token::RBraket(_) => "]",
+            Token::QuestionMark(_) => "?",
+            Token::Colon(_) => ":",
+            Token::Semicolon(_) => ";",
+            Token::Comma(_) => ",",
+            Token::Ellipsis(_) => "...",
+            Token::Hash(_) => "#",
+            Token::HashHash(_) => "##",
+            Token::Dot(_) => ".",
+            Token::Arrow(_) => "->",
+            Token::Incr(_) => "++",
+            Token::Decr(_) => "--",
+            Token::Plus(_) => "+",
+            Token::Minus(_) => "-",
+            Token::Mul(_) => "*",
+            Token::Div(_) => "/",
+            Token::Mod(_) => "%",
+            Token::Lt(_) => "<",
+            Token::Gt(_) => ">",
+            Token::Lte(_) => "<=",
+            Token::LShift(_) => "<<",
+            Token::Gte(_) => ">=",
+            Token::RShift(_) => ">>",
+            Token::Eq(_) => "==",
+            Token::Neq(_) => "!=",
+            Token::Not(_) => "!",
+            Token::And(_) => "&&",
+            Token::Or(_) => "||",
+            Token::BitAnd(_) => "&",
+            Token::BitOr(_) => "|",
+            Token::BitXor(_) => "^",
+            Token::BitNot(_) => "~",
+            Token::Assign(_) => "=",
+            Token::PlusAssign(_) => "+=",
+            Token::MinusAssign(_) => "-=",
+            Token::MulAssign(_) => "*=",
+            Token::DivAssign(_) => "/=",
+            Token::ModAssign(_) => "%=",
+            Token::LShiftAssign(_) => "<<=",
+            Token::RShiftAssign(_) => ">>=",
+            Token::BitAndAssign(_) => "&=",
+            Token::BitOrAssign(_) => "|=",
+            Token::BitXorAssign(_) => "^=",
+            Token::Auto(_) => "auto",
+            Token::Break(_) => "break",
+            Token::Case(_) => "case",
+            Token::CharKw(_) => "char",
+            Token::Const(_) => "const",
+            Token::Continue(_) => "continue",
+            Token::Default(_) => "default",
+            Token::Do(_) => "do",
+            Token::Double(_) => "double",
+            Token::Else(_) => "else",
+            Token::Enum(_) => "enum",
+            Token::Extern(_) => "extern",
+            Token::FloatKw(_) => "float",
+            Token::For(_) => "for",
+            Token::Goto(_) => "goto",
+            Token::If(_) => "if",
+            Token::Inline(_) => "inline",
+            Token::Int(_) => "int",
+            Token::Long(_) => "long",
+            Token::Register(_) => "register",
+            Token::Restrict(_) => "restrict",
+            Token::Return(_) => "return",
+            Token::Short(_) => "short",
+            Token::Signed(_) => "signed",
+            Token::Sizeof(_) => "sizeof",
+            Token::Static(_) => "static",
+            Token::Struct(_) => "struct",
+            Token::Switch(_) => "switch",
+            Token::Typedef(_) => "typedef",
+            Token::Union(_) => "union",
+            Token::Unsigned(_) => "unsigned",
+            Token::Void(_) => "void",
+            Token::Volatile(_) => "volatile",
+            Token::While(_) => "while",
+            Token::Alignas(_) => "_Alignas",
+            Token::Alignof(_) => "_Alignof",
+            Token::Atomic(_) => "_Atomic",
+            Token::BoolKw(_) => "_Bool",
+            Token::Complex(_) => "_Complex",
+            Token::Generic(_) => "_Generic",
+            Token::Imaginary(_) => "_Imaginary",
+            Token::Noreturn(_) => "_Noreturn",
+            Token::StaticAssert(_) => "_Static_assert",
+            Token::ThreadLocal(_) => "_Thread_local",
+            Token::EOF => "end of file",
+        };
+        write!(f, "{}", punct)
+    }
+}
+
+/// Returns the constructor of the token for the C11 keyword `s`.
+pub fn keyword(s: &str) -> Option<fn(Attr) -> Token> {
+    let token: fn(Attr) -> Token = match s {
+        "auto" => Token::Auto,
+        "break" => Token::Break,
+        "case" => Token::Case,
+        "char" => Token::CharKw,
+        "const" => Token::Const,
+        "continue" => Token::Continue,
+        "default" => Token::Default,
+        "do" => Token::Do,
+        "double" => Token::Double,
+        "else" => Token::Else,
+        "enum" => Token::Enum,
+        "extern" => Token::Extern,
+        "float" => Token::FloatKw,
+        "for" => Token::For,
+        "goto" => Token::Goto,
+        "if" => Token::If,
+        "inline" => Token::Inline,
+        "int" => Token::Int,
+        "long" => Token::Long,
+        "register" => Token::Register,
+        "restrict" => Token::Restrict,
+        "return" => Token::Return,
+        "short" => Token::Short,
+        "signed" => Token::Signed,
+        "sizeof" => Token::Sizeof,
+        "static" => Token::Static,
+        "struct" => Token::Struct,
+        "switch" => Token::Switch,
+        "typedef" => Token::Typedef,
+        "union" => Token::Union,
+        "unsigned" => Token::Unsigned,
+        "void" => Token::Void,
+        "volatile" => Token::Volatile,
+        "while" => Token::While,
+        "_Alignas" => Token::Alignas,
+        "_Alignof" => Token::Alignof,
+        "_Atomic" => Token::Atomic,
+        "_Bool" => Token::BoolKw,
+        "_Complex" => Token::Complex,
+        "_Generic" => Token::Generic,
+        "_Imaginary" => Token::Imaginary,
+        "_Noreturn" => Token::Noreturn,
+        "_Static_assert" => Token::StaticAssert,
+        "_Thread_local" => Token::ThreadLocal,
+        _ => return None,
+    };
+    Some(token)
+}
+
+/// The encoding prefix of a char constant or string literal.
+///
+/// Literal contents are stored as Rust chars: numeric escapes become the
+/// char with the same code unit value (so `"\xff"` holds `'\u{ff}'`), and
+/// universal character names become the named code point.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Encoding {
+    Plain, // 'a'
+    Utf8,  // u8'a'
+    Utf16, // u'a'
+    Utf32, // U'a'
+    Wide,  // L'a'
+}
+
+impl Encoding {
+    pub fn from_prefix(prefix: &str) -> Option<Encoding> {
+        match prefix {
+            "u8" => Some(Encoding::Utf8),
+            "u" => Some(Encoding::Utf16),
+            "U" => Some(Encoding::Utf32),
+            "L" => Some(Encoding::Wide),
+            _ => None,
+        }
+    }
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Encoding::Plain => "",
+            Encoding::Utf8 => "u8",
+            Encoding::Utf16 => "u",
+            Encoding::Utf32 => "U",
+            Encoding::Wide => "L",
+        }
+    }
+
+    /// The largest value of a single code unit, `wchar_t` being 32 bits.
+    pub fn max_code_unit(&self) -> u32 {
+        match self {
+            Encoding::Plain | Encoding::Utf8 => 0xff,
+            Encoding::Utf16 => 0xffff,
+            Encoding::Utf32 | Encoding::Wide => 0x10ffff,
+        }
+    }
+}
@@ -1,284 +1,534 @@
-use core::panic;
+use crate::syntax::source::source_file::FileId;
 
-use super::{attr::Attr, cursor::Cursor, token::Token};
+use super::{
+    attr::Attr,
+    cursor::Cursor,
+    error::{LexError, LexErrorKind},
+    lossless::Lossless,
+    number,
+    token::{keyword, Encoding, Token},
+};
 
 pub struct Tokenizer<'a> {
-    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    input: &'a str,
+    chars: Chars<'a>,
     cursor: Cursor,
     attr: Attr,
+    errors: Vec<LexError>,
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(input: &'a str) -> Self {
-        let tokenizer = Tokenizer {
+        Tokenizer::with_file(input, FileId::default())
+    }
+
+    /// Creates a tokenizer whose attrs point into the source map file `file`.
+    pub fn with_file(input: &'a str, file: FileId) -> Self {
+        Tokenizer {
+            input,
             chars: input.chars().peekable(),
             cursor: Cursor::new(),
-            attr: Attr::new(),
-        };
-        tokenizer
+            attr: Attr::new().with_file(file),
+            errors: Vec::new(),
+        }
     }
 
+    /// Switches to lossless mode, where tokens keep their spelling and the
+    /// whitespace and comments around them.
+    pub fn lossless(self) -> Lossless<'a> {
+        Lossless::new(self.input, self)
+    }
+
+    /// Consumes the next char, first splicing away any backslash-newline
+    /// (translation phase 2). The cursor still counts the physical lines
+    /// and columns of the source.
     fn advance(&mut self) -> Option<char> {
+        self.skip_splices();
+        self.advance_raw()
+    }
+
+    fn advance_raw(&mut self) -> Option<char> {
         let c = self.chars.next();
-        if c.is_some() {
-            self.cursor.incr_col();
+        match c {
+            // `\r\n` is one line break, counted at the `\n`
+            Some('\r') if self.chars.peek() == Some(&'\n') => self.cursor.incr_col('\r'),
+            Some(c1) if self.is_newline(c1) => self.cursor.incr_row(c1),
+            Some(c1) => self.cursor.incr_col(c1),
+            None => {}
         }
         c
     }
 
+    fn skip_splices(&mut self) {
+        while let Some(len) = splice_len(&self.chars) {
+            for _ in 0..len {
+                self.advance_raw();
+            }
+        }
+    }
+
     fn peek(&mut self) -> Option<&char> {
-        let c = self.chars.peek();
-        c
+        self.skip_splices();
+        self.chars.peek()
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        let mut chars = self.chars.clone();
+        for _ in 0..n {
+            skip_raw_splices(&mut chars);
+            chars.next();
+        }
+        skip_raw_splices(&mut chars);
+        chars.next()
+    }
+
+    /// Consumes the next char if it is `expected`.
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(&expected) {
+            self.advance();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Finishes the current token, whose start was marked when its first
+    /// char was consumed.
+    fn make(&mut self, token: fn(Attr) -> Token) -> Token {
+        self.mark_end();
+        token(self.attr.clone())
     }
 
-    fn is_keyword(&self, s: &str) -> bool {
-        match s {
-            "typedef" | "struct" | "enum" | "if" | "else" | "while" | "return" => true,
-            _ => false,
+    /// Lexes `op`, or `op_eq` when the operator is followed by `=`.
+    fn or_eq(&mut self, op: fn(Attr) -> Token, op_eq: fn(Attr) -> Token) -> Token {
+        if self.eat('=') {
+            self.make(op_eq)
+        } else {
+            self.make(op)
+        }
+    }
+
+    /// Skips the comment starting at the current `/`, which has already
+    /// been consumed.
+    fn skip_comment(&mut self) {
+        if self.eat('/') {
+            while let Some(&c) = self.peek() {
+                if self.is_newline(c) {
+                    break;
+                }
+                self.advance();
+            }
+            return;
+        }
+        self.advance();
+        let mut star = false;
+        loop {
+            match self.advance() {
+                Some('/') if star => return,
+                Some(c) => star = c == '*',
+                None => {
+                    self.mark_end();
+                    let error = LexError::new(self.attr.clone(), LexErrorKind::UnterminatedComment);
+                    self.errors.push(error);
+                    return;
+                }
+            }
         }
     }
 
+    /// Identifiers are ASCII letters, digits and `_`; other Unicode letters
+    /// and digits are accepted as an extension.
+    fn is_id_start(&self, c: char) -> bool {
+        c.is_ascii_alphabetic() || c == '_' || !c.is_ascii() && c.is_alphabetic()
+    }
+
+    fn is_id_continue(&self, c: char) -> bool {
+        self.is_id_start(c) || c.is_ascii_digit() || !c.is_ascii() && c.is_alphanumeric()
+    }
+
     fn mark_start(&mut self) {
         self.attr.set_start_pos(self.cursor.cur_pos());
+        self.attr.set_start_offset(self.cursor.cur_offset());
     }
 
     fn mark_end(&mut self) {
         self.attr.set_end_pos(self.cursor.cur_pos());
+        self.attr.set_end_offset(self.cursor.next_offset());
     }
 
-    fn read_number(&mut self, c: char) -> i64 {
-        let mut v = c.to_digit(10).unwrap();
-        while let Some(&c) = self.chars.peek() {
-            if c.is_digit(10) {
-                self.chars.next();
-                let digit = c.to_digit(10).unwrap() as u32;
-                v = v * 10 + digit;
-            } else {
+    /// Reads a C preprocessing number: digits, letters, `_`, `.` and the
+    /// signs of `e+`/`p-` style exponents.
+    fn read_number(&mut self, c: char) -> String {
+        let mut s = String::new();
+        s.push(c);
+        while let Some(&c1) = self.peek() {
+            let is_exponent = matches!(s.chars().last(), Some('e' | 'E' | 'p' | 'P'));
+            let is_sign = c1 == '+' || c1 == '-';
+            if !(c1.is_ascii_alphanumeric() || c1 == '_' || c1 == '.' || is_exponent && is_sign) {
                 break;
             }
+            s.push(c1);
+            self.advance();
         }
-        v as i64
+        s
     }
 
     fn parse_number(&mut self, c: char) -> Token {
-        self.mark_start();
-        let v = self.read_number(c);
+        let s = self.read_number(c);
         self.mark_end();
-        Token::Int64(self.attr.clone(), v)
+        let (token, error) = number::classify(&s, self.attr.clone());
+        if let Some(kind) = error {
+            self.errors.push(LexError::new(self.attr.clone(), kind));
+        }
+        token
     }
 
-    fn is_newline(&mut self, c: char) -> bool {
-        match c {
-            '\n' => true,
-            '\r' => true,
-            _ => false,
+    pub fn errors(&self) -> &[LexError] {
+        &self.errors
+    }
+
+    /// Reads the chars of a char constant or string literal up to the
+    /// closing `quote`, decoding escape sequences. Returns `None` when the
+    /// line or input ends first, leaving the newline unconsumed.
+    fn read_quoted(&mut self, quote: char, encoding: Encoding) -> Option<Vec<char>> {
+        let mut chars = Vec::new();
+        loop {
+            let c = match self.peek().copied() {
+                Some(c) if !self.is_newline(c) => c,
+                _ => return None,
+            };
+            self.advance();
+            if c == quote {
+                return Some(chars);
+            } else if c == '\\' {
+                if let Some(c1) = self.read_escape(encoding) {
+                    chars.push(c1);
+                }
+            } else {
+                chars.push(c);
+            }
         }
     }
+
+    /// Reports an unterminated char constant or string literal.
+    fn unterminated(&mut self, kind: LexErrorKind) -> Token {
+        self.mark_end();
+        self.errors.push(LexError::new(self.attr.clone(), kind));
+        Token::Illegal(self.attr.clone())
+    }
+
+    /// Decodes the escape sequence following a `\\`. Escapes whose value
+    /// does not fit the literal's encoding are reported and dropped.
+    fn read_escape(&mut self, encoding: Encoding) -> Option<char> {
+        let mut attr = self.attr.clone();
+        attr.set_start_pos(self.cursor.cur_pos());
+        attr.set_start_offset(self.cursor.cur_offset());
+        // a `\\` at the end of the input leaves the literal unterminated
+        let c = match self.peek().copied() {
+            Some(c) if !self.is_newline(c) => c,
+            _ => return None,
+        };
+        self.advance();
+        let value = match c {
+            '\'' | '"' | '?' | '\\' => Ok(c as u32),
+            'a' => Ok(0x07),
+            'b' => Ok(0x08),
+            'f' => Ok(0x0c),
+            'n' => Ok(0x0a),
+            'r' => Ok(0x0d),
+            't' => Ok(0x09),
+            'v' => Ok(0x0b),
+            '0'..='7' => {
+                let mut v = c.to_digit(8).unwrap();
+                for _ in 0..2 {
+                    match self.peek().and_then(|c1| c1.to_digit(8)) {
+                        Some(digit) => {
+                            self.advance();
+                            v = v * 8 + digit;
+                        }
+                        None => break,
+                    }
+                }
+                Ok(v)
+            }
+            'x' => {
+                let mut v: u32 = 0;
+                let mut digits = 0;
+                let mut overflow = false;
+                while let Some(digit) = self.peek().and_then(|c1| c1.to_digit(16)) {
+                    self.advance();
+                    digits += 1;
+                    match v.checked_mul(16) {
+                        Some(v1) => v = v1 + digit,
+                        None => overflow = true,
+                    }
+                }
+                match (digits, overflow) {
+                    (0, _) => Err(LexErrorKind::MissingHexEscapeDigits),
+                    (_, true) => Err(LexErrorKind::EscapeOutOfRange),
+                    _ => Ok(v),
+                }
+            }
+            'u' | 'U' => {
+                let len = if c == 'u' { 4 } else { 8 };
+                let mut v: u32 = 0;
+                let mut digits = 0;
+                while digits < len {
+                    match self.peek().and_then(|c1| c1.to_digit(16)) {
+                        Some(digit) => {
+                            self.advance();
+                            v = v * 16 + digit;
+                            digits += 1;
+                        }
+                        None => break,
+                    }
+                }
+                // C11 6.4.3: no surrogates, and nothing from the basic
+                // character set other than `$`, `@` and `` ` ``
+                let basic = v < 0xa0 && v != 0x24 && v != 0x40 && v != 0x60;
+                if digits < len {
+                    Err(LexErrorKind::IncompleteUniversalChar)
+                } else if basic || char::from_u32(v).is_none() {
+                    Err(LexErrorKind::InvalidUniversalChar(v))
+                } else {
+                    Ok(v)
+                }
+            }
+            _ => Err(LexErrorKind::UnknownEscape(c)),
+        };
+        attr.set_end_pos(self.cursor.cur_pos());
+        attr.set_end_offset(self.cursor.next_offset());
+        let result = value.and_then(|v| {
+            // universal character names in narrow literals are stored as
+            // code points, numeric escapes as code units
+            let is_ucn = c == 'u' || c == 'U';
+            if v > encoding.max_code_unit() && !is_ucn {
+                return Err(LexErrorKind::EscapeOutOfRange);
+            }
+            char::from_u32(v).ok_or(LexErrorKind::EscapeOutOfRange)
+        });
+        match result {
+            Ok(c) => Some(c),
+            Err(kind) => {
+                // an unknown escape still stands for the escaped char
+                let recovered = match kind {
+                    LexErrorKind::UnknownEscape(c) => Some(c),
+                    _ => None,
+                };
+                self.errors.push(LexError::new(attr, kind));
+                recovered
+            }
+        }
+    }
+
+    fn parse_char(&mut self, encoding: Encoding) -> Token {
+        let chars = match self.read_quoted('\'', encoding) {
+            Some(chars) => chars,
+            None => return self.unterminated(LexErrorKind::UnterminatedChar),
+        };
+        self.mark_end();
+        let c = match chars.len() {
+            0 => {
+                let error = LexError::new(self.attr.clone(), LexErrorKind::EmptyCharConstant);
+                self.errors.push(error);
+                return Token::Illegal(self.attr.clone());
+            }
+            1 => chars[0],
+            _ => {
+                // like gcc, a multi-char constant converted to `char` keeps
+                // its last char
+                let error = LexError::new(self.attr.clone(), LexErrorKind::MultiCharConstant);
+                self.errors.push(error);
+                chars[chars.len() - 1]
+            }
+        };
+        Token::Char(self.attr.clone(), encoding, c)
+    }
+
+    fn parse_string(&mut self, encoding: Encoding) -> Token {
+        let chars = match self.read_quoted('"', encoding) {
+            Some(chars) => chars,
+            None => return self.unterminated(LexErrorKind::UnterminatedString),
+        };
+        self.mark_end();
+        Token::String(self.attr.clone(), encoding, chars.into_iter().collect())
+    }
+
+    fn is_newline(&self, c: char) -> bool {
+        matches!(c, '\n' | '\r')
+    }
 }
 
 impl<'a> Iterator for Tokenizer<'a> {
     type Item = Token;
     fn next(&mut self) -> Option<Token> {
         while let Some(c) = self.advance() {
-            if self.is_newline(c) {
-                self.cursor.incr_row();
+            if c.is_whitespace() {
                 continue;
             }
-            if c.is_whitespace() {
+            self.mark_start();
+            if c == '/' && matches!(self.peek(), Some('/' | '*')) {
+                self.skip_comment();
                 continue;
             }
             return Some(match c {
                 '+' => {
-                    self.mark_start();
-                    let c1 = self.peek();
-                    match c1 {
-                        Some('+') => {
-                            self.advance();
-                            self.mark_end();
-                            Token::Incr(self.attr.clone())
-                        }
-                        _ => {
-                            self.mark_end();
-                            Token::Plus(self.attr.clone())
-                        }
+                    if self.eat('+') {
+                        self.make(Token::Incr)
+                    } else if self.eat('=') {
+                        self.make(Token::PlusAssign)
+                    } else {
+                        self.make(Token::Plus)
                     }
                 }
                 '-' => {
-                    self.mark_start();
-                    let c1 = self.peek();
-                    match c1 {
-                        Some('>') => {
-                            self.advance();
-                            self.mark_end();
-                            Token::Arrow(self.attr.clone())
-                        }
-                        _ => {
-                            self.mark_end();
-                            Token::Minus(self.attr.clone())
-                        }
+                    if self.eat('>') {
+                        self.make(Token::Arrow)
+                    } else if self.eat('-') {
+                        self.make(Token::Decr)
+                    } else if self.eat('=') {
+                        self.make(Token::MinusAssign)
+                    } else {
+                        self.make(Token::Minus)
                     }
                 }
-                '/' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::Div(self.attr.clone())
-                }
-                '*' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::Mul(self.attr.clone())
-                }
-                '!' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::Not(self.attr.clone())
-                }
-                '<' => {
-                    self.mark_start();
-                    let c1 = self.peek();
-                    match c1 {
-                        Some('<') => {
+                '*' => self.or_eq(Token::Mul, Token::MulAssign),
+                '/' => self.or_eq(Token::Div, Token::DivAssign),
+                '!' => self.or_eq(Token::Not, Token::Neq),
+                '=' => self.or_eq(Token::Assign, Token::Eq),
+                '^' => self.or_eq(Token::BitXor, Token::BitXorAssign),
+                '%' => {
+                    if self.eat('>') {
+                        self.make(Token::RBrace)
+                    } else if self.peek() == Some(&':') {
+                        self.advance();
+                        if self.peek() == Some(&'%') && self.peek_nth(1) == Some(':') {
                             self.advance();
-                            self.mark_end();
-                            Token::LShift(self.attr.clone())
-                        }
-                        Some('=') => {
                             self.advance();
-                            self.mark_end();
-                            Token::Lte(self.attr.clone())
-                        }
-                        _ => {
-                            self.mark_end();
-                            Token::Lt(self.attr.clone())
+                            self.make(Token::HashHash)
+                        } else {
+                            self.make(Token::Hash)
                         }
+                    } else {
+                        self.or_eq(Token::Mod, Token::ModAssign)
                     }
                 }
-                '>' => {
-                    self.mark_start();
-                    let c1 = self.peek();
-                    match c1 {
-                        Some('>') => {
-                            self.advance();
-                            self.mark_end();
-                            Token::RShift(self.attr.clone())
-                        }
-                        Some('=') => {
-                            self.advance();
-                            self.mark_end();
-                            Token::Gte(self.attr.clone())
-                        }
-                        _ => {
-                            self.mark_end();
-                            Token::Gt(self.attr.clone())
-                        }
+                '&' => {
+                    if self.eat('&') {
+                        self.make(Token::And)
+                    } else {
+                        self.or_eq(Token::BitAnd, Token::BitAndAssign)
                     }
                 }
-                '.' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::Dot(self.attr.clone())
-                }
-                '(' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::LParen(self.attr.clone())
-                }
-                ')' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::RParen(self.attr.clone())
-                }
-                '[' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::LBraket(self.attr.clone())
-                }
-                ']' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::RBraket(self.attr.clone())
-                }
-                '{' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::LBrace(self.attr.clone())
+                '|' => {
+                    if self.eat('|') {
+                        self.make(Token::Or)
+                    } else {
+                        self.or_eq(Token::BitOr, Token::BitOrAssign)
+                    }
                 }
-                '}' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::RBrace(self.attr.clone())
+                '<' => {
+                    if self.eat('<') {
+                        self.or_eq(Token::LShift, Token::LShiftAssign)
+                    } else if self.eat('=') {
+                        self.make(Token::Lte)
+                    } else if self.eat(':') {
+                        self.make(Token::LBraket)
+                    } else if self.eat('%') {
+                        self.make(Token::LBrace)
+                    } else {
+                        self.make(Token::Lt)
+                    }
                 }
-                '?' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::QuestionMark(self.attr.clone())
+                '>' => {
+                    if self.eat('>') {
+                        self.or_eq(Token::RShift, Token::RShiftAssign)
+                    } else if self.eat('=') {
+                        self.make(Token::Gte)
+                    } else {
+                        self.make(Token::Gt)
+                    }
                 }
-                '#' => {
-                    self.mark_start();
-                    self.mark_end();
-                    Token::Hash(self.attr.clone())
+                ':' => {
+                    if self.eat('>') {
+                        self.make(Token::RBraket)
+                    } else {
+                        self.make(Token::Colon)
+                    }
                 }
-                // char
-                '\'' => {
-                    self.mark_start();
-                    if let Some(c1) = self.advance() {
+                '.' if self.peek().is_some_and(|c1| c1.is_ascii_digit()) => self.parse_number(c),
+                '.' => {
+                    if self.peek() == Some(&'.') && self.peek_nth(1) == Some('.') {
                         self.advance();
-                        self.mark_end();
-                        let attr = self.attr.clone();
-                        Token::Char(attr, c1)
+                        self.advance();
+                        self.make(Token::Ellipsis)
                     } else {
-                        panic!("the ' is not enclosed")
+                        self.make(Token::Dot)
                     }
                 }
-                // string
-                '"' => {
-                    self.mark_start();
-                    let mut s = String::new();
-                    loop {
-                        let c1 = self.advance();
-                        match c1 {
-                            Some('"') => {
-                                self.mark_end();
-                                return Some(Token::String(self.attr.clone(), s));
-                            }
-                            Some(c2) => {
-                                s.push(c2);
-                            }
-                            None => panic!("\" is not enclosed"),
-                        }
+                '#' => {
+                    if self.eat('#') {
+                        self.make(Token::HashHash)
+                    } else {
+                        self.make(Token::Hash)
                     }
                 }
+                '~' => self.make(Token::BitNot),
+                '?' => self.make(Token::QuestionMark),
+                ';' => self.make(Token::Semicolon),
+                ',' => self.make(Token::Comma),
+                '(' => self.make(Token::LParen),
+                ')' => self.make(Token::RParen),
+                '[' => self.make(Token::LBraket),
+                ']' => self.make(Token::RBraket),
+                '{' => self.make(Token::LBrace),
+                '}' => self.make(Token::RBrace),
+                // char
+                '\'' => self.parse_char(Encoding::Plain),
+                // string
+                '"' => self.parse_string(Encoding::Plain),
                 // number
                 '0'..='9' => self.parse_number(c),
                 // id
-                'a'..='z' | 'A'..='Z' => {
-                    self.mark_start();
+                _ if self.is_id_start(c) => {
                     let mut s = String::new();
                     s.push(c);
-                    loop {
-                        if let Some(c1) = self.advance() {
-                            match c1 {
-                                'a'..='z' | 'A'..='Z' | '0'..='9' => {
-                                    s.push(c1);
-                                }
-                                _ => {
-                                    self.mark_end();
-                                    if self.is_keyword(&s) {
-                                        return Some(Token::ID(self.attr.clone(), s));
-                                    }
-                                    return Some(Token::String(self.attr.clone(), s));
-                                }
-                            }
-                        } else {
+                    while let Some(&c1) = self.peek() {
+                        if !self.is_id_continue(c1) {
+                            break;
+                        }
+                        s.push(c1);
+                        self.advance();
+                    }
+                    match (Encoding::from_prefix(&s), self.peek()) {
+                        (Some(encoding), Some('\'')) => {
+                            self.advance();
+                            self.parse_char(encoding)
+                        }
+                        (Some(encoding), Some('"')) => {
+                            self.advance();
+                            self.parse_string(encoding)
+                        }
+                        _ => {
                             self.mark_end();
-                            if self.is_keyword(&s) {
-                                return Some(Token::ID(self.attr.clone(), s));
+                            let attr = self.attr.clone();
+                            match s.as_str() {
+                                "true" => Token::Bool(attr, true),
+                                "false" => Token::Bool(attr, false),
+                                _ => match keyword(&s) {
+                                    Some(token) => token(attr),
+                                    None => Token::ID(attr, s),
+                                },
                             }
-                            return Some(Token::String(self.attr.clone(), s));
                         }
                     }
                 }
-                _ => panic!("{} is not supported yet", c),
+                _ => {
+                    self.mark_end();
+                    let error = LexError::new(self.attr.clone(), LexErrorKind::UnexpectedChar(c));
+                    self.errors.push(error);
+                    Token::Illegal(self.attr.clone())
+                }
             });
         }
 
@@ -286,34 +536,300 @@ impl<'a> Iterator for Tokenizer<'a> {
     }
 }
 
+type Chars<'a> = std::iter::Peekable<std::str::Chars<'a>>;
+
+/// Returns the length of the backslash-newline at the head of `chars`.
+fn splice_len(chars: &Chars) -> Option<usize> {
+    let mut lookahead = chars.clone();
+    if lookahead.next() != Some('\\') {
+        return None;
+    }
+    match (lookahead.next(), lookahead.next()) {
+        (Some('\r'), Some('\n')) => Some(3),
+        (Some('\r' | '\n'), _) => Some(2),
+        _ => None,
+    }
+}
+
+fn skip_raw_splices(chars: &mut Chars) {
+    while let Some(len) = splice_len(chars) {
+        for _ in 0..len {
+            chars.next();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::syntax::tokenizer::{attr::Attr, token::Token};
+    use crate::syntax::tokenizer::{
+        attr::Attr,
+        error::{LexError, LexErrorKind},
+        token::{Encoding, Token},
+    };
 
     use super::Tokenizer;
 
+    type Case = (&'static str, fn(Attr) -> Token);
+
     #[test]
     pub fn test_char() {
         let mut tokenizer = Tokenizer::new("'a'");
         assert_eq!(
             tokenizer.next(),
-            Some(Token::Char(Attr::range(1, 1, 1, 3), 'a'))
+            Some(Token::Char(
+                Attr::range(1, 1, 1, 3).with_span(0, 3),
+                Encoding::Plain,
+                'a'
+            ))
         )
     }
 
     #[test]
-    pub fn test_i64() {
+    pub fn test_escapes() {
+        let testcases = vec![
+            ("'\\n'", '\n'),
+            ("'\\''", '\''),
+            ("'\\\\'", '\\'),
+            ("'\\?'", '?'),
+            ("'\\a'", '\u{7}'),
+            ("'\\v'", '\u{b}'),
+            ("'\\0'", '\0'),
+            ("'\\101'", 'A'),
+            ("'\\x41'", 'A'),
+            ("'\\xff'", '\u{ff}'),
+            ("'\\u00e9'", 'é'),
+            ("'\\U0001F600'", '😀'),
+        ];
+        for (testcase, expected) in testcases {
+            let mut tokenizer = Tokenizer::new(testcase);
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Char(
+                    Attr::range(1, 1, 1, testcase.chars().count() as u32)
+                        .with_span(0, testcase.len()),
+                    Encoding::Plain,
+                    expected
+                ))
+            );
+            assert!(tokenizer.errors().is_empty(), "{}", testcase);
+        }
+
+        let mut tokenizer = Tokenizer::new("\"a\\\"b\\tc\\101\\1234\"");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 1, 1, 18).with_span(0, 18),
+                Encoding::Plain,
+                String::from("a\"b\tcAS4")
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_prefixed_literals() {
+        let testcases = vec![
+            ("L'a'", Encoding::Wide),
+            ("u'a'", Encoding::Utf16),
+            ("U'a'", Encoding::Utf32),
+            ("u8'a'", Encoding::Utf8),
+        ];
+        for (testcase, encoding) in testcases {
+            let mut tokenizer = Tokenizer::new(testcase);
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Char(
+                    Attr::range(1, 1, 1, testcase.len() as u32).with_span(0, testcase.len()),
+                    encoding,
+                    'a'
+                ))
+            );
+        }
+        let mut tokenizer = Tokenizer::new("u8\"ab\" L\"\\x10000\" U\"\\U0010FFFF\"");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 1, 1, 6).with_span(0, 6),
+                Encoding::Utf8,
+                String::from("ab")
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 8, 1, 17).with_span(7, 17),
+                Encoding::Wide,
+                String::from("\u{10000}")
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 19, 1, 31).with_span(18, 31),
+                Encoding::Utf32,
+                String::from("\u{10ffff}")
+            ))
+        );
+        assert!(tokenizer.errors().is_empty());
+    }
+
+    #[test]
+    pub fn test_escape_errors() {
+        let mut tokenizer = Tokenizer::new("\"a\\qb\\x\\x100\\u12\\u0041\" u\"\\xd800\"");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 1, 1, 23).with_span(0, 23),
+                Encoding::Plain,
+                String::from("aqb")
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 25, 1, 33).with_span(24, 33),
+                Encoding::Utf16,
+                String::new()
+            ))
+        );
+        assert_eq!(
+            tokenizer.errors(),
+            &[
+                LexError::new(
+                    Attr::range(1, 3, 1, 4).with_span(2, 4),
+                    LexErrorKind::UnknownEscape('q')
+                ),
+                LexError::new(
+                    Attr::range(1, 6, 1, 7).with_span(5, 7),
+                    LexErrorKind::MissingHexEscapeDigits
+                ),
+                LexError::new(
+                    Attr::range(1, 8, 1, 12).with_span(7, 12),
+                    LexErrorKind::EscapeOutOfRange
+                ),
+                LexError::new(
+                    Attr::range(1, 13, 1, 16).with_span(12, 16),
+                    LexErrorKind::IncompleteUniversalChar
+                ),
+                LexError::new(
+                    Attr::range(1, 17, 1, 22).with_span(16, 22),
+                    LexErrorKind::InvalidUniversalChar(0x41)
+                ),
+                LexError::new(
+                    Attr::range(1, 27, 1, 32).with_span(26, 32),
+                    LexErrorKind::EscapeOutOfRange
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_char_constant_errors() {
+        let mut tokenizer = Tokenizer::new("'' 'ab'");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::range(1, 1, 1, 2).with_span(0, 2)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Char(
+                Attr::range(1, 4, 1, 7).with_span(3, 7),
+                Encoding::Plain,
+                'b'
+            ))
+        );
+        assert_eq!(
+            tokenizer.errors(),
+            &[
+                LexError::new(
+                    Attr::range(1, 1, 1, 2).with_span(0, 2),
+                    LexErrorKind::EmptyCharConstant
+                ),
+                LexError::new(
+                    Attr::range(1, 4, 1, 7).with_span(3, 7),
+                    LexErrorKind::MultiCharConstant
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_i32() {
         let testcases = vec!["1", "12", "123", "1234"];
-        let testcase_results: Vec<i64> = vec![1, 12, 123, 1234];
+        let testcase_results: Vec<i32> = vec![1, 12, 123, 1234];
         for (i, testcase) in testcases.iter().enumerate() {
             let mut tokenizer = Tokenizer::new(&testcase);
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::Int64(Attr::point(1, 1), testcase_results[i]))
+                Some(Token::Int32(
+                    Attr::range(1, 1, 1, testcase.len() as u32).with_span(0, testcase.len()),
+                    testcase_results[i]
+                ))
             )
         }
     }
 
+    #[test]
+    pub fn test_numbers() {
+        let mut tokenizer = Tokenizer::new("0x1fUL 1.5e+3f .25 3 0xe+1");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::UInt64(Attr::range(1, 1, 1, 6).with_span(0, 6), 0x1f))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Float(
+                Attr::range(1, 8, 1, 14).with_span(7, 14),
+                1500.0
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Float64(
+                Attr::range(1, 16, 1, 18).with_span(15, 18),
+                0.25
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Int32(Attr::point(1, 20).with_span(19, 20), 3))
+        );
+        // `0xe+1` is a single (invalid) preprocessing number
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::range(1, 22, 1, 26).with_span(21, 26)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+    }
+
+    #[test]
+    pub fn test_number_errors() {
+        let mut tokenizer = Tokenizer::new("09 99999999999999999999");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::range(1, 1, 1, 2).with_span(0, 2)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::UInt64(
+                Attr::range(1, 4, 1, 23).with_span(3, 23),
+                u64::MAX
+            ))
+        );
+        assert_eq!(
+            tokenizer.errors(),
+            &[
+                LexError::new(
+                    Attr::range(1, 1, 1, 2).with_span(0, 2),
+                    LexErrorKind::InvalidDigit('9')
+                ),
+                LexError::new(
+                    Attr::range(1, 4, 1, 23).with_span(3, 23),
+                    LexErrorKind::IntegerTooLarge
+                ),
+            ]
+        );
+    }
+
     #[test]
     pub fn test_string() {
         let testcases = vec!["\"a\"", "\"ab\"", "\"abc\"", "\"abcd\""];
@@ -328,7 +844,8 @@ mod tests {
             assert_eq!(
                 tokenizer.next(),
                 Some(Token::String(
-                    Attr::range(1, 1, 1, (2 + (i + 1)) as u8),
+                    Attr::range(1, 1, 1, (2 + (i + 1)) as u32).with_span(0, testcase.len()),
+                    Encoding::Plain,
                     testcase_results[i].clone(),
                 ))
             );
@@ -340,129 +857,556 @@ mod tests {
         let mut tokenizer = Tokenizer::new("abc");
         assert_eq!(
             tokenizer.next(),
-            Some(Token::String(Attr::range(1, 1, 1, 3), String::from("abc")))
+            Some(Token::ID(
+                Attr::range(1, 1, 1, 3).with_span(0, 3),
+                String::from("abc")
+            ))
         );
+        let mut tokenizer = Tokenizer::new("_a1 scheme_entry(x)__b ifx intx é");
+        let ids = vec![
+            (Attr::range(1, 1, 1, 3).with_span(0, 3), "_a1"),
+            (Attr::range(1, 5, 1, 16).with_span(4, 16), "scheme_entry"),
+        ];
+        for (attr, id) in ids {
+            assert_eq!(tokenizer.next(), Some(Token::ID(attr, String::from(id))));
+        }
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::LParen(Attr::point(1, 17).with_span(16, 17)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::ID(
+                Attr::point(1, 18).with_span(17, 18),
+                String::from("x")
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::RParen(Attr::point(1, 19).with_span(18, 19)))
+        );
+        let ids = vec![
+            (Attr::range(1, 20, 1, 22).with_span(19, 22), "__b"),
+            (Attr::range(1, 24, 1, 26).with_span(23, 26), "ifx"),
+            (Attr::range(1, 28, 1, 31).with_span(27, 31), "intx"),
+            (Attr::point(1, 33).with_span(32, 34), "é"),
+        ];
+        for (attr, id) in ids {
+            assert_eq!(tokenizer.next(), Some(Token::ID(attr, String::from(id))));
+        }
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
     }
 
     #[test]
     pub fn test_symbols() {
         {
             let mut tokenizer = Tokenizer::new("+");
-            assert_eq!(tokenizer.next(), Some(Token::Plus(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Plus(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("++");
-            assert_eq!(tokenizer.next(), Some(Token::Incr(Attr::range(1, 1, 1, 2))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Incr(Attr::range(1, 1, 1, 2).with_span(0, 2)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("-");
-            assert_eq!(tokenizer.next(), Some(Token::Minus(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Minus(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("->");
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::Arrow(Attr::range(1, 1, 1, 2)))
+                Some(Token::Arrow(Attr::range(1, 1, 1, 2).with_span(0, 2)))
             );
         }
         {
             let mut tokenizer = Tokenizer::new("/");
-            assert_eq!(tokenizer.next(), Some(Token::Div(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Div(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("*");
-            assert_eq!(tokenizer.next(), Some(Token::Mul(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Mul(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("<");
-            assert_eq!(tokenizer.next(), Some(Token::Lt(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Lt(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("<<");
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::LShift(Attr::range(1, 1, 1, 2)))
+                Some(Token::LShift(Attr::range(1, 1, 1, 2).with_span(0, 2)))
             );
         }
         {
             let mut tokenizer = Tokenizer::new("<=");
-            assert_eq!(tokenizer.next(), Some(Token::Lte(Attr::range(1, 1, 1, 2))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Lte(Attr::range(1, 1, 1, 2).with_span(0, 2)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new(">");
-            assert_eq!(tokenizer.next(), Some(Token::Gt(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Gt(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new(">>");
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::RShift(Attr::range(1, 1, 1, 2)))
+                Some(Token::RShift(Attr::range(1, 1, 1, 2).with_span(0, 2)))
             );
         }
         {
             let mut tokenizer = Tokenizer::new(">=");
-            assert_eq!(tokenizer.next(), Some(Token::Gte(Attr::range(1, 1, 1, 2))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Gte(Attr::range(1, 1, 1, 2).with_span(0, 2)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("!");
-            assert_eq!(tokenizer.next(), Some(Token::Not(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Not(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("(");
-            assert_eq!(tokenizer.next(), Some(Token::LParen(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::LParen(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new(")");
-            assert_eq!(tokenizer.next(), Some(Token::RParen(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::RParen(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("[");
-            assert_eq!(tokenizer.next(), Some(Token::LBraket(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::LBraket(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("]");
-            assert_eq!(tokenizer.next(), Some(Token::RBraket(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::RBraket(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("{");
-            assert_eq!(tokenizer.next(), Some(Token::LBrace(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::LBrace(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("}");
-            assert_eq!(tokenizer.next(), Some(Token::RBrace(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::RBrace(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
         {
             let mut tokenizer = Tokenizer::new("?");
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::QuestionMark(Attr::point(1, 1)))
+                Some(Token::QuestionMark(Attr::point(1, 1).with_span(0, 1)))
             );
         }
         {
             let mut tokenizer = Tokenizer::new("?");
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::QuestionMark(Attr::point(1, 1)))
+                Some(Token::QuestionMark(Attr::point(1, 1).with_span(0, 1)))
             );
         }
         {
             let mut tokenizer = Tokenizer::new("#");
-            assert_eq!(tokenizer.next(), Some(Token::Hash(Attr::point(1, 1))));
+            assert_eq!(
+                tokenizer.next(),
+                Some(Token::Hash(Attr::point(1, 1).with_span(0, 1)))
+            );
         }
     }
 
+    #[test]
+    pub fn test_punctuators() {
+        let testcases: Vec<Case> = vec![
+            ("--", Token::Decr),
+            ("%", Token::Mod),
+            ("==", Token::Eq),
+            ("!=", Token::Neq),
+            ("&&", Token::And),
+            ("||", Token::Or),
+            ("&", Token::BitAnd),
+            ("|", Token::BitOr),
+            ("^", Token::BitXor),
+            ("=", Token::Assign),
+            ("+=", Token::PlusAssign),
+            ("-=", Token::MinusAssign),
+            ("*=", Token::MulAssign),
+            ("/=", Token::DivAssign),
+            ("%=", Token::ModAssign),
+            ("<<=", Token::LShiftAssign),
+            (">>=", Token::RShiftAssign),
+            ("&=", Token::BitAndAssign),
+            ("|=", Token::BitOrAssign),
+            ("^=", Token::BitXorAssign),
+            ("...", Token::Ellipsis),
+            ("##", Token::HashHash),
+            ("<:", Token::LBraket),
+            (":>", Token::RBraket),
+            ("<%", Token::LBrace),
+            ("%>", Token::RBrace),
+            ("%:", Token::Hash),
+            ("%:%:", Token::HashHash),
+        ];
+        for (testcase, token) in testcases {
+            let mut tokenizer = Tokenizer::new(testcase);
+            assert_eq!(
+                tokenizer.next(),
+                Some(token(
+                    Attr::range(1, 1, 1, testcase.len() as u32).with_span(0, testcase.len())
+                ))
+            );
+        }
+        for (testcase, token) in [
+            ("~", Token::BitNot as fn(Attr) -> Token),
+            (",", Token::Comma),
+            (";", Token::Semicolon),
+            (":", Token::Colon),
+        ] {
+            let mut tokenizer = Tokenizer::new(testcase);
+            assert_eq!(
+                tokenizer.next(),
+                Some(token(Attr::point(1, 1).with_span(0, 1)))
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_punctuator_sequence() {
+        let mut tokenizer = Tokenizer::new("..%:%&&=");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Dot(Attr::point(1, 1).with_span(0, 1)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Dot(Attr::point(1, 2).with_span(1, 2)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Hash(Attr::range(1, 3, 1, 4).with_span(2, 4)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Mod(Attr::point(1, 5).with_span(4, 5)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::And(Attr::range(1, 6, 1, 7).with_span(5, 7)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Assign(Attr::point(1, 8).with_span(7, 8)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+        let mut tokenizer = Tokenizer::new("<<=>>=");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::LShiftAssign(Attr::range(1, 1, 1, 3).with_span(0, 3)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::RShiftAssign(Attr::range(1, 4, 1, 6).with_span(3, 6)))
+        );
+    }
+
+    #[test]
+    pub fn test_comments() {
+        let mut tokenizer =
+            Tokenizer::new("+ // line comment\n/* block\n comment */ - /**/*\n/* a */ /");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Plus(Attr::point(1, 1).with_span(0, 1)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Minus(Attr::point(3, 13).with_span(39, 40)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Mul(Attr::point(3, 19).with_span(45, 46)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Div(Attr::point(4, 9).with_span(55, 56)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+        assert!(tokenizer.errors().is_empty());
+
+        let mut tokenizer = Tokenizer::new("\"// not a comment\" 1/2");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(1, 1, 1, 18).with_span(0, 18),
+                Encoding::Plain,
+                String::from("// not a comment")
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Int32(Attr::point(1, 20).with_span(19, 20), 1))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Div(Attr::point(1, 21).with_span(20, 21)))
+        );
+    }
+
+    #[test]
+    pub fn test_unterminated_comment() {
+        let mut tokenizer = Tokenizer::new("; /* a\n */ ; ");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Semicolon(Attr::point(1, 1).with_span(0, 1)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Semicolon(Attr::point(2, 5).with_span(11, 12)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+        let mut tokenizer = Tokenizer::new("; /* a\nb */");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Semicolon(Attr::point(1, 1).with_span(0, 1)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+        assert!(tokenizer.errors().is_empty());
+        let mut tokenizer = Tokenizer::new("; /* a\nb *");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Semicolon(Attr::point(1, 1).with_span(0, 1)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+        assert_eq!(
+            tokenizer.errors(),
+            &[LexError::new(
+                Attr::range(1, 3, 2, 3).with_span(2, 10),
+                LexErrorKind::UnterminatedComment
+            )]
+        );
+    }
+
+    #[test]
+    pub fn test_line_splicing() {
+        let mut tokenizer = Tokenizer::new("<\\\n<\\\r\n= \"a\\\nb\" // c \\\n d\n>\\\n>");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::LShiftAssign(Attr::range(1, 1, 3, 1).with_span(0, 8)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::String(
+                Attr::range(3, 3, 4, 2).with_span(9, 15),
+                Encoding::Plain,
+                String::from("ab")
+            ))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::RShift(Attr::range(6, 1, 7, 1).with_span(26, 30)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+    }
+
+    #[test]
+    pub fn test_crlf() {
+        let mut tokenizer = Tokenizer::new("+\r\n-\r*");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Plus(Attr::point(1, 1).with_span(0, 1)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Minus(Attr::point(2, 1).with_span(3, 4)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Mul(Attr::point(3, 1).with_span(5, 6)))
+        );
+    }
+
+    #[test]
+    pub fn test_recovery() {
+        let mut tokenizer = Tokenizer::new("'a\n\"abc\\\"\n@ + ` \"x\\");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::range(1, 1, 1, 2).with_span(0, 2)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::range(2, 1, 2, 6).with_span(3, 9)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::point(3, 1).with_span(10, 11)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Plus(Attr::point(3, 3).with_span(12, 13)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::point(3, 5).with_span(14, 15)))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Illegal(Attr::range(3, 7, 3, 9).with_span(16, 19)))
+        );
+        assert_eq!(tokenizer.next(), Some(Token::EOF));
+        assert_eq!(
+            tokenizer.errors(),
+            &[
+                LexError::new(
+                    Attr::range(1, 1, 1, 2).with_span(0, 2),
+                    LexErrorKind::UnterminatedChar
+                ),
+                LexError::new(
+                    Attr::range(2, 1, 2, 6).with_span(3, 9),
+                    LexErrorKind::UnterminatedString
+                ),
+                LexError::new(
+                    Attr::point(3, 1).with_span(10, 11),
+                    LexErrorKind::UnexpectedChar('@')
+                ),
+                LexError::new(
+                    Attr::point(3, 5).with_span(14, 15),
+                    LexErrorKind::UnexpectedChar('`')
+                ),
+                LexError::new(
+                    Attr::range(3, 7, 3, 9).with_span(16, 19),
+                    LexErrorKind::UnterminatedString
+                ),
+            ]
+        );
+    }
+
     #[test]
     pub fn test_keywords() {
-        let keywords = vec!["typedef", "struct", "enum", "if", "else", "while", "return"];
-        for (i, keyword) in keywords.iter().enumerate() {
-            let mut tokenizer = Tokenizer::new(&keyword);
+        let testcases: Vec<Case> = vec![
+            ("auto", Token::Auto),
+            ("break", Token::Break),
+            ("case", Token::Case),
+            ("char", Token::CharKw),
+            ("const", Token::Const),
+            ("continue", Token::Continue),
+            ("default", Token::Default),
+            ("do", Token::Do),
+            ("double", Token::Double),
+            ("else", Token::Else),
+            ("enum", Token::Enum),
+            ("extern", Token::Extern),
+            ("float", Token::FloatKw),
+            ("for", Token::For),
+            ("goto", Token::Goto),
+            ("if", Token::If),
+            ("inline", Token::Inline),
+            ("int", Token::Int),
+            ("long", Token::Long),
+            ("register", Token::Register),
+            ("restrict", Token::Restrict),
+            ("return", Token::Return),
+            ("short", Token::Short),
+            ("signed", Token::Signed),
+            ("sizeof", Token::Sizeof),
+            ("static", Token::Static),
+            ("struct", Token::Struct),
+            ("switch", Token::Switch),
+            ("typedef", Token::Typedef),
+            ("union", Token::Union),
+            ("unsigned", Token::Unsigned),
+            ("void", Token::Void),
+            ("volatile", Token::Volatile),
+            ("while", Token::While),
+            ("_Alignas", Token::Alignas),
+            ("_Alignof", Token::Alignof),
+            ("_Atomic", Token::Atomic),
+            ("_Bool", Token::BoolKw),
+            ("_Complex", Token::Complex),
+            ("_Generic", Token::Generic),
+            ("_Imaginary", Token::Imaginary),
+            ("_Noreturn", Token::Noreturn),
+            ("_Static_assert", Token::StaticAssert),
+            ("_Thread_local", Token::ThreadLocal),
+        ];
+        for (keyword, token) in testcases {
+            let mut tokenizer = Tokenizer::new(keyword);
             assert_eq!(
                 tokenizer.next(),
-                Some(Token::ID(
-                    Attr::range(1, 1, 1, keyword.len() as u8),
-                    String::from(keywords[i])
+                Some(token(
+                    Attr::range(1, 1, 1, keyword.len() as u32).with_span(0, keyword.len())
                 ))
             );
         }
     }
+
+    #[test]
+    pub fn test_bool() {
+        let mut tokenizer = Tokenizer::new("true false");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Bool(Attr::range(1, 1, 1, 4).with_span(0, 4), true))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Token::Bool(
+                Attr::range(1, 6, 1, 10).with_span(5, 10),
+                false
+            ))
+        );
+    }
+
+    #[test]
+    pub fn test_example() {
+        let source = std::fs::read_to_string("examples/c1.c").unwrap();
+        let tokens: Vec<Token> = Tokenizer::new(&source)
+            .take_while(|token| *token != Token::EOF)
+            .collect();
+        assert_eq!(tokens.len(), 37);
+        assert_eq!(
+            tokens[0],
+            Token::Int(Attr::range(1, 1, 1, 3).with_span(0, 3))
+        );
+        assert!(!tokens
+            .iter()
+            .any(|token| matches!(token, Token::Illegal(_))));
+    }
 }
@@ -1,3 +1,4 @@
 pub mod env;
+pub mod tag;
 pub mod type_checker;
 pub mod types;
@@ -0,0 +1,143 @@
+use super::types::Type;
+
+/// Identifies a `Tag` in a `TagTable`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
+pub struct TagId(pub u32);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TagKind {
+    Struct,
+    Union,
+    Enum,
+}
+
+impl TagKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            TagKind::Struct => "struct",
+            TagKind::Union => "union",
+            TagKind::Enum => "enum",
+        }
+    }
+}
+
+/// A member of a struct or union. Unnamed bit-fields and anonymous
+/// structs and unions have no name.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Field {
+    pub name: Option<String>,
+    pub typ: Type,
+    // the width of a bit-field
+    pub bits: Option<u32>,
+}
+
+impl Field {
+    pub fn new(name: Option<String>, typ: Type, bits: Option<u32>) -> Field {
+        Field { name, typ, bits }
+    }
+}
+
+/// A struct, union or enum type. Anonymous ones have no name, and a tag
+/// is incomplete from its forward declaration up to its definition.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Tag {
+    pub kind: TagKind,
+    pub name: Option<String>,
+    pub complete: bool,
+    // the members of a struct or union
+    pub fields: Vec<Field>,
+    // the constants of an enum with their values
+    pub enumerators: Vec<(String, i64)>,
+}
+
+impl Tag {
+    pub fn new(kind: TagKind, name: Option<String>) -> Tag {
+        Tag {
+            kind,
+            name,
+            complete: false,
+            fields: Vec::new(),
+            enumerators: Vec::new(),
+        }
+    }
+
+    /// The type that refers to this tag.
+    pub fn typ(&self, id: TagId) -> Type {
+        match self.kind {
+            TagKind::Struct => Type::Struct(id),
+            TagKind::Union => Type::Union(id),
+            TagKind::Enum => Type::Enum(id),
+        }
+    }
+
+    /// `struct S`, or `struct <anonymous>`.
+    pub fn spelling(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} {}", self.kind.keyword(), name),
+            None => format!("{} <anonymous>", self.kind.keyword()),
+        }
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields
+            .iter()
+            .find(|field| field.name.as_deref() == Some(name))
+    }
+}
+
+/// Every struct, union and enum of a translation unit, whatever scope
+/// declares them; `Type::Struct` and the others refer to them by id.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct TagTable {
+    tags: Vec<Tag>,
+}
+
+impl TagTable {
+    pub fn new() -> TagTable {
+        TagTable { tags: Vec::new() }
+    }
+
+    pub fn add(&mut self, tag: Tag) -> TagId {
+        self.tags.push(tag);
+        TagId(self.tags.len() as u32 - 1)
+    }
+
+    pub fn tag(&self, id: TagId) -> &Tag {
+        &self.tags[id.0 as usize]
+    }
+
+    pub fn tag_mut(&mut self, id: TagId) -> &mut Tag {
+        &mut self.tags[id.0 as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.tags.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::typing::types::Type;
+
+    use super::{Field, Tag, TagId, TagKind, TagTable};
+
+    #[test]
+    pub fn test_tag_table() {
+        let mut tags = TagTable::new();
+        let s = tags.add(Tag::new(TagKind::Struct, Some(String::from("S"))));
+        let e = tags.add(Tag::new(TagKind::Enum, None));
+        assert_eq!((s, e), (TagId(0), TagId(1)));
+        assert_eq!(tags.tag(s).typ(s), Type::Struct(s));
+        assert_eq!(tags.tag(s).spelling(), "struct S");
+        assert_eq!(tags.tag(e).spelling(), "enum <anonymous>");
+
+        let field = Field::new(Some(String::from("a")), Type::Int, Some(3));
+        tags.tag_mut(s).fields.push(field.clone());
+        assert_eq!(tags.tag(s).field("a"), Some(&field));
+        assert_eq!(tags.tag(s).field("b"), None);
+    }
+}
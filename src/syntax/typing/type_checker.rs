@@ -20,14 +20,19 @@ impl TypeChecker {
 
     pub fn type_check_expr(&mut self, expr: &Expr) -> Result<Type, Box<dyn Error>> {
         match expr {
-            Expr::LiteralExpr(le) => match le {
+            Expr::LiteralExpr(attr, le) => match le {
                 Literal::Bool(_) => Ok(Type::Bool),
-                Literal::ID(_) => panic!("not implemented id's type"),
+                Literal::ID(_) => panic!("{}: not implemented id's type", attr.start()),
                 Literal::Char(_) => Ok(Type::Char),
                 Literal::Int(_) => Ok(Type::Int),
+                Literal::UInt(u) if *u > u32::MAX as u64 => Ok(Type::ULong),
+                Literal::UInt(_) => Ok(Type::UInt),
+                Literal::Float(_) => Ok(Type::Double),
+                Literal::Float32(_) => Ok(Type::Float),
+                Literal::LongDouble(_) => Ok(Type::LongDouble),
                 Literal::String(_) => Ok(Type::String),
             },
-            _ => panic!("not implemented yet"),
+            expr => panic!("{}: not implemented yet", expr.attr().start()),
         }
     }
 
@@ -38,6 +43,7 @@ impl TypeChecker {
 
 #[cfg(test)]
 mod tests {
+    use crate::syntax::tokenizer::attr::Attr;
     use crate::syntax::{
         ast::expr::{expr::Expr, literal_expr::Literal},
         typing::types::Type,
@@ -49,7 +55,7 @@ mod tests {
     pub fn test_literal_types() {
         {
             let mut typechecker = TypeChecker::new();
-            let e = Expr::LiteralExpr(Literal::Bool(false));
+            let e = Expr::LiteralExpr(Attr::new(), Literal::Bool(false));
             let result = typechecker.type_check_expr(&e);
             if let Ok(typ) = result {
                 assert_eq!(typ, Type::Bool);
@@ -58,7 +64,7 @@ mod tests {
 
         {
             let mut typechecker = TypeChecker::new();
-            let e = Expr::LiteralExpr(Literal::Int(32));
+            let e = Expr::LiteralExpr(Attr::new(), Literal::Int(32));
             let result = typechecker.type_check_expr(&e);
             if let Ok(typ) = result {
                 assert_eq!(typ, Type::Int);
@@ -67,7 +73,7 @@ mod tests {
 
         {
             let mut typechecker = TypeChecker::new();
-            let e = Expr::LiteralExpr(Literal::Char('a'));
+            let e = Expr::LiteralExpr(Attr::new(), Literal::Char('a'));
             let result = typechecker.type_check_expr(&e);
             if let Ok(typ) = result {
                 assert_eq!(typ, Type::Char);
@@ -1,12 +1,258 @@
-#[derive(PartialEq, Debug)]
+use std::fmt;
+
+use super::tag::{TagId, TagKind, TagTable};
+
+/// What the parentheses of a function type declare about its parameters.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Prototype {
+    /// `f()`, which says nothing about them
+    Unspecified,
+    /// `f(void)` or `f(int a)`
+    Fixed,
+    /// `f(int a, ...)`
+    Variadic,
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum Type {
-    Int,                                 // int
-    Float,                               // float
-    Char,                                // char
-    Bool,                                // bool
-    String,                              // string
-    Vector(Box<Type>),                   // vector<T>,
-    Function(Vec<Box<Type>>, Box<Type>), // (t1, t2, ..., ) -> t
-    Pointer(Box<Type>),                  // int*
-    Reference(Box<Type>),                // int&
+    Void,                                           // void
+    Int,                                            // int
+    UInt,                                           // unsigned int
+    Short,                                          // short
+    UShort,                                         // unsigned short
+    Long,                                           // long
+    ULong,                                          // unsigned long
+    LongLong,                                       // long long
+    ULongLong,                                      // unsigned long long
+    Float,                                          // float
+    Double,                                         // double
+    LongDouble,                                     // long double
+    Char,                                           // char
+    UChar,                                          // unsigned char
+    Bool,                                           // bool
+    String,                                         // string
+    Vector(Box<Type>),                              // vector<T>,
+    Array(Box<Type>, Option<usize>),                // int[3], int[]
+    Function(Vec<Box<Type>>, Box<Type>, Prototype), // (t1, t2, ...) -> t
+    Pointer(Box<Type>),                             // int*
+    Reference(Box<Type>),                           // int&
+    Struct(TagId),                                  // struct S
+    Union(TagId),                                   // union U
+    Enum(TagId),                                    // enum E
+}
+
+impl Type {
+    /// How a declaration of `name` as a `Type` is written in C, with the
+    /// specifiers of tags from `tag`: `int *p[3]` or `int (*p)(char)`. An
+    /// empty name gives the type name of a cast or `sizeof`, such as
+    /// `int (*)[3]`.
+    pub fn declaration(&self, name: &str, tag: &dyn Fn(TagKind, TagId) -> String) -> String {
+        // the declarator is built from the name outwards
+        let mut declarator = name.to_owned();
+        let mut typ = self;
+        loop {
+            // a suffix binds tighter than the `*` before it
+            let grouped = |declarator: String| {
+                if declarator.starts_with('*') {
+                    format!("({})", declarator)
+                } else {
+                    declarator
+                }
+            };
+            typ = match typ {
+                Type::Pointer(pointee) => {
+                    declarator = format!("*{}", declarator);
+                    pointee
+                }
+                Type::Array(elem, size) => {
+                    let size = size.map(|size| size.to_string()).unwrap_or_default();
+                    declarator = format!("{}[{}]", grouped(declarator), size);
+                    elem
+                }
+                Type::Function(params, returns, prototype) => {
+                    let params: Vec<String> = params
+                        .iter()
+                        .map(|param| param.declaration("", tag))
+                        .collect();
+                    let params = parameter_list(params, *prototype);
+                    declarator = format!("{}({})", grouped(declarator), params);
+                    returns
+                }
+                base => {
+                    let base = base.specifier(tag);
+                    if declarator.is_empty() {
+                        return base;
+                    }
+                    return format!("{} {}", base, declarator);
+                }
+            };
+        }
+    }
+
+    /// The size in bytes of an object of this type on x86-64, or `None`
+    /// for incomplete types, functions and structs with bit-fields.
+    pub fn size(&self, tags: &TagTable) -> Option<usize> {
+        let size = match self {
+            Type::Bool | Type::Char | Type::UChar => 1,
+            Type::Short | Type::UShort => 2,
+            Type::Int | Type::UInt | Type::Float => 4,
+            Type::Long | Type::ULong | Type::LongLong | Type::ULongLong | Type::Double => 8,
+            Type::LongDouble => 16,
+            Type::Pointer(_) => 8,
+            Type::Array(elem, Some(n)) => return elem.size(tags)?.checked_mul(*n),
+            Type::Enum(id) if tags.tag(*id).complete => 4,
+            Type::Struct(id) | Type::Union(id) => {
+                let tag = tags.tag(*id);
+                if !tag.complete || tag.fields.iter().any(|f| f.bits.is_some()) {
+                    return None;
+                }
+                let mut size = 0;
+                for field in &tag.fields {
+                    let field_size = field.typ.size(tags)?;
+                    size = match self {
+                        Type::Struct(_) => align_to(size, field.typ.align(tags)?) + field_size,
+                        _ => size.max(field_size),
+                    };
+                }
+                align_to(size, self.align(tags)?)
+            }
+            _ => return None,
+        };
+        Some(size)
+    }
+
+    /// The alignment in bytes of an object of this type on x86-64.
+    pub fn align(&self, tags: &TagTable) -> Option<usize> {
+        match self {
+            Type::Array(elem, _) => elem.align(tags),
+            Type::Struct(id) | Type::Union(id) => {
+                let tag = tags.tag(*id);
+                if !tag.complete {
+                    return None;
+                }
+                tag.fields
+                    .iter()
+                    .try_fold(1, |align, f| Some(align.max(f.typ.align(tags)?)))
+            }
+            typ => typ.size(tags),
+        }
+    }
+
+    /// The specifiers of a type that is not derived from another one.
+    fn specifier(&self, tag: &dyn Fn(TagKind, TagId) -> String) -> String {
+        let keyword = match self {
+            Type::Void => "void",
+            Type::Int => "int",
+            Type::UInt => "unsigned int",
+            Type::Short => "short",
+            Type::UShort => "unsigned short",
+            Type::Long => "long",
+            Type::ULong => "unsigned long",
+            Type::LongLong => "long long",
+            Type::ULongLong => "unsigned long long",
+            Type::Float => "float",
+            Type::Double => "double",
+            Type::LongDouble => "long double",
+            Type::Char => "char",
+            Type::UChar => "unsigned char",
+            Type::Bool => "_Bool",
+            Type::String => "string",
+            Type::Vector(elem) => return format!("vector<{}>", elem.declaration("", tag)),
+            Type::Reference(typ) => return format!("{} &", typ.declaration("", tag)),
+            Type::Struct(id) => return tag(TagKind::Struct, *id),
+            Type::Union(id) => return tag(TagKind::Union, *id),
+            Type::Enum(id) => return tag(TagKind::Enum, *id),
+            Type::Pointer(_) | Type::Array(..) | Type::Function(..) => {
+                return self.declaration("", tag)
+            }
+        };
+        String::from(keyword)
+    }
+}
+
+/// The parameters of a function as written between its parentheses:
+/// nothing without a prototype, `void` for none, and `...` after them
+/// for a variadic function.
+pub fn parameter_list(mut params: Vec<String>, prototype: Prototype) -> String {
+    match prototype {
+        Prototype::Unspecified => {}
+        Prototype::Fixed if params.is_empty() => params.push(String::from("void")),
+        Prototype::Fixed => {}
+        Prototype::Variadic => params.push(String::from("...")),
+    }
+    params.join(", ")
+}
+
+fn align_to(offset: usize, align: usize) -> usize {
+    offset.div_ceil(align) * align
+}
+
+/// A tag by its id, for when the tag table is not at hand: `struct #0`.
+pub fn numbered_tag(kind: TagKind, id: TagId) -> String {
+    format!("{} #{}", kind.keyword(), id.0)
+}
+
+/// The type name, with tags by their ids: `int *` or `struct #0`.
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.declaration("", &numbered_tag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::syntax::typing::tag::{TagId, TagKind};
+
+    use super::{Prototype, Type};
+
+    fn ptr(typ: Type) -> Type {
+        Type::Pointer(Box::new(typ))
+    }
+
+    fn array(typ: Type, size: usize) -> Type {
+        Type::Array(Box::new(typ), Some(size))
+    }
+
+    #[test]
+    pub fn test_declaration() {
+        assert_eq!(Type::ULong.to_string(), "unsigned long");
+        assert_eq!(ptr(ptr(Type::Char)).to_string(), "char **");
+        assert_eq!(array(ptr(Type::Int), 3).to_string(), "int *[3]");
+        assert_eq!(ptr(array(Type::Int, 3)).to_string(), "int (*)[3]");
+        assert_eq!(Type::Union(TagId(1)).to_string(), "union #1");
+
+        let callback = ptr(Type::Function(
+            vec![Box::new(Type::Int), Box::new(ptr(Type::Char))],
+            Box::new(ptr(Type::Void)),
+            Prototype::Fixed,
+        ));
+        let name = |kind: TagKind, _| format!("{} s", kind.keyword());
+        assert_eq!(callback.to_string(), "void *(*)(int, char *)");
+        assert_eq!(
+            array(callback, 2).declaration("f", &name),
+            "void *(*f[2])(int, char *)"
+        );
+        let returns = Box::new(Type::Struct(TagId(0)));
+        assert_eq!(
+            Type::Function(vec![], returns.clone(), Prototype::Fixed).declaration("g", &name),
+            "struct s g(void)"
+        );
+        assert_eq!(
+            Type::Function(vec![], returns, Prototype::Unspecified).declaration("g", &name),
+            "struct s g()"
+        );
+        let printf = Type::Function(
+            vec![Box::new(ptr(Type::Char))],
+            Box::new(Type::Int),
+            Prototype::Variadic,
+        );
+        assert_eq!(
+            printf.declaration("printf", &name),
+            "int printf(char *, ...)"
+        );
+        assert_eq!(
+            Type::Array(Box::new(Type::Int), None).declaration("a", &name),
+            "int a[]"
+        );
+    }
 }